@@ -0,0 +1,41 @@
+use std::{env, error::Error, path::Path, process::Command, str};
+
+const LLVM_MAJOR_VERSION: usize = 15;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let version = llvm_config("--version")?;
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!(
+        "cargo:rerun-if-env-changed=MLIR_SYS_{}0_PREFIX",
+        LLVM_MAJOR_VERSION
+    );
+    println!("cargo:rustc-env=MLIR_VERSION={}", version.trim());
+    println!(
+        "cargo:rustc-env=LLVM_LIBRARY_DIRECTORY={}",
+        llvm_config("--libdir")?.trim()
+    );
+
+    Ok(())
+}
+
+fn llvm_config(argument: &str) -> Result<String, Box<dyn Error>> {
+    let prefix = env::var(format!("MLIR_SYS_{}0_PREFIX", LLVM_MAJOR_VERSION))
+        .map(|path| Path::new(&path).join("bin"))
+        .unwrap_or_default();
+    let output = Command::new(prefix.join("llvm-config"))
+        .arg(argument)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "llvm-config {} failed with {}: {}",
+            argument,
+            output.status,
+            str::from_utf8(&output.stderr)?.trim()
+        )
+        .into());
+    }
+
+    Ok(str::from_utf8(&output.stdout)?.into())
+}
@@ -1,15 +1,28 @@
 use crate::{
+    diagnostic::{self, Diagnostic},
     dialect::{self, Dialect},
+    ir::operation,
+    logical_result::LogicalResult,
     string_ref::StringRef,
+    utility::register_all_dialects,
+    Error,
 };
 use mlir_sys::{
-    mlirContextAppendDialectRegistry, mlirContextCreate, mlirContextDestroy,
-    mlirContextEnableMultithreading, mlirContextEqual, mlirContextGetAllowUnregisteredDialects,
-    mlirContextGetNumLoadedDialects, mlirContextGetNumRegisteredDialects,
-    mlirContextGetOrLoadDialect, mlirContextIsRegisteredOperation,
-    mlirContextLoadAllAvailableDialects, mlirContextSetAllowUnregisteredDialects, MlirContext,
+    mlirContextAppendDialectRegistry, mlirContextAttachDiagnosticHandler, mlirContextCreate,
+    mlirContextDestroy, mlirContextDetachDiagnosticHandler, mlirContextEnableMultithreading,
+    mlirContextEqual, mlirContextGetAllowUnregisteredDialects, mlirContextGetNumLoadedDialects,
+    mlirContextGetNumRegisteredDialects, mlirContextGetOrLoadDialect,
+    mlirContextIsRegisteredOperation, mlirContextLoadAllAvailableDialects,
+    mlirContextSetAllowUnregisteredDialects, MlirContext, MlirDiagnostic, MlirLogicalResult,
 };
-use std::{marker::PhantomData, ops::Deref};
+use std::{
+    cell::RefCell, collections::HashMap, ffi::c_void, marker::PhantomData, mem::ManuallyDrop,
+    ops::Deref,
+};
+
+thread_local! {
+    static MODULE_COUNTS: RefCell<HashMap<usize, usize>> = Default::default();
+}
 
 /// A context of IR, dialects, and passes.
 ///
@@ -22,15 +35,45 @@ pub struct Context {
 
 impl Context {
     /// Creates a context.
+    ///
+    /// All dialects are registered and loaded, and unregistered dialects are
+    /// allowed.
     pub fn new() -> Self {
-        Self {
-            r#ref: unsafe { ContextRef::from_raw(mlirContextCreate()) },
-        }
+        let context = unsafe { Self::new_unchecked() };
+        let registry = dialect::Registry::new();
+
+        register_all_dialects(&registry);
+        context.append_dialect_registry(&registry);
+        context.load_all_available_dialects();
+        context.set_allow_unregistered_dialects(true);
+
+        context
+    }
+
+    /// Creates a context without any dialects loaded except the `builtin`
+    /// dialect.
+    ///
+    /// # Safety
+    ///
+    /// Access to operations, types, or attributes that belong to dialects not
+    /// loaded in the context can lead to runtime errors or segmentation faults.
+    pub unsafe fn new_unchecked() -> Self {
+        let context = Self {
+            r#ref: ContextRef::from_raw(mlirContextCreate()),
+        };
+
+        // WebAssembly runtimes do not support threads in general.
+        #[cfg(target_family = "wasm")]
+        context.enable_multi_threading(false);
+
+        context
     }
 }
 
 impl Drop for Context {
     fn drop(&mut self) {
+        let _ = MODULE_COUNTS.try_with(|counts| counts.borrow_mut().remove(&self.key()));
+
         unsafe { mlirContextDestroy(self.raw) };
     }
 }
@@ -67,6 +110,15 @@ impl<'a> ContextRef<'a> {
         unsafe { mlirContextGetNumLoadedDialects(self.raw) as usize }
     }
 
+    /// Gets a number of live modules in a context.
+    ///
+    /// This helps to decide when to recycle contexts because their memory is
+    /// never freed until they are destroyed.
+    // TODO Count uniqued types and attributes.
+    pub fn module_count(&self) -> usize {
+        MODULE_COUNTS.with(|counts| counts.borrow().get(&self.key()).copied().unwrap_or(0))
+    }
+
     /// Gets or loads a dialect.
     pub fn get_or_load_dialect(&self, name: &str) -> Dialect {
         unsafe {
@@ -107,10 +159,107 @@ impl<'a> ContextRef<'a> {
         unsafe { mlirContextIsRegisteredOperation(self.raw, StringRef::from(name).to_raw()) }
     }
 
+    /// Checks if an operation of a given name can be created in a context.
+    ///
+    /// A dialect of the operation is loaded if it is registered but not loaded
+    /// yet.
+    pub fn check_operation_name(&self, name: &str) -> Result<(), Error> {
+        if self.is_registered_operation(name) || self.allow_unregistered_dialects() {
+            return Ok(());
+        }
+
+        let (namespace, _) = operation::split_name(name)
+            .ok_or_else(|| Error::OperationNotRegistered(name.into()))?;
+
+        if unsafe {
+            mlirContextGetOrLoadDialect(self.raw, StringRef::from(namespace).to_raw())
+                .ptr
+                .is_null()
+        } {
+            Err(Error::DialectNotLoaded(namespace.into()))
+        } else if self.is_registered_operation(name) {
+            Ok(())
+        } else {
+            Err(Error::OperationNotRegistered(name.into()))
+        }
+    }
+
+    /// Attaches a diagnostic handler.
+    ///
+    /// A handler returns `true` if it handles a diagnostic. Otherwise, the
+    /// diagnostic is passed to handlers attached earlier.
+    ///
+    /// Handlers are called one at a time but possibly on worker threads of
+    /// MLIR, e.g. during multi-threaded verification and pass runs.
+    pub fn attach_diagnostic_handler(
+        &self,
+        handler: impl FnMut(Diagnostic<'a>) -> bool + Send + 'static,
+    ) -> diagnostic::HandlerId {
+        type Handler<'a> = Box<dyn FnMut(Diagnostic<'a>) -> bool + Send>;
+
+        unsafe extern "C" fn handle(
+            diagnostic: MlirDiagnostic,
+            handler: *mut c_void,
+        ) -> MlirLogicalResult {
+            if (*(handler as *mut Handler))(Diagnostic::from_raw(diagnostic)) {
+                LogicalResult::success()
+            } else {
+                LogicalResult::failure()
+            }
+            .to_raw()
+        }
+
+        unsafe extern "C" fn destroy(handler: *mut c_void) {
+            drop(Box::from_raw(handler as *mut Handler));
+        }
+
+        unsafe {
+            diagnostic::HandlerId::from_raw(mlirContextAttachDiagnosticHandler(
+                self.raw,
+                Some(handle),
+                Box::into_raw(Box::new(Box::new(handler) as Handler<'a>)) as *mut c_void,
+                Some(destroy),
+            ))
+        }
+    }
+
+    /// Detaches a diagnostic handler.
+    pub fn detach_diagnostic_handler(&self, id: diagnostic::HandlerId) {
+        unsafe { mlirContextDetachDiagnosticHandler(self.raw, id.to_raw()) }
+    }
+
     pub(crate) unsafe fn to_raw(self) -> MlirContext {
         self.raw
     }
 
+    // Lends a context borrowed by a context reference. Objects created with the
+    // context cannot escape a callback.
+    pub(crate) fn with_context<T>(&self, callback: impl FnOnce(&Context) -> T) -> T {
+        let context = ManuallyDrop::new(Context {
+            r#ref: unsafe { ContextRef::from_raw(self.raw) },
+        });
+
+        callback(&context)
+    }
+
+    pub(crate) fn increment_module_count(&self) {
+        MODULE_COUNTS.with(|counts| *counts.borrow_mut().entry(self.key()).or_default() += 1);
+    }
+
+    pub(crate) fn decrement_module_count(&self) {
+        // Thread-local storage might be already destroyed when modules are
+        // dropped at thread exits.
+        let _ = MODULE_COUNTS.try_with(|counts| {
+            if let Some(count) = counts.borrow_mut().get_mut(&self.key()) {
+                *count -= 1;
+            }
+        });
+    }
+
+    fn key(&self) -> usize {
+        self.raw.ptr as usize
+    }
+
     pub(crate) unsafe fn from_raw(raw: MlirContext) -> Self {
         Self {
             raw,
@@ -130,6 +279,11 @@ impl<'a> Eq for ContextRef<'a> {}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ir::{Location, Module};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
 
     #[test]
     fn new() {
@@ -137,17 +291,65 @@ mod tests {
     }
 
     #[test]
-    fn registered_dialect_count() {
+    fn new_with_all_dialects() {
         let context = Context::new();
 
+        assert!(context.registered_dialect_count() > 1);
+        assert_eq!(
+            context.loaded_dialect_count(),
+            context.registered_dialect_count()
+        );
+        assert!(context.is_registered_operation("func.func"));
+        assert!(context.allow_unregistered_dialects());
+    }
+
+    #[test]
+    fn new_unchecked() {
+        unsafe { Context::new_unchecked() };
+    }
+
+    #[test]
+    fn registered_dialect_count() {
+        let context = unsafe { Context::new_unchecked() };
+
         assert_eq!(context.registered_dialect_count(), 1);
     }
 
     #[test]
     fn loaded_dialect_count() {
-        let context = Context::new();
+        let context = unsafe { Context::new_unchecked() };
+
+        assert_eq!(context.loaded_dialect_count(), 1);
+    }
+
+    #[test]
+    fn get_or_load_dialect() {
+        let context = unsafe { Context::new_unchecked() };
+
+        dialect::Handle::llvm().register_dialect(&context);
+
+        assert_eq!(
+            context.get_or_load_dialect("llvm"),
+            dialect::Handle::llvm().load_dialect(&context)
+        );
+        assert_eq!(context.loaded_dialect_count(), 2);
+    }
 
+    #[test]
+    fn load_all_available_dialects() {
+        let registry = dialect::Registry::new();
+        dialect::Handle::llvm().insert_dialect(&registry);
+
+        let context = unsafe { Context::new_unchecked() };
+        context.append_dialect_registry(&registry);
+
+        assert_eq!(context.registered_dialect_count(), 2);
         assert_eq!(context.loaded_dialect_count(), 1);
+
+        context.load_all_available_dialects();
+
+        assert_eq!(context.loaded_dialect_count(), 2);
+        assert!(context.is_registered_operation("llvm.func"));
     }
 
     #[test]
@@ -166,11 +368,63 @@ mod tests {
 
     #[test]
     fn is_not_registered_operation() {
-        let context = Context::new();
+        let context = unsafe { Context::new_unchecked() };
 
         assert!(!context.is_registered_operation("func.func"));
     }
 
+    #[test]
+    fn check_registered_operation_name() {
+        let context = Context::new();
+
+        assert_eq!(context.check_operation_name("func.func"), Ok(()));
+    }
+
+    #[test]
+    fn check_operation_name_of_unloaded_dialect() {
+        let context = unsafe { Context::new_unchecked() };
+
+        assert_eq!(
+            context.check_operation_name("scf.for"),
+            Err(Error::DialectNotLoaded("scf".into()))
+        );
+    }
+
+    #[test]
+    fn check_operation_name_of_registered_dialect() {
+        let context = unsafe { Context::new_unchecked() };
+        let registry = dialect::Registry::new();
+
+        register_all_dialects(&registry);
+        context.append_dialect_registry(&registry);
+
+        assert_eq!(context.check_operation_name("scf.for"), Ok(()));
+        assert!(context.is_registered_operation("scf.for"));
+    }
+
+    #[test]
+    fn check_unregistered_operation_name() {
+        let context = unsafe { Context::new_unchecked() };
+
+        assert_eq!(
+            context.check_operation_name("builtin.foo"),
+            Err(Error::OperationNotRegistered("builtin.foo".into()))
+        );
+        assert_eq!(
+            context.check_operation_name("foo"),
+            Err(Error::OperationNotRegistered("foo".into()))
+        );
+    }
+
+    #[test]
+    fn check_operation_name_with_unregistered_dialects() {
+        let context = unsafe { Context::new_unchecked() };
+
+        context.set_allow_unregistered_dialects(true);
+
+        assert_eq!(context.check_operation_name("scf.for"), Ok(()));
+    }
+
     #[test]
     fn enable_multi_threading() {
         let context = Context::new();
@@ -186,15 +440,104 @@ mod tests {
     }
 
     #[test]
-    fn allow_unregistered_dialects() {
+    fn module_count() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+
+        assert_eq!(context.module_count(), 0);
+
+        let module = Module::new(location);
+        let other_module = Module::parse(&context, "module {}").unwrap();
+
+        assert_eq!(context.module_count(), 2);
+
+        drop(module);
+
+        assert_eq!(context.module_count(), 1);
+
+        drop(other_module);
+
+        assert_eq!(context.module_count(), 0);
+    }
+
+    #[test]
+    fn module_count_per_context() {
+        let context = Context::new();
+        let other_context = Context::new();
+        let _module = Module::new(Location::unknown(&context));
+
+        assert_eq!(context.module_count(), 1);
+        assert_eq!(other_context.module_count(), 0);
+    }
+
+    #[test]
+    fn attach_diagnostic_handler() {
+        let context = Context::new();
+        let count = Arc::new(AtomicUsize::new(0));
+
+        context.attach_diagnostic_handler({
+            let count = count.clone();
+
+            move |diagnostic| {
+                assert_eq!(diagnostic.message(), "foo");
+                count.fetch_add(1, Ordering::SeqCst);
+                true
+            }
+        });
+        Location::unknown(&context).emit_error("foo");
+
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn detach_diagnostic_handler() {
+        let context = Context::new();
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let id = context.attach_diagnostic_handler({
+            let count = count.clone();
+
+            move |_| {
+                count.fetch_add(1, Ordering::SeqCst);
+                true
+            }
+        });
+        context.detach_diagnostic_handler(id);
+        context.attach_diagnostic_handler(|_| true);
+        Location::unknown(&context).emit_error("foo");
+
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn fall_back_to_earlier_diagnostic_handler() {
         let context = Context::new();
+        let count = Arc::new(AtomicUsize::new(0));
+
+        context.attach_diagnostic_handler({
+            let count = count.clone();
+
+            move |_| {
+                count.fetch_add(1, Ordering::SeqCst);
+                true
+            }
+        });
+        context.attach_diagnostic_handler(|_| false);
+        Location::unknown(&context).emit_error("foo");
+
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn allow_unregistered_dialects() {
+        let context = unsafe { Context::new_unchecked() };
 
         assert!(!context.allow_unregistered_dialects());
     }
 
     #[test]
     fn set_allow_unregistered_dialects() {
-        let context = Context::new();
+        let context = unsafe { Context::new_unchecked() };
 
         context.set_allow_unregistered_dialects(true);
 
@@ -0,0 +1,366 @@
+//! Diagnostics.
+
+use crate::{
+    ir::Location,
+    utility::{string_literal_length, unescape_string},
+};
+use mlir_sys::{
+    mlirDiagnosticGetLocation, mlirDiagnosticGetNote, mlirDiagnosticGetNumNotes,
+    mlirDiagnosticGetSeverity, mlirDiagnosticPrint, MlirDiagnostic, MlirDiagnosticHandlerID,
+    MlirDiagnosticSeverity_MlirDiagnosticError, MlirDiagnosticSeverity_MlirDiagnosticNote,
+    MlirDiagnosticSeverity_MlirDiagnosticRemark, MlirDiagnosticSeverity_MlirDiagnosticWarning,
+    MlirStringRef,
+};
+use std::{
+    ffi::c_void,
+    fmt::{self, Display, Formatter},
+    slice,
+};
+
+/// A diagnostic severity.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+    Error,
+    Note,
+    Remark,
+    Warning,
+}
+
+/// A diagnostic.
+///
+/// Diagnostics are copied out of MLIR when they are reported so that they can
+/// be kept after diagnostic handlers return.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic<'c> {
+    severity: Severity,
+    location: Location<'c>,
+    message: String,
+    notes: Vec<Diagnostic<'c>>,
+}
+
+impl<'c> Diagnostic<'c> {
+    /// Gets a severity.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Gets a location.
+    pub fn location(&self) -> Location<'c> {
+        self.location
+    }
+
+    /// Gets a message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Gets notes.
+    pub fn notes(&self) -> &[Diagnostic<'c>] {
+        &self.notes
+    }
+
+    /// Gets a source position if a location is a file location.
+    pub fn position(&self) -> Option<SourcePosition> {
+        self.location.position()
+    }
+
+    #[allow(non_upper_case_globals)]
+    pub(crate) unsafe fn from_raw(raw: MlirDiagnostic) -> Self {
+        let mut message = String::new();
+
+        mlirDiagnosticPrint(
+            raw,
+            Some(message_callback),
+            &mut message as *mut _ as *mut c_void,
+        );
+
+        Self {
+            severity: match mlirDiagnosticGetSeverity(raw) {
+                MlirDiagnosticSeverity_MlirDiagnosticError => Severity::Error,
+                MlirDiagnosticSeverity_MlirDiagnosticNote => Severity::Note,
+                MlirDiagnosticSeverity_MlirDiagnosticRemark => Severity::Remark,
+                MlirDiagnosticSeverity_MlirDiagnosticWarning => Severity::Warning,
+                // Diagnostic handlers cannot panic across the FFI boundary.
+                _ => Severity::Error,
+            },
+            location: Location::from_raw(mlirDiagnosticGetLocation(raw)),
+            message,
+            notes: (0..mlirDiagnosticGetNumNotes(raw))
+                .map(|index| Self::from_raw(mlirDiagnosticGetNote(raw, index)))
+                .collect(),
+        }
+    }
+}
+
+impl<'c> Display for Diagnostic<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.message)
+    }
+}
+
+/// A captured diagnostic.
+///
+/// Unlike [`Diagnostic`], it does not borrow a context and its location is
+/// printed, so that it can be attached to [`Error`](crate::Error)s.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CapturedDiagnostic {
+    severity: Severity,
+    location: String,
+    message: String,
+    notes: Vec<CapturedDiagnostic>,
+}
+
+impl CapturedDiagnostic {
+    /// Gets a severity.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Gets a printed location.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Gets a message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Gets notes.
+    pub fn notes(&self) -> &[CapturedDiagnostic] {
+        &self.notes
+    }
+
+    /// Gets a source position if a location is a file location.
+    pub fn position(&self) -> Option<SourcePosition> {
+        SourcePosition::parse(&self.location)
+    }
+
+    pub(crate) fn map_locations(self, map: &impl Fn(&str) -> String) -> Self {
+        Self {
+            severity: self.severity,
+            location: map(&self.location),
+            message: self.message,
+            notes: self
+                .notes
+                .into_iter()
+                .map(|note| note.map_locations(map))
+                .collect(),
+        }
+    }
+}
+
+impl<'c> From<&Diagnostic<'c>> for CapturedDiagnostic {
+    fn from(diagnostic: &Diagnostic<'c>) -> Self {
+        Self {
+            severity: diagnostic.severity,
+            location: diagnostic.location.to_string(),
+            message: diagnostic.message.clone(),
+            notes: diagnostic.notes.iter().map(Self::from).collect(),
+        }
+    }
+}
+
+impl Display for CapturedDiagnostic {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self.position() {
+            Some(position) => write!(formatter, "{}: {}", position, self.message),
+            None => write!(formatter, "{}", self.message),
+        }
+    }
+}
+
+/// A source position of a diagnostic.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourcePosition {
+    filename: String,
+    line: usize,
+    column: usize,
+}
+
+impl SourcePosition {
+    /// Creates a source position.
+    pub fn new(filename: &str, line: usize, column: usize) -> Self {
+        Self {
+            filename: filename.into(),
+            line,
+            column,
+        }
+    }
+
+    /// Gets a filename.
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Gets a line number.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Gets a column number.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    // Parses a printed location in the form of `loc("filename":line:column)`.
+    pub(crate) fn parse(location: &str) -> Option<Self> {
+        let location = location.strip_prefix("loc(\"")?.strip_suffix(')')?;
+        let mut components = location.rsplitn(3, ':');
+        let column = components.next()?.parse().ok()?;
+        let line = components.next()?.parse().ok()?;
+        let filename = unescape_string(components.next()?.strip_suffix('"')?)?;
+
+        Some(Self::new(&filename, line, column))
+    }
+}
+
+impl Display for SourcePosition {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}:{}:{}", self.filename, self.line, self.column)
+    }
+}
+
+/// A source range of a diagnostic.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceRange {
+    start: SourcePosition,
+    end_line: usize,
+    end_column: usize,
+}
+
+impl SourceRange {
+    /// Creates a source range.
+    ///
+    /// The end position is exclusive and in the same file as the start
+    /// position.
+    pub fn new(start: SourcePosition, end: SourcePosition) -> Self {
+        Self {
+            start,
+            end_line: end.line,
+            end_column: end.column,
+        }
+    }
+
+    /// Gets a start position.
+    pub fn start(&self) -> &SourcePosition {
+        &self.start
+    }
+
+    /// Gets an end position.
+    pub fn end(&self) -> SourcePosition {
+        SourcePosition::new(&self.start.filename, self.end_line, self.end_column)
+    }
+
+    // MLIR reports only positions at which tokens start, so we find their ends in
+    // sources.
+    pub(crate) fn token(source: &str, start: SourcePosition) -> Self {
+        let rest = source
+            .lines()
+            .nth(start.line.saturating_sub(1))
+            .and_then(|line| line.get(start.column.saturating_sub(1)..))
+            .unwrap_or_default();
+        let is_token =
+            |character: char| character.is_alphanumeric() || "_$.-@%#^!".contains(character);
+
+        let length = match rest.chars().next() {
+            Some('"') => string_literal_length(rest),
+            Some(character) if is_token(character) => rest
+                .find(|character| !is_token(character))
+                .unwrap_or(rest.len()),
+            Some(character) => character.len_utf8(),
+            None => 0,
+        };
+
+        Self {
+            end_line: start.line,
+            end_column: start.column + length,
+            start,
+        }
+    }
+
+    pub(crate) fn with_line_offset(self, offset: usize) -> Self {
+        Self {
+            start: SourcePosition::new(
+                &self.start.filename,
+                self.start.line.saturating_sub(offset),
+                self.start.column,
+            ),
+            end_line: self.end_line.saturating_sub(offset),
+            end_column: self.end_column,
+        }
+    }
+}
+
+impl Display for SourceRange {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.start)
+    }
+}
+
+/// A diagnostic handler ID.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HandlerId {
+    raw: MlirDiagnosticHandlerID,
+}
+
+impl HandlerId {
+    pub(crate) unsafe fn from_raw(raw: MlirDiagnosticHandlerID) -> Self {
+        Self { raw }
+    }
+
+    pub(crate) unsafe fn to_raw(self) -> MlirDiagnosticHandlerID {
+        self.raw
+    }
+}
+
+unsafe extern "C" fn message_callback(string: MlirStringRef, data: *mut c_void) {
+    let message = &mut *(data as *mut String);
+
+    message.push_str(&String::from_utf8_lossy(slice::from_raw_parts(
+        string.data as *const u8,
+        string.length as usize,
+    )));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_position() {
+        assert_eq!(
+            SourcePosition::parse("loc(\"foo.mlir\":1:2)"),
+            Some(SourcePosition::new("foo.mlir", 1, 2))
+        );
+    }
+
+    #[test]
+    fn parse_position_with_colon() {
+        assert_eq!(
+            SourcePosition::parse("loc(\"foo:bar\":3:4)"),
+            Some(SourcePosition::new("foo:bar", 3, 4))
+        );
+    }
+
+    #[test]
+    fn parse_position_with_escaped_filename() {
+        assert_eq!(
+            SourcePosition::parse("loc(\"foo\\22bar\\\\baz\":5:6)"),
+            Some(SourcePosition::new("foo\"bar\\baz", 5, 6))
+        );
+    }
+
+    #[test]
+    fn parse_position_none() {
+        assert_eq!(SourcePosition::parse("loc(unknown)"), None);
+    }
+
+    #[test]
+    fn display_position() {
+        assert_eq!(
+            SourcePosition::new("foo.mlir", 1, 2).to_string(),
+            "foo.mlir:1:2"
+        );
+    }
+}
@@ -1,8 +1,17 @@
 //! Dialect handles, instances, and registry.
 
+pub mod arith;
+pub mod cf;
+pub mod func;
+pub mod gpu;
 mod handle;
+pub mod linalg;
 pub mod llvm;
+pub mod memref;
 mod registry;
+pub mod scf;
+pub mod spirv;
+pub mod tensor;
 
 pub use self::{handle::Handle, registry::Registry};
 use crate::{
@@ -0,0 +1,317 @@
+//! The `arith` dialect
+
+use crate::{
+    ir::{operation, BlockRef, Identifier, IntegerAttribute, Location, Operation, Type, Value},
+    utility::append_operation_result,
+    Context,
+};
+
+/// Creates an `arith.constant` operation of the `index` type.
+pub fn constant_index<'c>(
+    context: &'c Context,
+    value: i64,
+    location: Location<'c>,
+) -> Operation<'c> {
+    operation::Builder::new("arith.constant", location)
+        .add_attributes(&[(
+            Identifier::new(context, "value"),
+            IntegerAttribute::index(context, value).into(),
+        )])
+        .add_results(&[Type::index(context)])
+        .build()
+        // Operations with explicit result types are always built.
+        .unwrap()
+}
+
+/// An index expression folded at build time.
+///
+/// Arithmetic of constant expressions is folded into constants and operations
+/// are appended to a block only for dynamic ones.
+#[derive(Clone, Copy, Debug)]
+pub enum IndexExpr<'a> {
+    /// A constant.
+    Constant(i64),
+    /// A dynamic value of the `index` type.
+    Value(Value<'a>),
+}
+
+impl<'a> IndexExpr<'a> {
+    /// Adds an expression.
+    pub fn add(
+        self,
+        other: Self,
+        context: &'a Context,
+        block: &BlockRef<'a>,
+        location: Location<'a>,
+    ) -> Self {
+        match (self, other) {
+            (Self::Constant(0), other) | (other, Self::Constant(0)) => other,
+            _ => self.fold(
+                other,
+                i64::checked_add,
+                "arith.addi",
+                context,
+                block,
+                location,
+            ),
+        }
+    }
+
+    /// Subtracts an expression.
+    pub fn subtract(
+        self,
+        other: Self,
+        context: &'a Context,
+        block: &BlockRef<'a>,
+        location: Location<'a>,
+    ) -> Self {
+        match other {
+            Self::Constant(0) => self,
+            _ => self.fold(
+                other,
+                i64::checked_sub,
+                "arith.subi",
+                context,
+                block,
+                location,
+            ),
+        }
+    }
+
+    /// Multiplies an expression.
+    pub fn multiply(
+        self,
+        other: Self,
+        context: &'a Context,
+        block: &BlockRef<'a>,
+        location: Location<'a>,
+    ) -> Self {
+        match (self, other) {
+            (Self::Constant(0), _) | (_, Self::Constant(0)) => Self::Constant(0),
+            (Self::Constant(1), other) | (other, Self::Constant(1)) => other,
+            _ => self.fold(
+                other,
+                i64::checked_mul,
+                "arith.muli",
+                context,
+                block,
+                location,
+            ),
+        }
+    }
+
+    /// Divides an expression with truncation towards zero.
+    pub fn divide(
+        self,
+        other: Self,
+        context: &'a Context,
+        block: &BlockRef<'a>,
+        location: Location<'a>,
+    ) -> Self {
+        match other {
+            Self::Constant(1) => self,
+            _ => self.fold(
+                other,
+                i64::checked_div,
+                "arith.divsi",
+                context,
+                block,
+                location,
+            ),
+        }
+    }
+
+    /// Computes a remainder of a division with truncation towards zero.
+    pub fn remainder(
+        self,
+        other: Self,
+        context: &'a Context,
+        block: &BlockRef<'a>,
+        location: Location<'a>,
+    ) -> Self {
+        match other {
+            Self::Constant(1) => Self::Constant(0),
+            _ => self.fold(
+                other,
+                i64::checked_rem,
+                "arith.remsi",
+                context,
+                block,
+                location,
+            ),
+        }
+    }
+
+    /// Converts an expression into a value.
+    ///
+    /// An `arith.constant` operation is appended to a block if the expression
+    /// is constant.
+    pub fn into_value(
+        self,
+        context: &'a Context,
+        block: &BlockRef<'a>,
+        location: Location<'a>,
+    ) -> Value<'a> {
+        match self {
+            Self::Constant(value) => block
+                .append_operation(constant_index(context, value, location))
+                .result(0)
+                // Constants always have results.
+                .unwrap()
+                .into(),
+            Self::Value(value) => value,
+        }
+    }
+
+    fn fold(
+        self,
+        other: Self,
+        fold: fn(i64, i64) -> Option<i64>,
+        name: &str,
+        context: &'a Context,
+        block: &BlockRef<'a>,
+        location: Location<'a>,
+    ) -> Self {
+        if let (Self::Constant(one), Self::Constant(other)) = (self, other) {
+            if let Some(value) = fold(one, other) {
+                return Self::Constant(value);
+            }
+        }
+
+        let lhs = self.into_value(context, block, location);
+        let rhs = other.into_value(context, block, location);
+
+        Self::Value(append_operation_result(
+            block,
+            operation::Builder::new(name, location)
+                .add_operands(&[lhs, rhs])
+                .add_results(&[Type::index(context)]),
+        ))
+    }
+}
+
+impl<'a> From<i64> for IndexExpr<'a> {
+    fn from(value: i64) -> Self {
+        Self::Constant(value)
+    }
+}
+
+impl<'a> From<Value<'a>> for IndexExpr<'a> {
+    fn from(value: Value<'a>) -> Self {
+        Self::Value(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ir::Block, Context};
+
+    #[test]
+    fn constant_index() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+
+        block.append_operation(super::constant_index(&context, 42, location));
+
+        assert_eq!(
+            block.first_operation().unwrap().to_string(),
+            "%c42 = arith.constant 42 : index\n"
+        );
+    }
+
+    #[test]
+    fn fold_constants() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+
+        let expression = IndexExpr::from(2)
+            .multiply(3.into(), &context, &block, location)
+            .add(4.into(), &context, &block, location)
+            .subtract(1.into(), &context, &block, location)
+            .divide(2.into(), &context, &block, location)
+            .remainder(3.into(), &context, &block, location);
+
+        assert!(matches!(expression, IndexExpr::Constant(1)));
+        assert_eq!(block.operation_count(), 0);
+
+        expression.into_value(&context, &block, location);
+
+        assert_eq!(
+            block.first_operation().unwrap().to_string(),
+            "%c1 = arith.constant 1 : index\n"
+        );
+    }
+
+    #[test]
+    fn fold_identities() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+        let value = IndexExpr::from(Value::from(block.argument(0).unwrap()));
+
+        for expression in [
+            value.add(0.into(), &context, &block, location),
+            IndexExpr::from(0).add(value, &context, &block, location),
+            value.subtract(0.into(), &context, &block, location),
+            value.multiply(1.into(), &context, &block, location),
+            IndexExpr::from(1).multiply(value, &context, &block, location),
+            value.divide(1.into(), &context, &block, location),
+        ] {
+            assert!(matches!(expression, IndexExpr::Value(_)));
+        }
+
+        assert!(matches!(
+            value.multiply(0.into(), &context, &block, location),
+            IndexExpr::Constant(0)
+        ));
+        assert!(matches!(
+            value.remainder(1.into(), &context, &block, location),
+            IndexExpr::Constant(0)
+        ));
+        assert_eq!(block.operation_count(), 0);
+    }
+
+    #[test]
+    fn build_dynamic_expressions() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+
+        IndexExpr::from(Value::from(block.argument(0).unwrap()))
+            .multiply(4.into(), &context, &block, location)
+            .add(2.into(), &context, &block, location);
+
+        assert_eq!(
+            block
+                .operations()
+                .map(|operation| operation
+                    .name()
+                    .as_string_ref()
+                    .as_str()
+                    .unwrap()
+                    .to_owned())
+                .collect::<Vec<_>>(),
+            [
+                "arith.constant",
+                "arith.muli",
+                "arith.constant",
+                "arith.addi"
+            ]
+        );
+    }
+
+    #[test]
+    fn keep_division_by_zero() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+
+        assert!(matches!(
+            IndexExpr::from(1).divide(0.into(), &context, &block, location),
+            IndexExpr::Value(_)
+        ));
+    }
+}
@@ -0,0 +1,251 @@
+//! The `cf` dialect
+
+use crate::{
+    ir::{operation, Attribute, BlockRef, Identifier, Location, Operation, Value, ValueLike},
+    Context, Error,
+};
+
+/// Creates a `cf.br` operation.
+pub fn br<'c>(
+    destination: BlockRef,
+    operands: &[Value],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    check_successor_operands(destination, operands)?;
+
+    operation::Builder::new("cf.br", location)
+        .add_operands(operands)
+        .add_successor(destination)
+        .build()
+}
+
+/// Creates a `cf.cond_br` operation.
+///
+/// Destinations are paired with operands passed to them.
+pub fn cond_br<'c>(
+    condition: Value,
+    (true_destination, true_operands): (BlockRef, &[Value]),
+    (false_destination, false_operands): (BlockRef, &[Value]),
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    check_successor_operands(true_destination, true_operands)?;
+    check_successor_operands(false_destination, false_operands)?;
+
+    operation::Builder::new("cf.cond_br", location)
+        .add_operand_segments(&[&[condition], true_operands, false_operands])
+        .add_successors(&[true_destination, false_destination])
+        .build()
+}
+
+/// Creates a `cf.switch` operation.
+///
+/// A default destination and cases of values and destinations are paired with
+/// operands passed to the destinations.
+pub fn switch<'c>(
+    context: &'c Context,
+    flag: Value,
+    (default_destination, default_operands): (BlockRef, &[Value]),
+    cases: &[(i64, BlockRef, &[Value])],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    check_successor_operands(default_destination, default_operands)?;
+
+    for &(_, destination, operands) in cases {
+        check_successor_operands(destination, operands)?;
+    }
+
+    let case_operands = cases
+        .iter()
+        .flat_map(|(_, _, operands)| operands.iter().copied())
+        .collect::<Vec<_>>();
+    let mut attributes = vec![];
+
+    if !cases.is_empty() {
+        attributes.push((
+            Identifier::new(context, "case_values"),
+            Attribute::parse(
+                context,
+                &format!(
+                    "dense<[{}]> : vector<{}x{}>",
+                    cases
+                        .iter()
+                        .map(|(value, _, _)| value.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    cases.len(),
+                    flag.r#type()
+                ),
+            )
+            .ok_or_else(|| Error::BuildOperation("cf.switch".into()))?,
+        ));
+    }
+
+    operation::Builder::new("cf.switch", location)
+        .add_operand_segments(&[&[flag], default_operands, &case_operands])
+        .add_segment_sizes(
+            "case_operand_segments",
+            &cases
+                .iter()
+                .map(|(_, _, operands)| operands.len() as i32)
+                .collect::<Vec<_>>(),
+        )
+        .add_successor(default_destination)
+        .add_successors(
+            &cases
+                .iter()
+                .map(|(_, destination, _)| *destination)
+                .collect::<Vec<_>>(),
+        )
+        .add_attributes(&attributes)
+        .build()
+}
+
+fn check_successor_operands(destination: BlockRef, operands: &[Value]) -> Result<(), Error> {
+    if operands.len() == destination.argument_count() {
+        Ok(())
+    } else {
+        Err(Error::SuccessorOperandCount(
+            destination.argument_count(),
+            operands.len(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dialect::func,
+        ir::{Block, Module, Region, Type},
+    };
+
+    // Builds a function of an entry block and a return block with an argument.
+    fn build_function<'c>(
+        context: &'c Context,
+        flag_type: Type<'c>,
+        build_terminator: impl for<'a> FnOnce(Value<'a>, BlockRef<'a>, Value<'a>) -> Operation<'c>,
+    ) {
+        let location = Location::unknown(context);
+        let index_type = Type::index(context);
+        let module = Module::new(location);
+
+        let entry_block = Block::new(&[(flag_type, location), (index_type, location)]);
+        let return_block = Block::new(&[(index_type, location)]);
+
+        return_block.append_operation(
+            func::r#return(&[return_block.argument(0).unwrap().into()], location).unwrap(),
+        );
+        entry_block.append_operation(build_terminator(
+            entry_block.argument(0).unwrap().into(),
+            *return_block,
+            entry_block.argument(1).unwrap().into(),
+        ));
+
+        let region = Region::new();
+        region.append_block(entry_block);
+        region.append_block(return_block);
+
+        module.append_operation(
+            operation::Builder::new("func.func", location)
+                .add_attributes(&[
+                    (
+                        Identifier::new(context, "function_type"),
+                        Attribute::parse(context, &format!("({}, index) -> index", flag_type))
+                            .unwrap(),
+                    ),
+                    (
+                        Identifier::new(context, "sym_name"),
+                        Attribute::parse(context, "\"foo\"").unwrap(),
+                    ),
+                ])
+                .add_region(region)
+                .build()
+                .unwrap(),
+        );
+
+        assert!(module.as_operation().verify());
+    }
+
+    #[test]
+    fn br() {
+        let context = Context::new();
+
+        build_function(
+            &context,
+            Type::integer(&context, 1),
+            |_, destination, value| {
+                super::br(destination, &[value], Location::unknown(&context)).unwrap()
+            },
+        );
+    }
+
+    #[test]
+    fn br_with_wrong_operand_count() {
+        let context = Context::new();
+        let block = Block::new(&[(Type::index(&context), Location::unknown(&context))]);
+
+        assert_eq!(
+            super::br(*block, &[], Location::unknown(&context)).unwrap_err(),
+            Error::SuccessorOperandCount(1, 0)
+        );
+    }
+
+    #[test]
+    fn cond_br() {
+        let context = Context::new();
+
+        build_function(
+            &context,
+            Type::integer(&context, 1),
+            |condition, destination, value| {
+                super::cond_br(
+                    condition,
+                    (destination, &[value]),
+                    (destination, &[value]),
+                    Location::unknown(&context),
+                )
+                .unwrap()
+            },
+        );
+    }
+
+    #[test]
+    fn switch() {
+        let context = Context::new();
+
+        build_function(
+            &context,
+            Type::integer(&context, 32),
+            |flag, destination, value| {
+                super::switch(
+                    &context,
+                    flag,
+                    (destination, &[value]),
+                    &[(1, destination, &[value]), (2, destination, &[value])],
+                    Location::unknown(&context),
+                )
+                .unwrap()
+            },
+        );
+    }
+
+    #[test]
+    fn switch_without_cases() {
+        let context = Context::new();
+
+        build_function(
+            &context,
+            Type::integer(&context, 32),
+            |flag, destination, value| {
+                super::switch(
+                    &context,
+                    flag,
+                    (destination, &[value]),
+                    &[],
+                    Location::unknown(&context),
+                )
+                .unwrap()
+            },
+        );
+    }
+}
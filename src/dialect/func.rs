@@ -0,0 +1,202 @@
+//! The `func` dialect
+
+use crate::{
+    ir::{
+        operation, r#type::Function, Attribute, Block, BlockRef, Identifier, Location, Operation,
+        Region, Type, Value,
+    },
+    utility::capture_diagnostics,
+    Context, Error,
+};
+
+/// A `func.func` operation builder.
+pub struct Builder<'c> {
+    context: &'c Context,
+    name: String,
+    inputs: Vec<Type<'c>>,
+    results: Vec<Type<'c>>,
+    location: Location<'c>,
+}
+
+impl<'c> Builder<'c> {
+    /// Creates a `func.func` operation builder.
+    pub fn new(
+        context: &'c Context,
+        name: &str,
+        inputs: &[Type<'c>],
+        results: &[Type<'c>],
+        location: Location<'c>,
+    ) -> Self {
+        Self {
+            context,
+            name: name.into(),
+            inputs: inputs.to_vec(),
+            results: results.to_vec(),
+            location,
+        }
+    }
+
+    /// Builds a `func.func` operation.
+    ///
+    /// A callback receives an entry block with arguments of input types and
+    /// should terminate it, e.g. with a `func.return` operation. A built
+    /// operation is verified and diagnostics reported by verifiers are
+    /// attached to an error.
+    pub fn build(
+        self,
+        build_body: impl FnOnce(BlockRef<'c>) -> Result<(), Error>,
+    ) -> Result<Operation<'c>, Error> {
+        let operation = build_function(
+            "func.func",
+            self.context,
+            &self.name,
+            &self.inputs,
+            &self.results,
+            &[],
+            self.location,
+            build_body,
+        )?;
+
+        let (verified, diagnostics) =
+            capture_diagnostics(operation.context(), || operation.verify());
+
+        if verified {
+            Ok(operation)
+        } else {
+            Err(Error::VerifyOperation(operation.to_string(), diagnostics))
+        }
+    }
+}
+
+// Builds a function-like operation with `function_type` and `sym_name`
+// attributes and a body region of a single entry block.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn build_function<'c>(
+    operation_name: &str,
+    context: &'c Context,
+    name: &str,
+    inputs: &[Type<'c>],
+    results: &[Type<'c>],
+    attributes: &[(Identifier<'c>, Attribute<'c>)],
+    location: Location<'c>,
+    build_body: impl FnOnce(BlockRef<'c>) -> Result<(), Error>,
+) -> Result<Operation<'c>, Error> {
+    let block = Block::new(
+        &inputs
+            .iter()
+            .map(|&r#type| (r#type, location))
+            .collect::<Vec<_>>(),
+    );
+
+    build_body(*block)?;
+
+    let region = Region::new();
+    region.append_block(block);
+
+    let function_type = Function::new(context, inputs, results);
+
+    operation::Builder::new(operation_name, location)
+        .add_attributes(&[
+            (
+                Identifier::new(context, "function_type"),
+                Attribute::from_type(function_type.into()),
+            ),
+            (
+                Identifier::new(context, "sym_name"),
+                Attribute::string(context, name),
+            ),
+        ])
+        .add_attributes(attributes)
+        .add_regions(vec![region])
+        .build()
+}
+
+/// Creates a `func.return` operation.
+pub fn r#return<'c>(operands: &[Value], location: Location<'c>) -> Result<Operation<'c>, Error> {
+    operation::Builder::new("func.return", location)
+        .add_operands(operands)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Module;
+    use indoc::indoc;
+
+    #[test]
+    fn build() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let integer_type = Type::integer(&context, 64);
+        let module = Module::new(location);
+
+        module.body().append_operation(
+            Builder::new(
+                &context,
+                "add",
+                &[integer_type, integer_type],
+                &[integer_type],
+                location,
+            )
+            .build(|block| {
+                let sum = block.append_operation(
+                    operation::Builder::new("arith.addi", location)
+                        .add_operands(&[
+                            block.argument(0).unwrap().into(),
+                            block.argument(1).unwrap().into(),
+                        ])
+                        .add_results(&[integer_type])
+                        .build()?,
+                );
+
+                block.append_operation(r#return(&[sum.result(0).unwrap().into()], location)?);
+
+                Ok(())
+            })
+            .unwrap(),
+        );
+
+        assert!(module.as_operation().verify());
+        assert_eq!(
+            module.as_operation().to_string(),
+            indoc!(
+                "
+                module {
+                  func.func @add(%arg0: i64, %arg1: i64) -> i64 {
+                    %0 = arith.addi %arg0, %arg1 : i64
+                    return %0 : i64
+                  }
+                }
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn build_without_terminator() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+
+        let error = Builder::new(&context, "foo", &[], &[], location)
+            .build(|_| Ok(()))
+            .unwrap_err();
+
+        assert!(matches!(error, Error::VerifyOperation(_, _)));
+        assert!(!error.diagnostics().is_empty());
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn build_with_body_error() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+
+        assert_eq!(
+            Builder::new(&context, "foo", &[], &[], location)
+                .build(|_| Err(Error::InvokeFunction))
+                .unwrap_err(),
+            Error::InvokeFunction
+        );
+    }
+}
@@ -0,0 +1,430 @@
+//! The `gpu` dialect
+
+use super::func::build_function;
+use crate::{
+    ir::{
+        operation, Attribute, Block, BlockRef, Identifier, Location, Operation, OperationRef,
+        Region, Type, Value,
+    },
+    string_ref::StringRef,
+    Context, Error,
+};
+use mlir_sys::{
+    mlirFlatSymbolRefAttrGet, mlirIntegerAttrGet, mlirIntegerTypeGet, mlirStringAttrGet,
+    mlirSymbolRefAttrGet, mlirUnitAttrGet,
+};
+
+/// A name of a unit attribute which marks `gpu.func` operations as kernels.
+pub const KERNEL_ATTRIBUTE: &str = "gpu.kernel";
+
+/// A name of a unit attribute which marks modules as containers of
+/// `gpu.module` operations.
+pub const CONTAINER_MODULE_ATTRIBUTE: &str = "gpu.container_module";
+
+/// A dimension of thread and block IDs and sizes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Dimension {
+    X,
+    Y,
+    Z,
+}
+
+impl Dimension {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::X => "x",
+            Self::Y => "y",
+            Self::Z => "z",
+        }
+    }
+}
+
+/// A `gpu.func` operation builder.
+pub struct FuncBuilder<'c> {
+    context: &'c Context,
+    name: String,
+    inputs: Vec<Type<'c>>,
+    results: Vec<Type<'c>>,
+    kernel: bool,
+    location: Location<'c>,
+}
+
+impl<'c> FuncBuilder<'c> {
+    /// Creates a `gpu.func` operation builder.
+    pub fn new(
+        context: &'c Context,
+        name: &str,
+        inputs: &[Type<'c>],
+        results: &[Type<'c>],
+        location: Location<'c>,
+    ) -> Self {
+        Self {
+            context,
+            name: name.into(),
+            inputs: inputs.to_vec(),
+            results: results.to_vec(),
+            kernel: false,
+            location,
+        }
+    }
+
+    /// Marks a function as a kernel.
+    pub fn kernel(mut self, kernel: bool) -> Self {
+        self.kernel = kernel;
+        self
+    }
+
+    /// Builds a `gpu.func` operation.
+    ///
+    /// A callback receives an entry block with arguments of input types and
+    /// should terminate it with a `gpu.return` operation. A built operation is
+    /// verified when it is in a `gpu.module` operation.
+    pub fn build(
+        self,
+        build_body: impl FnOnce(BlockRef<'c>) -> Result<(), Error>,
+    ) -> Result<Operation<'c>, Error> {
+        let mut attributes = vec![(
+            Identifier::new(self.context, "workgroup_attributions"),
+            unsafe {
+                Attribute::from_raw(mlirIntegerAttrGet(
+                    mlirIntegerTypeGet(self.context.to_raw(), 64),
+                    0,
+                ))
+            },
+        )];
+
+        if self.kernel {
+            attributes.push((
+                Identifier::new(self.context, KERNEL_ATTRIBUTE),
+                unit_attribute(self.context),
+            ));
+        }
+
+        build_function(
+            "gpu.func",
+            self.context,
+            &self.name,
+            &self.inputs,
+            &self.results,
+            &attributes,
+            self.location,
+            build_body,
+        )
+    }
+}
+
+/// Creates a `gpu.module` operation.
+///
+/// A callback receives a body block, in which `gpu.func` operations are
+/// appended. A `gpu.module_end` terminator is appended after it returns.
+pub fn module<'c>(
+    context: &'c Context,
+    name: &str,
+    location: Location<'c>,
+    build_body: impl FnOnce(BlockRef<'c>) -> Result<(), Error>,
+) -> Result<Operation<'c>, Error> {
+    let block = Block::new(&[]);
+
+    build_body(*block)?;
+
+    block.append_operation(operation::Builder::new("gpu.module_end", location).build()?);
+
+    let region = Region::new();
+    region.append_block(block);
+
+    operation::Builder::new("gpu.module", location)
+        .add_attributes(&[(Identifier::new(context, "sym_name"), unsafe {
+            Attribute::from_raw(mlirStringAttrGet(
+                context.to_raw(),
+                StringRef::from(name).to_raw(),
+            ))
+        })])
+        .add_regions(vec![region])
+        .build()
+}
+
+/// Creates a `gpu.return` operation.
+pub fn r#return<'c>(operands: &[Value], location: Location<'c>) -> Result<Operation<'c>, Error> {
+    operation::Builder::new("gpu.return", location)
+        .add_operands(operands)
+        .build()
+}
+
+/// Creates a `gpu.launch_func` operation.
+///
+/// A kernel is referred to by names of a `gpu.module` operation and a
+/// `gpu.func` one in it. Grid and block sizes are values of the `index` type.
+pub fn launch_func<'c>(
+    context: &'c Context,
+    (module, function): (&str, &str),
+    grid_size: [Value; 3],
+    block_size: [Value; 3],
+    dynamic_shared_memory_size: Option<Value>,
+    operands: &[Value],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    let kernel = unsafe {
+        let function =
+            mlirFlatSymbolRefAttrGet(context.to_raw(), StringRef::from(function).to_raw());
+
+        Attribute::from_raw(mlirSymbolRefAttrGet(
+            context.to_raw(),
+            StringRef::from(module).to_raw(),
+            1,
+            &function,
+        ))
+    };
+
+    operation::Builder::new("gpu.launch_func", location)
+        .add_operand_segments(&[
+            &[],
+            &grid_size[0..1],
+            &grid_size[1..2],
+            &grid_size[2..3],
+            &block_size[0..1],
+            &block_size[1..2],
+            &block_size[2..3],
+            &dynamic_shared_memory_size.into_iter().collect::<Vec<_>>(),
+            operands,
+        ])
+        .add_attributes(&[(Identifier::new(context, "kernel"), kernel)])
+        .build()
+}
+
+/// Creates a `gpu.launch` operation.
+///
+/// Grid and block sizes are values of the `index` type. A callback receives a
+/// body block whose arguments are block IDs, thread IDs, grid sizes, and block
+/// sizes in this order, each of which has values in the `x`, `y`, and `z`
+/// dimensions. A `gpu.terminator` operation is appended after it returns.
+pub fn launch<'c>(
+    context: &'c Context,
+    grid_size: [Value; 3],
+    block_size: [Value; 3],
+    dynamic_shared_memory_size: Option<Value>,
+    location: Location<'c>,
+    build_body: impl FnOnce(BlockRef<'c>) -> Result<(), Error>,
+) -> Result<Operation<'c>, Error> {
+    let block = Block::new(&[(Type::index(context), location); 12]);
+
+    build_body(*block)?;
+
+    block.append_operation(operation::Builder::new("gpu.terminator", location).build()?);
+
+    let region = Region::new();
+    region.append_block(block);
+
+    operation::Builder::new("gpu.launch", location)
+        .add_operand_segments(&[
+            &[],
+            &grid_size[0..1],
+            &grid_size[1..2],
+            &grid_size[2..3],
+            &block_size[0..1],
+            &block_size[1..2],
+            &block_size[2..3],
+            &dynamic_shared_memory_size.into_iter().collect::<Vec<_>>(),
+        ])
+        .add_regions(vec![region])
+        .build()
+}
+
+/// Creates a `gpu.thread_id` operation.
+pub fn thread_id<'c>(
+    context: &'c Context,
+    dimension: Dimension,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    index_operation(context, "gpu.thread_id", dimension, location)
+}
+
+/// Creates a `gpu.block_id` operation.
+pub fn block_id<'c>(
+    context: &'c Context,
+    dimension: Dimension,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    index_operation(context, "gpu.block_id", dimension, location)
+}
+
+/// Creates a `gpu.block_dim` operation.
+pub fn block_dim<'c>(
+    context: &'c Context,
+    dimension: Dimension,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    index_operation(context, "gpu.block_dim", dimension, location)
+}
+
+/// Creates a `gpu.grid_dim` operation.
+pub fn grid_dim<'c>(
+    context: &'c Context,
+    dimension: Dimension,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    index_operation(context, "gpu.grid_dim", dimension, location)
+}
+
+/// Marks a module as a container of `gpu.module` operations.
+///
+/// Modules need to be marked before `gpu.launch_func` operations in them are
+/// verified.
+pub fn set_container_module(operation: OperationRef) {
+    operation.set_attribute(CONTAINER_MODULE_ATTRIBUTE, unsafe {
+        Attribute::from_raw(mlirUnitAttrGet(operation.context().to_raw()))
+    });
+}
+
+fn index_operation<'c>(
+    context: &'c Context,
+    name: &str,
+    dimension: Dimension,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    operation::Builder::new(name, location)
+        .add_attributes(&[(
+            Identifier::new(context, "dimension"),
+            Attribute::parse(context, &format!("#gpu<dim {}>", dimension.name()))
+                .ok_or_else(|| Error::BuildOperation(name.into()))?,
+        )])
+        .add_results(&[Type::index(context)])
+        .build()
+}
+
+fn unit_attribute(context: &Context) -> Attribute {
+    unsafe { Attribute::from_raw(mlirUnitAttrGet(context.to_raw())) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dialect::func, ir::Module};
+
+    #[test]
+    fn build_kernel() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let index_type = Type::index(&context);
+        let module = Module::new(location);
+
+        set_container_module(module.as_operation());
+
+        module.append_operation(
+            super::module(&context, "kernels", location, |block| {
+                block.append_operation(
+                    FuncBuilder::new(&context, "foo", &[index_type], &[], location)
+                        .kernel(true)
+                        .build(|block| {
+                            block.append_operation(thread_id(&context, Dimension::X, location)?);
+                            block.append_operation(block_id(&context, Dimension::Y, location)?);
+                            block.append_operation(block_dim(&context, Dimension::Z, location)?);
+                            block.append_operation(grid_dim(&context, Dimension::X, location)?);
+                            block.append_operation(r#return(&[], location)?);
+
+                            Ok(())
+                        })?,
+                );
+
+                Ok(())
+            })
+            .unwrap(),
+        );
+
+        module
+            .append_function(
+                func::Builder::new(&context, "main", &[index_type], &[], location),
+                |block| {
+                    let size = block.argument(0)?.into();
+
+                    block.append_operation(launch_func(
+                        &context,
+                        ("kernels", "foo"),
+                        [size; 3],
+                        [size; 3],
+                        None,
+                        &[size],
+                        location,
+                    )?);
+                    block.append_operation(func::r#return(&[], location)?);
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+        assert!(module.as_operation().verify());
+
+        let source = module.as_operation().to_string();
+
+        assert!(source.contains("gpu.func @foo(%arg0: index) kernel"));
+        assert!(source.contains("@kernels::@foo"));
+    }
+
+    #[test]
+    fn launch() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let index_type = Type::index(&context);
+        let module = Module::new(location);
+
+        module
+            .append_function(
+                func::Builder::new(&context, "main", &[index_type], &[], location),
+                |block| {
+                    let size = block.argument(0)?.into();
+
+                    block.append_operation(super::launch(
+                        &context,
+                        [size; 3],
+                        [size; 3],
+                        None,
+                        location,
+                        |block| {
+                            assert_eq!(block.argument_count(), 12);
+
+                            Ok(())
+                        },
+                    )?);
+                    block.append_operation(func::r#return(&[], location)?);
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+        assert!(module.as_operation().verify());
+        assert!(module
+            .as_operation()
+            .to_string()
+            .contains("gpu.launch blocks("));
+    }
+
+    #[test]
+    fn launch_with_body_error() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+        let size = block.argument(0).unwrap().into();
+
+        assert_eq!(
+            super::launch(&context, [size; 3], [size; 3], None, location, |_| Err(
+                Error::InvokeFunction
+            ))
+            .unwrap_err(),
+            Error::InvokeFunction
+        );
+    }
+
+    #[test]
+    fn build_kernel_with_body_error() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+
+        assert_eq!(
+            FuncBuilder::new(&context, "foo", &[], &[], location)
+                .build(|_| Err(Error::InvokeFunction))
+                .unwrap_err(),
+            Error::InvokeFunction
+        );
+    }
+}
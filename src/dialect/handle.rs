@@ -10,6 +10,7 @@ use mlir_sys::{
 };
 
 /// A dialect handle.
+// TODO Add handles of the `arith`, `memref`, and `spv` dialects.
 #[derive(Clone, Copy, Debug)]
 pub struct Handle {
     raw: MlirDialectHandle,
@@ -99,6 +100,10 @@ impl Handle {
     pub(crate) unsafe fn from_raw(handle: MlirDialectHandle) -> Self {
         Self { raw: handle }
     }
+
+    pub(crate) unsafe fn to_raw(self) -> MlirDialectHandle {
+        self.raw
+    }
 }
 
 #[cfg(test)]
@@ -117,7 +122,22 @@ mod tests {
 
     #[test]
     fn namespace() {
-        Handle::func().namespace();
+        for (handle, namespace) in [
+            (Handle::r#async(), "async"),
+            (Handle::cf(), "cf"),
+            (Handle::func(), "func"),
+            (Handle::gpu(), "gpu"),
+            (Handle::linalg(), "linalg"),
+            (Handle::llvm(), "llvm"),
+            (Handle::pdl(), "pdl"),
+            (Handle::quant(), "quant"),
+            (Handle::scf(), "scf"),
+            (Handle::shape(), "shape"),
+            (Handle::sparse_tensor(), "sparse_tensor"),
+            (Handle::tensor(), "tensor"),
+        ] {
+            assert_eq!(handle.namespace().as_str().unwrap(), namespace);
+        }
     }
 
     #[test]
@@ -134,6 +154,16 @@ mod tests {
         Handle::func().load_dialect(&context);
     }
 
+    #[test]
+    fn load_dialect_into_empty_context() {
+        let context = unsafe { Context::new_unchecked() };
+
+        let dialect = Handle::scf().load_dialect(&context);
+
+        assert_eq!(dialect.namespace().as_str().unwrap(), "scf");
+        assert!(context.is_registered_operation("scf.for"));
+    }
+
     #[test]
     fn register_dialect() {
         let context = Context::new();
@@ -0,0 +1,358 @@
+//! The `linalg` dialect
+
+use super::scf::{build_region, value_types};
+use crate::{
+    ir::{
+        operation,
+        r#type::{Shaped, TypeLike},
+        AffineMap, ArrayAttribute, Attribute, BlockRef, Identifier, Location, Operation, Region,
+        Type, Value,
+    },
+    Context, Error,
+};
+use mlir_sys::mlirLinalgFillBuiltinNamedOpRegion;
+
+/// An iterator type of a loop.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IteratorType {
+    Parallel,
+    Reduction,
+    Window,
+}
+
+impl IteratorType {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Parallel => "parallel",
+            Self::Reduction => "reduction",
+            Self::Window => "window",
+        }
+    }
+}
+
+/// A `linalg.generic` operation builder.
+pub struct GenericBuilder<'c> {
+    context: &'c Context,
+    inputs: Vec<Value<'c>>,
+    outputs: Vec<Value<'c>>,
+    indexing_maps: Vec<AffineMap<'c>>,
+    iterator_types: Vec<IteratorType>,
+    location: Location<'c>,
+}
+
+impl<'c> GenericBuilder<'c> {
+    /// Creates a `linalg.generic` operation builder.
+    pub fn new(
+        context: &'c Context,
+        inputs: &[Value<'c>],
+        outputs: &[Value<'c>],
+        location: Location<'c>,
+    ) -> Self {
+        Self {
+            context,
+            inputs: inputs.to_vec(),
+            outputs: outputs.to_vec(),
+            indexing_maps: vec![],
+            iterator_types: vec![],
+            location,
+        }
+    }
+
+    /// Sets indexing maps of inputs and outputs.
+    pub fn indexing_maps(mut self, indexing_maps: &[AffineMap<'c>]) -> Self {
+        self.indexing_maps = indexing_maps.to_vec();
+        self
+    }
+
+    /// Sets iterator types of loops.
+    pub fn iterator_types(mut self, iterator_types: &[IteratorType]) -> Self {
+        self.iterator_types = iterator_types.to_vec();
+        self
+    }
+
+    /// Builds a `linalg.generic` operation.
+    ///
+    /// A callback receives a body block and its arguments of element types of
+    /// inputs and outputs, and returns values yielded to outputs. Results of
+    /// the operation have types of tensor outputs.
+    pub fn build(
+        self,
+        build_body: impl FnOnce(BlockRef<'c>, &[Value<'c>]) -> Result<Vec<Value<'c>>, Error>,
+    ) -> Result<Operation<'c>, Error> {
+        let region = build_region(
+            &value_types(&[&self.inputs[..], &self.outputs[..]].concat())
+                .into_iter()
+                .map(element_type)
+                .collect::<Vec<_>>(),
+            self.location,
+            build_body,
+            |values| r#yield(&values, self.location),
+        )?;
+
+        operation::Builder::new("linalg.generic", self.location)
+            .add_operand_segments(&[&self.inputs, &self.outputs])
+            .add_attributes(&[
+                (
+                    Identifier::new(self.context, "indexing_maps"),
+                    ArrayAttribute::new(
+                        self.context,
+                        &self
+                            .indexing_maps
+                            .iter()
+                            .map(|&map| map.into())
+                            .collect::<Vec<_>>(),
+                    )
+                    .into(),
+                ),
+                (
+                    Identifier::new(self.context, "iterator_types"),
+                    ArrayAttribute::new(
+                        self.context,
+                        &self
+                            .iterator_types
+                            .iter()
+                            .map(|iterator_type| {
+                                Attribute::string(self.context, iterator_type.name())
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                    .into(),
+                ),
+            ])
+            .add_results(&tensor_types(&self.outputs))
+            .add_regions(vec![region])
+            .build()
+    }
+}
+
+/// Creates a `linalg.yield` operation.
+pub fn r#yield<'c>(values: &[Value], location: Location<'c>) -> Result<Operation<'c>, Error> {
+    operation::Builder::new("linalg.yield", location)
+        .add_operands(values)
+        .build()
+}
+
+/// Creates a `linalg.matmul` operation.
+pub fn matmul<'c>(
+    lhs: Value<'c>,
+    rhs: Value<'c>,
+    output: Value<'c>,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    build_named("linalg.matmul", &[lhs, rhs], output, location)
+}
+
+/// Creates a `linalg.fill` operation.
+pub fn fill<'c>(
+    value: Value<'c>,
+    output: Value<'c>,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    build_named("linalg.fill", &[value], output, location)
+}
+
+/// Creates a transpose operation.
+///
+/// A permutation maps dimensions of an output to ones of an input.
+// TODO Create a `linalg.transpose` operation. MLIR 15 does not have it, so a
+// `linalg.generic` operation is created instead.
+pub fn transpose<'c>(
+    context: &'c Context,
+    input: Value<'c>,
+    output: Value<'c>,
+    permutation: &[usize],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    GenericBuilder::new(context, &[input], &[output], location)
+        .indexing_maps(&[
+            AffineMap::permutation(context, permutation)?,
+            AffineMap::identity(context, permutation.len()),
+        ])
+        .iterator_types(&vec![IteratorType::Parallel; permutation.len()])
+        .build(|_, arguments| Ok(vec![arguments[0]]))
+}
+
+fn build_named<'c>(
+    name: &str,
+    inputs: &[Value<'c>],
+    output: Value<'c>,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    let context = location.context();
+
+    context.check_operation_name(name)?;
+
+    // Regions can be filled only for registered operations.
+    if !context.is_registered_operation(name) {
+        return Err(Error::OperationNotRegistered(name.into()));
+    }
+
+    let operation = operation::Builder::new(name, location)
+        .add_operand_segments(&[inputs, &[output]])
+        .add_results(&tensor_types(&[output]))
+        .add_regions(vec![Region::new()])
+        .build()?;
+
+    unsafe { mlirLinalgFillBuiltinNamedOpRegion(operation.to_raw()) }
+
+    Ok(operation)
+}
+
+fn element_type(r#type: Type) -> Type {
+    Shaped::try_from(r#type)
+        .map(|shaped| shaped.element_type())
+        .unwrap_or(r#type)
+}
+
+fn tensor_types<'c>(values: &[Value<'c>]) -> Vec<Type<'c>> {
+    value_types(values)
+        .into_iter()
+        .filter(|r#type| r#type.is_tensor())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Block, ValueLike};
+
+    #[test]
+    fn generic() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::parse(&context, "tensor<4xf32>").unwrap();
+        let block = Block::new(&[(r#type, location); 3]);
+        let argument = |index| block.argument(index).unwrap().into();
+
+        let operation = GenericBuilder::new(
+            &context,
+            &[argument(0), argument(1)],
+            &[argument(2)],
+            location,
+        )
+        .indexing_maps(&[AffineMap::identity(&context, 1); 3])
+        .iterator_types(&[IteratorType::Parallel])
+        .build(|block, arguments| {
+            let sum = block.append_operation(
+                operation::Builder::new("arith.addf", location)
+                    .add_operands(&arguments[..2])
+                    .enable_result_type_inference()
+                    .build()?,
+            );
+
+            Ok(vec![sum.result(0).unwrap().into()])
+        })
+        .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(operation.result_count(), 1);
+    }
+
+    #[test]
+    fn generic_with_memref() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::parse(&context, "memref<4xf32>").unwrap();
+        let block = Block::new(&[(r#type, location); 2]);
+        let argument = |index| block.argument(index).unwrap().into();
+
+        let operation = GenericBuilder::new(&context, &[argument(0)], &[argument(1)], location)
+            .indexing_maps(&[AffineMap::identity(&context, 1); 2])
+            .iterator_types(&[IteratorType::Parallel])
+            .build(|_, arguments| Ok(vec![arguments[0]]))
+            .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(operation.result_count(), 0);
+    }
+
+    #[test]
+    fn matmul() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[
+            (Type::parse(&context, "tensor<2x3xf32>").unwrap(), location),
+            (Type::parse(&context, "tensor<3x4xf32>").unwrap(), location),
+            (Type::parse(&context, "tensor<2x4xf32>").unwrap(), location),
+        ]);
+        let argument = |index| block.argument(index).unwrap().into();
+
+        let operation = super::matmul(argument(0), argument(1), argument(2), location).unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(operation.result_count(), 1);
+    }
+
+    #[test]
+    fn matmul_without_dialect() {
+        let context = unsafe { Context::new_unchecked() };
+        let location = Location::unknown(&context);
+        let r#type = Type::parse(&context, "tensor<2x2xf32>").unwrap();
+        let block = Block::new(&[(r#type, location); 3]);
+        let argument = |index| block.argument(index).unwrap().into();
+
+        assert_eq!(
+            super::matmul(argument(0), argument(1), argument(2), location).unwrap_err(),
+            Error::DialectNotLoaded("linalg".into())
+        );
+
+        context.set_allow_unregistered_dialects(true);
+
+        assert_eq!(
+            super::matmul(argument(0), argument(1), argument(2), location).unwrap_err(),
+            Error::OperationNotRegistered("linalg.matmul".into())
+        );
+    }
+
+    #[test]
+    fn fill() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::parse(&context, "memref<2x4xf32>").unwrap(), location)]);
+        let value = block.append_operation(
+            operation::Builder::new("arith.constant", location)
+                .add_attributes(&[(
+                    Identifier::new(&context, "value"),
+                    Attribute::parse(&context, "0.0 : f32").unwrap(),
+                )])
+                .enable_result_type_inference()
+                .build()
+                .unwrap(),
+        );
+
+        let operation = super::fill(
+            value.result(0).unwrap().into(),
+            block.argument(0).unwrap().into(),
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(operation.result_count(), 0);
+    }
+
+    #[test]
+    fn transpose() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[
+            (Type::parse(&context, "tensor<2x4xf32>").unwrap(), location),
+            (Type::parse(&context, "tensor<4x2xf32>").unwrap(), location),
+        ]);
+
+        let operation = super::transpose(
+            &context,
+            block.argument(0).unwrap().into(),
+            block.argument(1).unwrap().into(),
+            &[1, 0],
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(
+            operation.result(0).unwrap().r#type(),
+            Type::parse(&context, "tensor<4x2xf32>").unwrap()
+        );
+    }
+}
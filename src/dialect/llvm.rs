@@ -1,3 +1,5 @@
 //! The `llvm` dialect
 
+// TODO Add debug information attributes, e.g. of subprograms and files.
+
 pub mod r#type;
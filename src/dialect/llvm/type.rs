@@ -3,15 +3,13 @@
 use crate::{
     context::Context,
     ir::{Type, TypeLike},
-    utility::into_raw_array,
+    utility::escape_string,
 };
 use mlir_sys::{
     mlirLLVMArrayTypeGet, mlirLLVMFunctionTypeGet, mlirLLVMPointerTypeGet,
     mlirLLVMStructTypeLiteralGet, mlirLLVMVoidTypeGet,
 };
 
-// TODO Check if the `llvm` dialect is loaded on use of those functions.
-
 /// Creates an LLVM array type.
 pub fn array(r#type: Type, len: u32) -> Type {
     unsafe { Type::from_raw(mlirLLVMArrayTypeGet(r#type.to_raw(), len)) }
@@ -27,7 +25,11 @@ pub fn function<'c>(
         Type::from_raw(mlirLLVMFunctionTypeGet(
             result.to_raw(),
             arguments.len() as isize,
-            into_raw_array(arguments.iter().map(|argument| argument.to_raw()).collect()),
+            arguments
+                .iter()
+                .map(|argument| argument.to_raw())
+                .collect::<Vec<_>>()
+                .as_ptr(),
             variadic_arguments,
         ))
     }
@@ -38,18 +40,74 @@ pub fn pointer(r#type: Type, address_space: u32) -> Type {
     unsafe { Type::from_raw(mlirLLVMPointerTypeGet(r#type.to_raw(), address_space)) }
 }
 
+// TODO Use C API functions for opaque pointers and identified structs.
+
+/// Creates an LLVM opaque pointer type.
+///
+/// It returns `None` if the `llvm` dialect is not loaded.
+pub fn opaque_pointer(context: &Context, address_space: u32) -> Option<Type> {
+    Type::parse(
+        context,
+        &if address_space == 0 {
+            "!llvm.ptr".into()
+        } else {
+            format!("!llvm.ptr<{}>", address_space)
+        },
+    )
+}
+
 /// Creates an LLVM struct type.
 pub fn r#struct<'c>(context: &'c Context, fields: &[Type<'c>], packed: bool) -> Type<'c> {
     unsafe {
         Type::from_raw(mlirLLVMStructTypeLiteralGet(
             context.to_raw(),
             fields.len() as isize,
-            into_raw_array(fields.iter().map(|field| field.to_raw()).collect()),
+            fields
+                .iter()
+                .map(|field| field.to_raw())
+                .collect::<Vec<_>>()
+                .as_ptr(),
             packed,
         ))
     }
 }
 
+/// Creates an LLVM identified struct type.
+///
+/// It returns `None` if the `llvm` dialect is not loaded or a struct of the
+/// same name has a different body.
+pub fn identified_struct<'c>(
+    context: &'c Context,
+    name: &str,
+    fields: &[Type<'c>],
+    packed: bool,
+) -> Option<Type<'c>> {
+    Type::parse(
+        context,
+        &format!(
+            "!llvm.struct<\"{}\", {}({})>",
+            escape_string(name),
+            if packed { "packed " } else { "" },
+            fields
+                .iter()
+                .map(|field| field.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    )
+}
+
+/// Creates an LLVM opaque struct type.
+///
+/// It returns `None` if the `llvm` dialect is not loaded or a struct of the
+/// same name has a body.
+pub fn opaque_struct<'c>(context: &'c Context, name: &str) -> Option<Type<'c>> {
+    Type::parse(
+        context,
+        &format!("!llvm.struct<\"{}\", opaque>", escape_string(name)),
+    )
+}
+
 /// Creates an LLVM void type.
 pub fn void(context: &Context) -> Type {
     unsafe { Type::from_raw(mlirLLVMVoidTypeGet(context.to_raw())) }
@@ -58,20 +116,10 @@ pub fn void(context: &Context) -> Type {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::dialect;
-
-    fn create_context() -> Context {
-        let context = Context::new();
-
-        dialect::Handle::llvm().register_dialect(&context);
-        context.get_or_load_dialect("llvm");
-
-        context
-    }
 
     #[test]
     fn pointer() {
-        let context = create_context();
+        let context = Context::new();
         let i32 = Type::integer(&context, 32);
 
         assert_eq!(
@@ -82,7 +130,7 @@ mod tests {
 
     #[test]
     fn pointer_with_address_space() {
-        let context = create_context();
+        let context = Context::new();
         let i32 = Type::integer(&context, 32);
 
         assert_eq!(
@@ -93,7 +141,7 @@ mod tests {
 
     #[test]
     fn void() {
-        let context = create_context();
+        let context = Context::new();
 
         assert_eq!(
             super::void(&context),
@@ -103,7 +151,7 @@ mod tests {
 
     #[test]
     fn array() {
-        let context = create_context();
+        let context = Context::new();
         let i32 = Type::integer(&context, 32);
 
         assert_eq!(
@@ -114,7 +162,7 @@ mod tests {
 
     #[test]
     fn function() {
-        let context = create_context();
+        let context = Context::new();
         let i8 = Type::integer(&context, 8);
         let i32 = Type::integer(&context, 32);
         let i64 = Type::integer(&context, 64);
@@ -127,7 +175,7 @@ mod tests {
 
     #[test]
     fn r#struct() {
-        let context = create_context();
+        let context = Context::new();
         let i32 = Type::integer(&context, 32);
         let i64 = Type::integer(&context, 64);
 
@@ -139,7 +187,7 @@ mod tests {
 
     #[test]
     fn packed_struct() {
-        let context = create_context();
+        let context = Context::new();
         let i32 = Type::integer(&context, 32);
         let i64 = Type::integer(&context, 64);
 
@@ -148,4 +196,57 @@ mod tests {
             Type::parse(&context, "!llvm.struct<packed (i32, i64)>").unwrap()
         );
     }
+
+    #[test]
+    fn opaque_pointer() {
+        let context = Context::new();
+
+        assert_eq!(
+            super::opaque_pointer(&context, 0),
+            Type::parse(&context, "!llvm.ptr")
+        );
+        assert_eq!(
+            super::opaque_pointer(&context, 4),
+            Type::parse(&context, "!llvm.ptr<4>")
+        );
+    }
+
+    #[test]
+    fn identified_struct() {
+        let context = Context::new();
+        let i32 = Type::integer(&context, 32);
+        let i64 = Type::integer(&context, 64);
+
+        assert_eq!(
+            super::identified_struct(&context, "foo", &[i32, i64], false),
+            Type::parse(&context, "!llvm.struct<\"foo\", (i32, i64)>")
+        );
+        assert_eq!(
+            super::identified_struct(&context, "bar", &[i32], true),
+            Type::parse(&context, "!llvm.struct<\"bar\", packed (i32)>")
+        );
+    }
+
+    #[test]
+    fn identified_struct_with_different_body() {
+        let context = Context::new();
+        let i32 = Type::integer(&context, 32);
+        let i64 = Type::integer(&context, 64);
+
+        assert!(super::identified_struct(&context, "foo", &[i32], false).is_some());
+        assert_eq!(
+            super::identified_struct(&context, "foo", &[i64], false),
+            None
+        );
+    }
+
+    #[test]
+    fn opaque_struct() {
+        let context = Context::new();
+
+        assert_eq!(
+            super::opaque_struct(&context, "foo"),
+            Type::parse(&context, "!llvm.struct<\"foo\", opaque>")
+        );
+    }
 }
@@ -0,0 +1,620 @@
+//! The `memref` dialect
+
+use crate::{
+    ir::{
+        operation::Builder,
+        r#type::{MemRef, Shaped},
+        ArrayAttribute, Attribute, Identifier, Location, Operation, Type, TypeLike, Value,
+        ValueLike,
+    },
+    Context, Error,
+};
+use mlir_sys::{
+    mlirShapedTypeGetElementType, mlirTypeIsAUnrankedMemRef, mlirUnrankedMemrefGetMemorySpace,
+    mlirValueGetType,
+};
+use std::slice;
+
+/// A static or dynamic offset, size, or stride.
+#[derive(Clone, Copy, Debug)]
+pub enum Index<'a> {
+    /// A static index.
+    Static(i64),
+    /// A dynamic index of the `index` type.
+    Dynamic(Value<'a>),
+}
+
+/// Creates a `memref.alloc` operation.
+///
+/// Dynamic sizes are given for dynamic dimensions of a type.
+pub fn alloc<'c>(
+    r#type: MemRef<'c>,
+    dynamic_sizes: &[Value],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    build_allocation("memref.alloc", r#type, dynamic_sizes, location)
+}
+
+/// Creates a `memref.alloca` operation.
+///
+/// Dynamic sizes are given for dynamic dimensions of a type.
+pub fn alloca<'c>(
+    r#type: MemRef<'c>,
+    dynamic_sizes: &[Value],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    build_allocation("memref.alloca", r#type, dynamic_sizes, location)
+}
+
+/// Creates a `memref.dealloc` operation.
+pub fn dealloc<'c>(value: Value, location: Location<'c>) -> Result<Operation<'c>, Error> {
+    Builder::new("memref.dealloc", location)
+        .add_operand(value)
+        .build()
+}
+
+/// Creates a `memref.load` operation.
+pub fn load<'c>(
+    memref: Value,
+    indices: &[Value],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    let r#type = MemRef::try_from(unsafe { Type::from_raw(mlirValueGetType(memref.to_raw())) })?;
+
+    Builder::new("memref.load", location)
+        .add_operand(memref)
+        .add_operands(indices)
+        .add_results(&[unsafe { Type::from_raw(mlirShapedTypeGetElementType(r#type.to_raw())) }])
+        .build()
+}
+
+/// Creates a `memref.store` operation.
+pub fn store<'c>(
+    value: Value,
+    memref: Value,
+    indices: &[Value],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    Builder::new("memref.store", location)
+        .add_operands(&[value, memref])
+        .add_operands(indices)
+        .build()
+}
+
+/// Creates a `memref.cast` operation.
+pub fn cast<'c>(
+    source: Value,
+    r#type: Type<'c>,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    Builder::new("memref.cast", location)
+        .add_operand(source)
+        .add_results(&[r#type])
+        .build()
+}
+
+/// Creates a `memref.subview` operation.
+///
+/// A result type is inferred from a source type and offsets, sizes, and
+/// strides.
+pub fn subview<'c>(
+    context: &'c Context,
+    source: Value,
+    offsets: &[Index],
+    sizes: &[Index],
+    strides: &[Index],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    let source_type =
+        MemRef::try_from(unsafe { Type::from_raw(mlirValueGetType(source.to_raw())) })?;
+    let (source_strides, source_offset) = source_type.strides_and_offset()?;
+
+    for indices in [offsets, sizes, strides] {
+        if indices.len() != source_strides.len() {
+            return Err(Error::IndexCount(source_strides.len(), indices.len()));
+        }
+    }
+
+    let (static_offsets, dynamic_offsets) =
+        split_indices(offsets, MemRef::DYNAMIC_STRIDE_OR_OFFSET);
+    let (static_sizes, dynamic_sizes) = split_indices(sizes, MemRef::DYNAMIC_SIZE);
+    let (static_strides, dynamic_strides) =
+        split_indices(strides, MemRef::DYNAMIC_STRIDE_OR_OFFSET);
+
+    let offset = static_offsets.iter().zip(&source_strides).fold(
+        source_offset,
+        |offset, (&index, &stride)| {
+            add_stride_or_offset(offset, multiply_stride_or_offset(index, stride))
+        },
+    );
+    let result_strides = static_strides
+        .iter()
+        .zip(&source_strides)
+        .map(|(&stride, &source_stride)| multiply_stride_or_offset(stride, source_stride))
+        .collect::<Vec<_>>();
+
+    build_view(
+        context,
+        "memref.subview",
+        &[source],
+        (&static_offsets, &dynamic_offsets),
+        (&static_sizes, &dynamic_sizes),
+        (&static_strides, &dynamic_strides),
+        MemRef::new(
+            unsafe { Type::from_raw(mlirShapedTypeGetElementType(source_type.to_raw())) },
+            &static_sizes,
+            Some(MemRef::strided_layout(context, offset, &result_strides)),
+            source_type.memory_space(),
+        )
+        .into(),
+        location,
+    )
+}
+
+/// Creates a `memref.reinterpret_cast` operation.
+///
+/// A result type is inferred from a source type and an offset, sizes, and
+/// strides. A source can be a ranked or unranked memref.
+pub fn reinterpret_cast<'c>(
+    context: &'c Context,
+    source: Value,
+    offset: Index,
+    sizes: &[Index],
+    strides: &[Index],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    let source_type = unsafe { Type::from_raw(mlirValueGetType(source.to_raw())) };
+    let memory_space = if let Ok(source_type) = MemRef::try_from(source_type) {
+        source_type.memory_space()
+    } else if unsafe { mlirTypeIsAUnrankedMemRef(source_type.to_raw()) } {
+        unsafe {
+            Attribute::from_option_raw(mlirUnrankedMemrefGetMemorySpace(source_type.to_raw()))
+        }
+    } else {
+        return Err(Error::MemRefExpected(source_type.to_string()));
+    };
+
+    let (static_offsets, dynamic_offsets) =
+        split_indices(&[offset], MemRef::DYNAMIC_STRIDE_OR_OFFSET);
+    let (static_sizes, dynamic_sizes) = split_indices(sizes, MemRef::DYNAMIC_SIZE);
+    let (static_strides, dynamic_strides) =
+        split_indices(strides, MemRef::DYNAMIC_STRIDE_OR_OFFSET);
+
+    build_view(
+        context,
+        "memref.reinterpret_cast",
+        &[source],
+        (&static_offsets, &dynamic_offsets),
+        (&static_sizes, &dynamic_sizes),
+        (&static_strides, &dynamic_strides),
+        MemRef::new(
+            unsafe { Type::from_raw(mlirShapedTypeGetElementType(source_type.to_raw())) },
+            &static_sizes,
+            Some(MemRef::strided_layout(
+                context,
+                static_offsets[0],
+                &static_strides,
+            )),
+            memory_space,
+        )
+        .into(),
+        location,
+    )
+}
+
+fn build_allocation<'c>(
+    name: &str,
+    r#type: MemRef<'c>,
+    dynamic_sizes: &[Value],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    let dynamic_size_count = Shaped::from(r#type)
+        .dimension_sizes()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|&size| size == MemRef::DYNAMIC_SIZE)
+        .count();
+
+    if dynamic_sizes.len() != dynamic_size_count {
+        return Err(Error::DynamicSizeCount(
+            dynamic_size_count,
+            dynamic_sizes.len(),
+        ));
+    }
+
+    Builder::new(name, location)
+        .add_operand_segments(&[dynamic_sizes, &[]])
+        .add_results(&[r#type.into()])
+        .build()
+}
+
+// Builds a view-like operation with operands followed by mixed static and
+// dynamic offsets, sizes, and strides.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn build_view<'c>(
+    context: &'c Context,
+    name: &str,
+    operands: &[Value],
+    (static_offsets, dynamic_offsets): (&[i64], &[Value]),
+    (static_sizes, dynamic_sizes): (&[i64], &[Value]),
+    (static_strides, dynamic_strides): (&[i64], &[Value]),
+    result_type: Type<'c>,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    Builder::new(name, location)
+        .add_operand_segments(
+            &operands
+                .iter()
+                .map(slice::from_ref)
+                .chain([dynamic_offsets, dynamic_sizes, dynamic_strides])
+                .collect::<Vec<_>>(),
+        )
+        .add_attributes(&[
+            (
+                Identifier::new(context, "static_offsets"),
+                ArrayAttribute::i64(context, static_offsets).into(),
+            ),
+            (
+                Identifier::new(context, "static_sizes"),
+                ArrayAttribute::i64(context, static_sizes).into(),
+            ),
+            (
+                Identifier::new(context, "static_strides"),
+                ArrayAttribute::i64(context, static_strides).into(),
+            ),
+        ])
+        .add_results(&[result_type])
+        .build()
+}
+
+pub(crate) fn split_indices<'a>(indices: &[Index<'a>], dynamic: i64) -> (Vec<i64>, Vec<Value<'a>>) {
+    let mut static_indices = Vec::with_capacity(indices.len());
+    let mut dynamic_indices = vec![];
+
+    for index in indices {
+        match index {
+            Index::Static(index) => static_indices.push(*index),
+            Index::Dynamic(value) => {
+                static_indices.push(dynamic);
+                dynamic_indices.push(*value);
+            }
+        }
+    }
+
+    (static_indices, dynamic_indices)
+}
+
+fn add_stride_or_offset(one: i64, other: i64) -> i64 {
+    if one == MemRef::DYNAMIC_STRIDE_OR_OFFSET || other == MemRef::DYNAMIC_STRIDE_OR_OFFSET {
+        MemRef::DYNAMIC_STRIDE_OR_OFFSET
+    } else {
+        one + other
+    }
+}
+
+fn multiply_stride_or_offset(one: i64, other: i64) -> i64 {
+    if one == MemRef::DYNAMIC_STRIDE_OR_OFFSET || other == MemRef::DYNAMIC_STRIDE_OR_OFFSET {
+        MemRef::DYNAMIC_STRIDE_OR_OFFSET
+    } else {
+        one * other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Block;
+
+    fn result_strides_and_offset(operation: &Operation) -> (Vec<i64>, i64) {
+        MemRef::try_from(operation.result(0).unwrap().r#type())
+            .unwrap()
+            .strides_and_offset()
+            .unwrap()
+    }
+
+    #[test]
+    fn alloc() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+        let r#type = MemRef::new(
+            Type::float32(&context),
+            &[MemRef::DYNAMIC_SIZE, 4],
+            None,
+            None,
+        );
+
+        let operation =
+            super::alloc(r#type, &[block.argument(0).unwrap().into()], location).unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(operation.result(0).unwrap().r#type(), r#type.into());
+    }
+
+    #[test]
+    fn alloc_error() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = MemRef::new(
+            Type::float32(&context),
+            &[MemRef::DYNAMIC_SIZE, 4],
+            None,
+            None,
+        );
+
+        assert_eq!(
+            super::alloc(r#type, &[], location).unwrap_err(),
+            Error::DynamicSizeCount(1, 0)
+        );
+    }
+
+    #[test]
+    fn alloca() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+
+        let operation = super::alloca(
+            MemRef::new(Type::float32(&context), &[4], None, None),
+            &[],
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+    }
+
+    #[test]
+    fn load_and_store() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let index_type = Type::index(&context);
+        let block = Block::new(&[
+            (
+                MemRef::new(Type::float32(&context), &[4], None, None).into(),
+                location,
+            ),
+            (index_type, location),
+        ]);
+        let memref = block.argument(0).unwrap().into();
+        let index = block.argument(1).unwrap().into();
+
+        let load = block.append_operation(super::load(memref, &[index], location).unwrap());
+
+        assert!(load.verify());
+        assert_eq!(load.result(0).unwrap().r#type(), Type::float32(&context));
+
+        let store =
+            super::store(load.result(0).unwrap().into(), memref, &[index], location).unwrap();
+
+        assert!(store.verify());
+    }
+
+    #[test]
+    fn dealloc() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(
+            MemRef::new(Type::float32(&context), &[4], None, None).into(),
+            location,
+        )]);
+
+        assert!(super::dealloc(block.argument(0).unwrap().into(), location)
+            .unwrap()
+            .verify());
+    }
+
+    #[test]
+    fn cast() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(
+            MemRef::new(Type::float32(&context), &[4], None, None).into(),
+            location,
+        )]);
+
+        let operation = super::cast(
+            block.argument(0).unwrap().into(),
+            MemRef::new(Type::float32(&context), &[MemRef::DYNAMIC_SIZE], None, None).into(),
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+    }
+
+    #[test]
+    fn subview() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = MemRef::new(Type::float32(&context), &[8, 16], None, None);
+        let block = Block::new(&[(r#type.into(), location)]);
+
+        let operation = super::subview(
+            &context,
+            block.argument(0).unwrap().into(),
+            &[Index::Static(2), Index::Static(4)],
+            &[Index::Static(4), Index::Static(4)],
+            &[Index::Static(1), Index::Static(2)],
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(result_strides_and_offset(&operation), (vec![16, 2], 36));
+    }
+
+    #[test]
+    fn subview_with_missing_offset() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = MemRef::new(Type::float32(&context), &[8, 16], None, None);
+        let block = Block::new(&[(r#type.into(), location)]);
+
+        assert_eq!(
+            super::subview(
+                &context,
+                block.argument(0).unwrap().into(),
+                &[Index::Static(2)],
+                &[Index::Static(4), Index::Static(4)],
+                &[Index::Static(1), Index::Static(1)],
+                location,
+            )
+            .unwrap_err(),
+            Error::IndexCount(2, 1)
+        );
+    }
+
+    #[test]
+    fn subview_with_dynamic_indices() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let index_type = Type::index(&context);
+        let r#type = MemRef::new(
+            Type::float32(&context),
+            &[MemRef::DYNAMIC_SIZE, 16],
+            None,
+            None,
+        );
+        let block = Block::new(&[
+            (r#type.into(), location),
+            (index_type, location),
+            (index_type, location),
+        ]);
+        let index = block.argument(1).unwrap().into();
+        let size = block.argument(2).unwrap().into();
+
+        let operation = super::subview(
+            &context,
+            block.argument(0).unwrap().into(),
+            &[Index::Static(0), Index::Dynamic(index)],
+            &[Index::Dynamic(size), Index::Static(4)],
+            &[Index::Static(1), Index::Static(1)],
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(
+            result_strides_and_offset(&operation),
+            (vec![16, 1], MemRef::DYNAMIC_STRIDE_OR_OFFSET)
+        );
+    }
+
+    #[test]
+    fn subview_of_strided_source() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = MemRef::new(
+            Type::float32(&context),
+            &[8, 8],
+            Some(MemRef::strided_layout(&context, 3, &[32, 2])),
+            None,
+        );
+        let block = Block::new(&[(r#type.into(), location)]);
+
+        let operation = super::subview(
+            &context,
+            block.argument(0).unwrap().into(),
+            &[Index::Static(1), Index::Static(1)],
+            &[Index::Static(2), Index::Static(2)],
+            &[Index::Static(2), Index::Static(3)],
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(result_strides_and_offset(&operation), (vec![64, 6], 37));
+    }
+
+    #[test]
+    fn subview_error() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+
+        assert_eq!(
+            super::subview(
+                &context,
+                block.argument(0).unwrap().into(),
+                &[],
+                &[],
+                &[],
+                location,
+            )
+            .unwrap_err(),
+            Error::MemRefExpected("index".into())
+        );
+    }
+
+    #[test]
+    fn reinterpret_cast() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = MemRef::new(Type::float32(&context), &[64], None, None);
+        let block = Block::new(&[(r#type.into(), location)]);
+
+        let operation = super::reinterpret_cast(
+            &context,
+            block.argument(0).unwrap().into(),
+            Index::Static(8),
+            &[Index::Static(4), Index::Static(4)],
+            &[Index::Static(8), Index::Static(1)],
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(result_strides_and_offset(&operation), (vec![8, 1], 8));
+    }
+
+    #[test]
+    fn reinterpret_cast_with_dynamic_indices() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let index_type = Type::index(&context);
+        let r#type = Type::parse(&context, "memref<*xf32>").unwrap();
+        let block = Block::new(&[
+            (r#type, location),
+            (index_type, location),
+            (index_type, location),
+        ]);
+        let offset = block.argument(1).unwrap().into();
+        let size = block.argument(2).unwrap().into();
+
+        let operation = super::reinterpret_cast(
+            &context,
+            block.argument(0).unwrap().into(),
+            Index::Dynamic(offset),
+            &[Index::Dynamic(size)],
+            &[Index::Static(1)],
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(
+            result_strides_and_offset(&operation),
+            (vec![1], MemRef::DYNAMIC_STRIDE_OR_OFFSET)
+        );
+    }
+
+    #[test]
+    fn reinterpret_cast_error() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+
+        assert_eq!(
+            super::reinterpret_cast(
+                &context,
+                block.argument(0).unwrap().into(),
+                Index::Static(0),
+                &[],
+                &[],
+                location,
+            )
+            .unwrap_err(),
+            Error::MemRefExpected("index".into())
+        );
+    }
+}
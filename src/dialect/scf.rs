@@ -0,0 +1,414 @@
+//! The `scf` dialect
+
+use crate::{
+    ir::{operation, Block, BlockRef, Location, Operation, Region, Type, Value, ValueLike},
+    Error,
+};
+
+/// Creates a `scf.for` operation at the end of a block.
+///
+/// A callback receives a body block, an induction variable, and loop-carried
+/// values, and returns values yielded to the next iteration. Results of the
+/// operation have the same types as initial values and are returned.
+pub fn r#for<'c>(
+    lower_bound: Value<'c>,
+    upper_bound: Value<'c>,
+    step: Value<'c>,
+    initial_values: &[Value<'c>],
+    block: &BlockRef<'c>,
+    location: Location<'c>,
+    build_body: impl FnOnce(BlockRef<'c>, Value<'c>, &[Value<'c>]) -> Result<Vec<Value<'c>>, Error>,
+) -> Result<Vec<Value<'c>>, Error> {
+    let types = value_types(initial_values);
+    let region = build_region(
+        &[&[lower_bound.r#type()], &types[..]].concat(),
+        location,
+        |block, arguments| build_body(block, arguments[0], &arguments[1..]),
+        |values| r#yield(&values, location),
+    )?;
+
+    append_operation(
+        block,
+        operation::Builder::new("scf.for", location)
+            .add_operands(&[lower_bound, upper_bound, step])
+            .add_operands(initial_values)
+            .add_results(&types)
+            .add_regions(vec![region]),
+    )
+}
+
+/// Creates a `scf.if` operation at the end of a block.
+///
+/// Callbacks receive blocks of `then` and `else` regions and return values
+/// yielded as results of the operation, which are returned.
+pub fn r#if<'c>(
+    condition: Value<'c>,
+    result_types: &[Type<'c>],
+    block: &BlockRef<'c>,
+    location: Location<'c>,
+    build_then: impl FnOnce(BlockRef<'c>) -> Result<Vec<Value<'c>>, Error>,
+    build_else: impl FnOnce(BlockRef<'c>) -> Result<Vec<Value<'c>>, Error>,
+) -> Result<Vec<Value<'c>>, Error> {
+    let then_region = build_region(
+        &[],
+        location,
+        |block, _| build_then(block),
+        |values| r#yield(&values, location),
+    )?;
+    let else_region = build_region(
+        &[],
+        location,
+        |block, _| build_else(block),
+        |values| r#yield(&values, location),
+    )?;
+
+    append_operation(
+        block,
+        operation::Builder::new("scf.if", location)
+            .add_operands(&[condition])
+            .add_results(result_types)
+            .add_regions(vec![then_region, else_region]),
+    )
+}
+
+/// Creates a `scf.while` operation at the end of a block.
+///
+/// A `before` callback receives a block and loop-carried values, and returns
+/// a condition and values passed to an `after` region or returned as results.
+/// An `after` callback receives a block and the values, and returns values
+/// yielded to the next iteration. Results of the operation are returned.
+pub fn r#while<'c>(
+    initial_values: &[Value<'c>],
+    result_types: &[Type<'c>],
+    block: &BlockRef<'c>,
+    location: Location<'c>,
+    build_before: impl FnOnce(BlockRef<'c>, &[Value<'c>]) -> Result<(Value<'c>, Vec<Value<'c>>), Error>,
+    build_after: impl FnOnce(BlockRef<'c>, &[Value<'c>]) -> Result<Vec<Value<'c>>, Error>,
+) -> Result<Vec<Value<'c>>, Error> {
+    let before_region = build_region(
+        &value_types(initial_values),
+        location,
+        build_before,
+        |(value, values)| condition(value, &values, location),
+    )?;
+    let after_region = build_region(result_types, location, build_after, |values| {
+        r#yield(&values, location)
+    })?;
+
+    append_operation(
+        block,
+        operation::Builder::new("scf.while", location)
+            .add_operands(initial_values)
+            .add_results(result_types)
+            .add_regions(vec![before_region, after_region]),
+    )
+}
+
+/// Creates a `scf.yield` operation.
+pub fn r#yield<'c>(values: &[Value], location: Location<'c>) -> Result<Operation<'c>, Error> {
+    operation::Builder::new("scf.yield", location)
+        .add_operands(values)
+        .build()
+}
+
+/// Creates a `scf.condition` operation.
+pub fn condition<'c>(
+    condition: Value,
+    values: &[Value],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    operation::Builder::new("scf.condition", location)
+        .add_operands(&[condition])
+        .add_operands(values)
+        .build()
+}
+
+pub(crate) fn build_region<'c, T>(
+    argument_types: &[Type<'c>],
+    location: Location<'c>,
+    build_body: impl FnOnce(BlockRef<'c>, &[Value<'c>]) -> Result<T, Error>,
+    build_terminator: impl FnOnce(T) -> Result<Operation<'c>, Error>,
+) -> Result<Region, Error> {
+    let block = Block::new(
+        &argument_types
+            .iter()
+            .map(|&r#type| (r#type, location))
+            .collect::<Vec<_>>(),
+    );
+    let arguments = (0..block.argument_count())
+        .map(|index| block.argument(index).map(Value::from))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let terminator = build_terminator(build_body(*block, &arguments)?)?;
+    block.append_operation(terminator);
+
+    let region = Region::new();
+    region.append_block(block);
+
+    Ok(region)
+}
+
+pub(crate) fn value_types<'c>(values: &[Value<'c>]) -> Vec<Type<'c>> {
+    values.iter().map(|value| value.r#type()).collect()
+}
+
+fn append_operation<'c>(
+    block: &BlockRef<'c>,
+    builder: operation::Builder<'c>,
+) -> Result<Vec<Value<'c>>, Error> {
+    Ok(block
+        .append_operation(builder.build()?)
+        .results()
+        .map(Value::from)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dialect::func,
+        ir::{Attribute, Identifier, Module},
+        Context,
+    };
+    use indoc::indoc;
+
+    fn constant<'c>(
+        context: &'c Context,
+        block: BlockRef<'c>,
+        value: &str,
+        location: Location<'c>,
+    ) -> Result<Value<'c>, Error> {
+        let attribute = Attribute::parse(context, value).unwrap();
+
+        Ok(block
+            .append_operation(
+                operation::Builder::new("arith.constant", location)
+                    .add_attributes(&[(Identifier::new(context, "value"), attribute)])
+                    .add_results(&[attribute.r#type().unwrap()])
+                    .build()?,
+            )
+            .result(0)?
+            .into())
+    }
+
+    fn binary<'c>(
+        name: &str,
+        block: BlockRef<'c>,
+        lhs: Value,
+        rhs: Value,
+        result_type: Type<'c>,
+        location: Location<'c>,
+    ) -> Result<Value<'c>, Error> {
+        Ok(block
+            .append_operation(
+                operation::Builder::new(name, location)
+                    .add_operands(&[lhs, rhs])
+                    .add_results(&[result_type])
+                    .build()?,
+            )
+            .result(0)?
+            .into())
+    }
+
+    fn build_module<'c>(
+        context: &'c Context,
+        inputs: &[Type<'c>],
+        results: &[Type<'c>],
+        build_body: impl FnOnce(BlockRef<'c>) -> Result<(), Error>,
+    ) -> Module<'c> {
+        let location = Location::unknown(context);
+        let module = Module::new(location);
+
+        module
+            .append_function(
+                func::Builder::new(context, "foo", inputs, results, location),
+                build_body,
+            )
+            .unwrap();
+
+        assert!(module.as_operation().verify());
+
+        module
+    }
+
+    #[test]
+    fn r#for() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let index_type = Type::index(&context);
+
+        let module = build_module(&context, &[index_type], &[index_type], |block| {
+            let zero = constant(&context, block, "0 : index", location)?;
+            let one = constant(&context, block, "1 : index", location)?;
+            let upper_bound = block.argument(0)?.into();
+
+            let results = super::r#for(
+                zero,
+                upper_bound,
+                one,
+                &[zero],
+                &block,
+                location,
+                |block, induction_variable, values| {
+                    Ok(vec![binary(
+                        "arith.addi",
+                        block,
+                        values[0],
+                        induction_variable,
+                        index_type,
+                        location,
+                    )?])
+                },
+            )?;
+
+            block.append_operation(func::r#return(&results, location)?);
+
+            Ok(())
+        });
+
+        assert_eq!(
+            module.as_operation().to_string(),
+            indoc!(
+                "
+                module {
+                  func.func @foo(%arg0: index) -> index {
+                    %c0 = arith.constant 0 : index
+                    %c1 = arith.constant 1 : index
+                    %0 = scf.for %arg1 = %c0 to %arg0 step %c1 iter_args(%arg2 = %c0) -> (index) {
+                      %1 = arith.addi %arg2, %arg1 : index
+                      scf.yield %1 : index
+                    }
+                    return %0 : index
+                  }
+                }
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn r#if() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let index_type = Type::index(&context);
+
+        build_module(
+            &context,
+            &[Type::integer(&context, 1)],
+            &[index_type],
+            |block| {
+                let results = super::r#if(
+                    block.argument(0)?.into(),
+                    &[index_type],
+                    &block,
+                    location,
+                    |block| Ok(vec![constant(&context, block, "1 : index", location)?]),
+                    |block| Ok(vec![constant(&context, block, "2 : index", location)?]),
+                )?;
+
+                block.append_operation(func::r#return(&results, location)?);
+
+                Ok(())
+            },
+        );
+    }
+
+    #[test]
+    fn if_without_results() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+
+        build_module(&context, &[Type::integer(&context, 1)], &[], |block| {
+            assert_eq!(
+                super::r#if(
+                    block.argument(0)?.into(),
+                    &[],
+                    &block,
+                    location,
+                    |_| Ok(vec![]),
+                    |_| Ok(vec![]),
+                )?,
+                vec![]
+            );
+
+            block.append_operation(func::r#return(&[], location)?);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn r#while() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let index_type = Type::index(&context);
+
+        build_module(&context, &[index_type], &[index_type], |block| {
+            let one = constant(&context, block, "1 : index", location)?;
+
+            let results = super::r#while(
+                &[block.argument(0)?.into()],
+                &[index_type],
+                &block,
+                location,
+                |block, values| {
+                    let zero = constant(&context, block, "0 : index", location)?;
+
+                    Ok((
+                        block
+                            .append_operation(
+                                operation::Builder::new("arith.cmpi", location)
+                                    .add_attributes(&[(
+                                        Identifier::new(&context, "predicate"),
+                                        Attribute::parse(&context, "1 : i64").unwrap(),
+                                    )])
+                                    .add_operands(&[values[0], zero])
+                                    .add_results(&[Type::integer(&context, 1)])
+                                    .build()?,
+                            )
+                            .result(0)?
+                            .into(),
+                        values.to_vec(),
+                    ))
+                },
+                |block, values| {
+                    Ok(vec![binary(
+                        "arith.subi",
+                        block,
+                        values[0],
+                        one,
+                        index_type,
+                        location,
+                    )?])
+                },
+            )?;
+
+            block.append_operation(func::r#return(&results, location)?);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn for_with_body_error() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+
+        build_module(&context, &[], &[], |block| {
+            let zero = constant(&context, block, "0 : index", location)?;
+
+            assert_eq!(
+                super::r#for(zero, zero, zero, &[], &block, location, |_, _, _| Err(
+                    Error::InvokeFunction
+                ))
+                .unwrap_err(),
+                Error::InvokeFunction
+            );
+
+            block.append_operation(func::r#return(&[], location)?);
+
+            Ok(())
+        });
+    }
+}
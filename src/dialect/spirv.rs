@@ -0,0 +1,34 @@
+//! The `spv` dialect
+
+use super::Dialect;
+use crate::Context;
+
+/// A namespace of the dialect.
+///
+/// MLIR 15 names the SPIR-V dialect `spv`.
+pub const NAMESPACE: &str = "spv";
+
+// TODO Serialize `spv.module` operations into SPIR-V binaries.
+/// Gets or loads the dialect.
+///
+/// The dialect needs to be registered in a context in advance, e.g. by
+/// [`Context::new`] or
+/// [`register_all_dialects`](crate::utility::register_all_dialects).
+pub fn load_dialect(context: &Context) -> Dialect {
+    context.get_or_load_dialect(NAMESPACE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_dialect() {
+        let context = Context::new();
+
+        assert_eq!(
+            super::load_dialect(&context).namespace().as_str().unwrap(),
+            NAMESPACE
+        );
+    }
+}
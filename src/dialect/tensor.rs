@@ -0,0 +1,289 @@
+//! The `tensor` dialect
+
+use super::memref::{build_view, split_indices, Index};
+use crate::{
+    ir::{
+        operation::Builder,
+        r#type::{MemRef, Shaped},
+        ArrayAttribute, Attribute, Identifier, Location, Operation, Type, TypeLike, Value,
+        ValueLike,
+    },
+    Context, Error,
+};
+use mlir_sys::mlirRankedTensorTypeGet;
+
+/// Creates an empty tensor.
+///
+/// Dynamic sizes are of the `index` type.
+// TODO Create a `tensor.empty` operation. MLIR 15 does not have it, so a
+// `linalg.init_tensor` operation is created instead.
+pub fn empty<'c>(
+    context: &'c Context,
+    sizes: &[Index],
+    element_type: Type<'c>,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    let (static_sizes, dynamic_sizes) = split_indices(sizes, Shaped::DYNAMIC);
+
+    Builder::new("linalg.init_tensor", location)
+        .add_operands(&dynamic_sizes)
+        .add_attributes(&[(
+            Identifier::new(context, "static_sizes"),
+            ArrayAttribute::i64(context, &static_sizes).into(),
+        )])
+        .add_results(&[ranked_tensor_type(&static_sizes, element_type)])
+        .build()
+}
+
+/// Creates a `tensor.extract` operation.
+pub fn extract<'c>(
+    tensor: Value<'c>,
+    indices: &[Value],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    let element_type = Shaped::try_from(tensor.r#type())?.element_type();
+
+    Builder::new("tensor.extract", location)
+        .add_operand(tensor)
+        .add_operands(indices)
+        .add_results(&[element_type])
+        .build()
+}
+
+/// Creates a `tensor.insert` operation.
+pub fn insert<'c>(
+    value: Value,
+    destination: Value<'c>,
+    indices: &[Value],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    Builder::new("tensor.insert", location)
+        .add_operands(&[value, destination])
+        .add_operands(indices)
+        .add_results(&[destination.r#type()])
+        .build()
+}
+
+/// Creates a `tensor.extract_slice` operation.
+///
+/// A result type is inferred from a source type and sizes.
+pub fn extract_slice<'c>(
+    context: &'c Context,
+    source: Value<'c>,
+    offsets: &[Index],
+    sizes: &[Index],
+    strides: &[Index],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    let element_type = Shaped::try_from(source.r#type())?.element_type();
+
+    let (static_offsets, dynamic_offsets) =
+        split_indices(offsets, MemRef::DYNAMIC_STRIDE_OR_OFFSET);
+    let (static_sizes, dynamic_sizes) = split_indices(sizes, Shaped::DYNAMIC);
+    let (static_strides, dynamic_strides) =
+        split_indices(strides, MemRef::DYNAMIC_STRIDE_OR_OFFSET);
+
+    build_view(
+        context,
+        "tensor.extract_slice",
+        &[source],
+        (&static_offsets, &dynamic_offsets),
+        (&static_sizes, &dynamic_sizes),
+        (&static_strides, &dynamic_strides),
+        ranked_tensor_type(&static_sizes, element_type),
+        location,
+    )
+}
+
+/// Creates a `tensor.insert_slice` operation.
+pub fn insert_slice<'c>(
+    context: &'c Context,
+    source: Value,
+    destination: Value<'c>,
+    offsets: &[Index],
+    sizes: &[Index],
+    strides: &[Index],
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    let (static_offsets, dynamic_offsets) =
+        split_indices(offsets, MemRef::DYNAMIC_STRIDE_OR_OFFSET);
+    let (static_sizes, dynamic_sizes) = split_indices(sizes, Shaped::DYNAMIC);
+    let (static_strides, dynamic_strides) =
+        split_indices(strides, MemRef::DYNAMIC_STRIDE_OR_OFFSET);
+
+    build_view(
+        context,
+        "tensor.insert_slice",
+        &[source, destination],
+        (&static_offsets, &dynamic_offsets),
+        (&static_sizes, &dynamic_sizes),
+        (&static_strides, &dynamic_strides),
+        destination.r#type(),
+        location,
+    )
+}
+
+/// Creates a `tensor.cast` operation.
+pub fn cast<'c>(
+    source: Value,
+    r#type: Type<'c>,
+    location: Location<'c>,
+) -> Result<Operation<'c>, Error> {
+    Builder::new("tensor.cast", location)
+        .add_operand(source)
+        .add_results(&[r#type])
+        .build()
+}
+
+fn ranked_tensor_type<'c>(dimensions: &[i64], element_type: Type<'c>) -> Type<'c> {
+    unsafe {
+        Type::from_raw(mlirRankedTensorTypeGet(
+            dimensions.len() as isize,
+            dimensions.as_ptr(),
+            element_type.to_raw(),
+            Attribute::null().to_raw(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Block;
+
+    #[test]
+    fn empty() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+
+        let operation = super::empty(
+            &context,
+            &[
+                Index::Static(4),
+                Index::Dynamic(block.argument(0).unwrap().into()),
+            ],
+            Type::float32(&context),
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(
+            operation.result(0).unwrap().r#type(),
+            Type::parse(&context, "tensor<4x?xf32>").unwrap()
+        );
+    }
+
+    #[test]
+    fn extract_and_insert() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let index_type = Type::index(&context);
+        let block = Block::new(&[
+            (Type::parse(&context, "tensor<4xf32>").unwrap(), location),
+            (index_type, location),
+        ]);
+        let tensor = block.argument(0).unwrap().into();
+        let index = block.argument(1).unwrap().into();
+
+        let extract = block.append_operation(super::extract(tensor, &[index], location).unwrap());
+
+        assert!(extract.verify());
+        assert_eq!(extract.result(0).unwrap().r#type(), Type::float32(&context));
+
+        let insert = super::insert(
+            extract.result(0).unwrap().into(),
+            tensor,
+            &[index],
+            location,
+        )
+        .unwrap();
+
+        assert!(insert.verify());
+    }
+
+    #[test]
+    fn extract_error() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+
+        assert_eq!(
+            super::extract(block.argument(0).unwrap().into(), &[], location).unwrap_err(),
+            Error::ShapedExpected("index".into())
+        );
+    }
+
+    #[test]
+    fn extract_slice() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[
+            (Type::parse(&context, "tensor<8x16xf32>").unwrap(), location),
+            (Type::index(&context), location),
+        ]);
+        let size = block.argument(1).unwrap().into();
+
+        let operation = super::extract_slice(
+            &context,
+            block.argument(0).unwrap().into(),
+            &[Index::Static(0), Index::Static(4)],
+            &[Index::Static(4), Index::Dynamic(size)],
+            &[Index::Static(1), Index::Static(1)],
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(
+            operation.result(0).unwrap().r#type(),
+            Type::parse(&context, "tensor<4x?xf32>").unwrap()
+        );
+    }
+
+    #[test]
+    fn insert_slice() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[
+            (Type::parse(&context, "tensor<4x4xf32>").unwrap(), location),
+            (Type::parse(&context, "tensor<8x16xf32>").unwrap(), location),
+            (Type::index(&context), location),
+        ]);
+        let offset = block.argument(2).unwrap().into();
+
+        let operation = super::insert_slice(
+            &context,
+            block.argument(0).unwrap().into(),
+            block.argument(1).unwrap().into(),
+            &[Index::Dynamic(offset), Index::Static(4)],
+            &[Index::Static(4), Index::Static(4)],
+            &[Index::Static(1), Index::Static(2)],
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+        assert_eq!(
+            operation.result(0).unwrap().r#type(),
+            Type::parse(&context, "tensor<8x16xf32>").unwrap()
+        );
+    }
+
+    #[test]
+    fn cast() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::parse(&context, "tensor<4xf32>").unwrap(), location)]);
+
+        let operation = super::cast(
+            block.argument(0).unwrap().into(),
+            Type::parse(&context, "tensor<?xf32>").unwrap(),
+            location,
+        )
+        .unwrap();
+
+        assert!(operation.verify());
+    }
+}
@@ -1,28 +1,79 @@
+use crate::diagnostic::{CapturedDiagnostic, SourceRange};
 use std::{
     error,
     fmt::{self, Display, Formatter},
 };
 
 /// A Melior error.
+///
+/// Most variants carry printouts of IR objects and positions involved.
+/// Variants of errors reported by MLIR also carry diagnostics captured while
+/// they occur.
 #[derive(Debug, Eq, PartialEq)]
 pub enum Error {
+    AffineMapResultPosition(String, usize),
+    ArrayElementPosition(String, usize),
+    ArrayExpected(String),
     BlockArgumentExpected(String),
     BlockArgumentPosition(String, usize),
+    BuildOperation(String),
+    DialectNotLoaded(String),
+    DimensionCount(usize, usize),
+    DictionaryElementPosition(String, usize),
+    DictionaryExpected(String),
+    DuplicateAttribute(String),
+    DynamicSizeCount(usize, usize),
+    FloatExpected(String),
     FunctionExpected(String),
     FunctionInputPosition(String, usize),
     FunctionResultPosition(String, usize),
+    IndexCount(usize, usize),
+    InlineCall(String),
+    IntegerAttributeExpected(String),
+    IntegerExpected(String),
     InvokeFunction,
+    LookupSymbol(String),
+    MemRefExpected(String),
+    MergeBlock(String),
+    MlirVersionMismatch(String, String),
+    OperationNotRegistered(String),
+    OperationOperandPosition(String, usize),
     OperationResultExpected(String),
     OperationResultPosition(String, usize),
+    OperationSuccessorPosition(String, usize),
+    ParseModule(Option<SourceRange>, String, Vec<CapturedDiagnostic>),
+    ParseOperation(Option<SourceRange>, String, Vec<CapturedDiagnostic>),
     ParsePassPipeline,
-    RunPass,
+    PermutationExpected(String),
+    ReplaceAllSymbolUses(String),
+    ResultCount(usize, usize),
+    RunPass(Vec<CapturedDiagnostic>),
+    ShapedDimensionPosition(String, usize),
+    ShapedExpected(String),
+    SplitBlock(String),
+    StridedLayoutExpected(String),
+    SuccessorOperandCount(usize, usize),
+    SymbolCount(usize, usize),
+    SymbolTableExpected(String),
     TupleExpected(String),
     TupleFieldPosition(String, usize),
+    VerifyOperation(String, Vec<CapturedDiagnostic>),
 }
 
 impl Display for Error {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         match self {
+            Self::AffineMapResultPosition(map, position) => write!(
+                formatter,
+                "affine map result position {} out of range: {}",
+                position, map
+            ),
+            Self::ArrayElementPosition(array, position) => write!(
+                formatter,
+                "array element position {} out of range: {}",
+                position, array
+            ),
+            Self::ArrayExpected(attribute) => write!(formatter, "array expected: {}", attribute),
             Self::BlockArgumentExpected(value) => {
                 write!(formatter, "block argument expected: {}", value)
             }
@@ -33,6 +84,30 @@ impl Display for Error {
                     position, block
                 )
             }
+            Self::BuildOperation(name) => write!(formatter, "failed to build operation: {}", name),
+            Self::DialectNotLoaded(namespace) => {
+                write!(formatter, "dialect not loaded: {}", namespace)
+            }
+            Self::DimensionCount(expected, actual) => write!(
+                formatter,
+                "dimension count mismatch: {} expected but {} given",
+                expected, actual
+            ),
+            Self::DictionaryElementPosition(dictionary, position) => write!(
+                formatter,
+                "dictionary element position {} out of range: {}",
+                position, dictionary
+            ),
+            Self::DictionaryExpected(attribute) => {
+                write!(formatter, "dictionary expected: {}", attribute)
+            }
+            Self::DuplicateAttribute(name) => write!(formatter, "duplicate attribute: {}", name),
+            Self::DynamicSizeCount(expected, actual) => write!(
+                formatter,
+                "dynamic size count mismatch: {} expected but {} given",
+                expected, actual
+            ),
+            Self::FloatExpected(attribute) => write!(formatter, "float expected: {}", attribute),
             Self::FunctionExpected(r#type) => write!(formatter, "function expected: {}", r#type),
             Self::FunctionInputPosition(r#type, position) => write!(
                 formatter,
@@ -44,7 +119,35 @@ impl Display for Error {
                 "function result position {} out of range: {}",
                 position, r#type
             ),
+            Self::IndexCount(expected, actual) => write!(
+                formatter,
+                "index count mismatch: {} expected but {} given",
+                expected, actual
+            ),
+            Self::InlineCall(call) => write!(formatter, "failed to inline call: {}", call),
+            Self::IntegerAttributeExpected(attribute) => {
+                write!(formatter, "integer attribute expected: {}", attribute)
+            }
+            Self::IntegerExpected(r#type) => write!(formatter, "integer expected: {}", r#type),
             Self::InvokeFunction => write!(formatter, "failed to invoke JIT-compiled function"),
+            Self::LookupSymbol(name) => write!(formatter, "failed to look up symbol: {}", name),
+            Self::MemRefExpected(r#type) => write!(formatter, "memref expected: {}", r#type),
+            Self::MergeBlock(block) => write!(formatter, "failed to merge block: {}", block),
+            Self::MlirVersionMismatch(required, actual) => write!(
+                formatter,
+                "MLIR version mismatch: {} required but {} found",
+                required, actual
+            ),
+            Self::OperationNotRegistered(name) => {
+                write!(formatter, "operation not registered: {}", name)
+            }
+            Self::OperationOperandPosition(operation, position) => {
+                write!(
+                    formatter,
+                    "operation operand position {} out of range: {}",
+                    position, operation
+                )
+            }
             Self::OperationResultExpected(value) => {
                 write!(formatter, "operation result expected: {}", value)
             }
@@ -55,8 +158,76 @@ impl Display for Error {
                     position, operation
                 )
             }
+            Self::OperationSuccessorPosition(operation, position) => {
+                write!(
+                    formatter,
+                    "operation successor position {} out of range: {}",
+                    position, operation
+                )
+            }
+            Self::ParseModule(position, message, _) => {
+                write!(formatter, "failed to parse module: ")?;
+
+                if let Some(position) = position {
+                    write!(formatter, "{}: ", position)?;
+                }
+
+                write!(formatter, "{}", message)
+            }
+            Self::ParseOperation(position, message, _) => {
+                write!(formatter, "failed to parse operation: ")?;
+
+                if let Some(position) = position {
+                    write!(formatter, "{}: ", position)?;
+                }
+
+                write!(formatter, "{}", message)
+            }
             Self::ParsePassPipeline => write!(formatter, "failed to parse pass pipeline"),
-            Self::RunPass => write!(formatter, "failed to run pass"),
+            Self::PermutationExpected(positions) => {
+                write!(formatter, "permutation expected: {}", positions)
+            }
+            Self::ReplaceAllSymbolUses(symbol) => {
+                write!(
+                    formatter,
+                    "failed to replace all uses of symbol: {}",
+                    symbol
+                )
+            }
+            Self::ResultCount(expected, actual) => write!(
+                formatter,
+                "result count mismatch: {} expected but {} given",
+                expected, actual
+            ),
+            Self::RunPass(diagnostics) => {
+                write!(formatter, "failed to run pass")?;
+                write_diagnostics(formatter, diagnostics)
+            }
+            Self::ShapedDimensionPosition(r#type, position) => {
+                write!(
+                    formatter,
+                    "shaped type dimension position {} out of range: {}",
+                    position, r#type
+                )
+            }
+            Self::ShapedExpected(r#type) => write!(formatter, "shaped type expected: {}", r#type),
+            Self::SplitBlock(block) => write!(formatter, "failed to split block: {}", block),
+            Self::StridedLayoutExpected(r#type) => {
+                write!(formatter, "strided layout expected: {}", r#type)
+            }
+            Self::SuccessorOperandCount(expected, actual) => write!(
+                formatter,
+                "successor operand count mismatch: {} expected but {} given",
+                expected, actual
+            ),
+            Self::SymbolCount(expected, actual) => write!(
+                formatter,
+                "symbol count mismatch: {} expected but {} given",
+                expected, actual
+            ),
+            Self::SymbolTableExpected(operation) => {
+                write!(formatter, "symbol table expected: {}", operation)
+            }
             Self::TupleExpected(r#type) => write!(formatter, "tuple expected: {}", r#type),
             Self::TupleFieldPosition(r#type, position) => {
                 write!(
@@ -65,8 +236,119 @@ impl Display for Error {
                     position, r#type
                 )
             }
+            Self::VerifyOperation(operation, diagnostics) => {
+                write!(formatter, "failed to verify operation: {}", operation)?;
+                write_diagnostics(formatter, diagnostics)
+            }
+        }
+    }
+}
+
+impl Error {
+    /// Gets diagnostics captured with an error.
+    ///
+    /// Messages of parse errors are taken from their first error diagnostics.
+    pub fn diagnostics(&self) -> &[CapturedDiagnostic] {
+        match self {
+            Self::ParseModule(_, _, diagnostics)
+            | Self::ParseOperation(_, _, diagnostics)
+            | Self::RunPass(diagnostics)
+            | Self::VerifyOperation(_, diagnostics) => diagnostics,
+            _ => &[],
+        }
+    }
+
+    /// Gets an expected kind of an object if the object is of another kind.
+    pub fn expected_kind(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::ArrayExpected(_) => "array",
+            Self::BlockArgumentExpected(_) => "block argument",
+            Self::DictionaryExpected(_) => "dictionary",
+            Self::FloatExpected(_) => "float",
+            Self::FunctionExpected(_) => "function",
+            Self::IntegerAttributeExpected(_) => "integer attribute",
+            Self::IntegerExpected(_) => "integer",
+            Self::MemRefExpected(_) => "memref",
+            Self::OperationResultExpected(_) => "operation result",
+            Self::PermutationExpected(_) => "permutation",
+            Self::ShapedExpected(_) => "shaped type",
+            Self::StridedLayoutExpected(_) => "strided layout",
+            Self::SymbolTableExpected(_) => "symbol table",
+            Self::TupleExpected(_) => "tuple",
+            _ => return None,
+        })
+    }
+
+    /// Gets a printout of an object of an unexpected kind.
+    pub fn actual(&self) -> Option<&str> {
+        match self {
+            Self::ArrayExpected(actual)
+            | Self::BlockArgumentExpected(actual)
+            | Self::DictionaryExpected(actual)
+            | Self::FloatExpected(actual)
+            | Self::FunctionExpected(actual)
+            | Self::IntegerAttributeExpected(actual)
+            | Self::IntegerExpected(actual)
+            | Self::MemRefExpected(actual)
+            | Self::OperationResultExpected(actual)
+            | Self::PermutationExpected(actual)
+            | Self::ShapedExpected(actual)
+            | Self::StridedLayoutExpected(actual)
+            | Self::SymbolTableExpected(actual)
+            | Self::TupleExpected(actual) => Some(actual),
+            _ => None,
         }
     }
 }
 
 impl error::Error for Error {}
+
+fn write_diagnostics(formatter: &mut Formatter, diagnostics: &[CapturedDiagnostic]) -> fmt::Result {
+    for diagnostic in diagnostics {
+        write!(formatter, "\n{}", diagnostic)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{diagnostic::SourcePosition, ir::Location, utility::capture_diagnostics, Context};
+    use std::error::Error as _;
+
+    #[test]
+    fn run_pass() {
+        let context = Context::new();
+        let location = Location::new(&context, "foo.mlir", 1, 2);
+
+        let (_, diagnostics) = capture_diagnostics(location.context(), || {
+            location.emit_error("bar");
+        });
+        let error = Error::RunPass(diagnostics);
+
+        assert_eq!(error.diagnostics().len(), 1);
+        assert_eq!(error.diagnostics()[0].message(), "bar");
+        assert_eq!(
+            error.diagnostics()[0].position(),
+            Some(SourcePosition::new("foo.mlir", 1, 2))
+        );
+        assert!(error.source().is_none());
+        assert_eq!(error.to_string(), "failed to run pass\nfoo.mlir:1:2: bar");
+    }
+
+    #[test]
+    fn run_pass_without_diagnostics() {
+        assert_eq!(Error::RunPass(vec![]).to_string(), "failed to run pass");
+    }
+
+    #[test]
+    fn expected_kind() {
+        let error = Error::FloatExpected("i64".into());
+
+        assert_eq!(error.expected_kind(), Some("float"));
+        assert_eq!(error.actual(), Some("i64"));
+        assert_eq!(Error::InvokeFunction.expected_kind(), None);
+        assert_eq!(Error::InvokeFunction.actual(), None);
+    }
+}
@@ -1,17 +1,29 @@
+mod async_invocation;
+mod jit_function;
+
+pub use self::{async_invocation::AsyncInvocation, jit_function::JitFunction};
 use crate::{ir::Module, logical_result::LogicalResult, string_ref::StringRef, Error};
 use mlir_sys::{
-    mlirExecutionEngineCreate, mlirExecutionEngineDestroy, mlirExecutionEngineInvokePacked,
-    MlirExecutionEngine,
+    mlirExecutionEngineCreate, mlirExecutionEngineDestroy, mlirExecutionEngineDumpToObjectFile,
+    mlirExecutionEngineInvokePacked, mlirExecutionEngineLookup, mlirExecutionEngineLookupPacked,
+    mlirExecutionEngineRegisterSymbol, MlirExecutionEngine,
 };
 use std::ffi::c_void;
 
 /// An execution engine.
+// TODO Support lazy compilation of functions.
 pub struct ExecutionEngine {
     raw: MlirExecutionEngine,
 }
 
 impl ExecutionEngine {
     /// Creates an execution engine.
+    ///
+    /// Debug information is emitted for operations with file locations and
+    /// compiled code is registered with GDB and perf JIT interfaces if LLVM
+    /// supports them, so that JIT-compiled functions can be debugged and
+    /// profiled.
+    // TODO Make JIT event listeners and object dumps optional.
     pub fn new(module: &Module, optimization_level: usize, shared_library_paths: &[&str]) -> Self {
         Self {
             raw: unsafe {
@@ -50,6 +62,109 @@ impl ExecutionEngine {
             Err(Error::InvokeFunction)
         }
     }
+
+    /// Invokes an async function in a module which returns an `!async.token`
+    /// value. The `arguments` argument includes pointers to arguments of the
+    /// function but not to its result.
+    ///
+    /// A returned future resolves when the token becomes available. The
+    /// `mlir_async_runtime` shared library needs to be loaded into the
+    /// execution engine.
+    ///
+    /// # Safety
+    ///
+    /// This function modifies memory locations pointed by the `arguments`
+    /// argument. If those pointers are invalid or misaligned, or a function
+    /// does not return a token, calling this function might result in
+    /// undefined behavior.
+    pub unsafe fn invoke_async(
+        &self,
+        name: &str,
+        arguments: &mut [*mut ()],
+    ) -> Result<AsyncInvocation, Error> {
+        let mut token = std::ptr::null_mut::<c_void>();
+        let mut arguments = arguments.to_vec();
+
+        arguments.push(&mut token as *mut *mut c_void as *mut ());
+        self.invoke_packed(name, &mut arguments)?;
+
+        AsyncInvocation::new(self, token)
+    }
+
+    /// Looks up a function in a module.
+    ///
+    /// A returned pointer can be transmuted into an `extern "C"` function
+    /// pointer and called repeatedly without looking up the function again.
+    pub fn lookup(&self, name: &str) -> Option<*mut ()> {
+        let address =
+            unsafe { mlirExecutionEngineLookup(self.raw, StringRef::from(name).to_raw()) };
+
+        (!address.is_null()).then_some(address as *mut ())
+    }
+
+    /// Looks up a packed interface of a function in a module.
+    ///
+    /// A returned pointer can be transmuted into an `extern "C"` function
+    /// pointer of a type `fn(*mut *mut ())` which receives arguments in the
+    /// same way as [`invoke_packed`](Self::invoke_packed).
+    pub fn lookup_packed(&self, name: &str) -> Option<*mut ()> {
+        let address =
+            unsafe { mlirExecutionEngineLookupPacked(self.raw, StringRef::from(name).to_raw()) };
+
+        (!address.is_null()).then_some(address as *mut ())
+    }
+
+    /// Links symbols defined in another execution engine so that external
+    /// declarations in a module of this engine can refer to them.
+    ///
+    /// This allows modules to be compiled incrementally against a base module
+    /// compiled once. Symbols must be linked before functions referring to
+    /// them are invoked.
+    ///
+    /// # Safety
+    ///
+    /// The other execution engine must outlive this one. Otherwise, calling
+    /// functions referring to the linked symbols results in undefined
+    /// behavior.
+    pub unsafe fn link(&self, engine: &ExecutionEngine, names: &[&str]) -> Result<(), Error> {
+        for &name in names {
+            let address = engine
+                .lookup(name)
+                .ok_or_else(|| Error::LookupSymbol(name.into()))?;
+
+            self.register_symbol(name, address);
+        }
+
+        Ok(())
+    }
+
+    // TODO Check types of symbols against their declarations in modules.
+    /// Registers a symbol so that a module of this engine can refer to it.
+    ///
+    /// This allows JIT-compiled functions to call `extern "C"` functions in
+    /// Rust. Symbols must be registered before functions referring to them
+    /// are invoked.
+    ///
+    /// # Safety
+    ///
+    /// A symbol must point to a function or data with a type matching its
+    /// declaration in a module and live as long as the engine. Otherwise,
+    /// calling functions referring to the symbol results in undefined
+    /// behavior.
+    pub unsafe fn register_symbol(&self, name: &str, address: *mut ()) {
+        mlirExecutionEngineRegisterSymbol(
+            self.raw,
+            StringRef::from(name).to_raw(),
+            address as *mut c_void,
+        );
+    }
+
+    /// Dumps compiled code into an object file.
+    ///
+    /// Only functions already compiled on lookups or invocations are dumped.
+    pub fn dump_to_object_file(&self, path: &str) {
+        unsafe { mlirExecutionEngineDumpToObjectFile(self.raw, StringRef::from(path).to_raw()) }
+    }
 }
 
 impl Drop for ExecutionEngine {
@@ -63,17 +178,36 @@ mod tests {
     use super::*;
     use crate::{
         context::Context,
-        dialect, pass,
-        utility::{register_all_dialects, register_all_llvm_translations},
+        pass::{self, preset},
+        utility::register_all_llvm_translations,
     };
+    use std::{
+        env::{
+            self,
+            consts::{DLL_PREFIX, DLL_SUFFIX},
+        },
+        fs,
+        future::Future,
+        pin::pin,
+        sync::Arc,
+        task::{self, Poll, Wake, Waker},
+        thread,
+    };
+
+    fn lower_module(context: &Context, module: &mut Module) {
+        let pass_manager = pass::Manager::new(context);
+        pass_manager.add_pass(pass::conversion::convert_func_to_llvm());
+
+        pass_manager
+            .nested_under("func.func")
+            .add_pass(pass::conversion::convert_arithmetic_to_llvm());
+
+        assert_eq!(pass_manager.run(module), Ok(()));
+    }
 
     #[test]
     fn invoke_packed() {
-        let registry = dialect::Registry::new();
-        register_all_dialects(&registry);
-
         let context = Context::new();
-        context.append_dialect_registry(&registry);
         register_all_llvm_translations(&context);
 
         let mut module = Module::parse(
@@ -89,14 +223,7 @@ mod tests {
         )
         .unwrap();
 
-        let pass_manager = pass::Manager::new(&context);
-        pass_manager.add_pass(pass::conversion::convert_func_to_llvm());
-
-        pass_manager
-            .nested_under("func.func")
-            .add_pass(pass::conversion::convert_arithmetic_to_llvm());
-
-        assert_eq!(pass_manager.run(&mut module), Ok(()));
+        lower_module(&context, &mut module);
 
         let engine = ExecutionEngine::new(&module, 2, &[]);
 
@@ -119,4 +246,328 @@ mod tests {
         assert_eq!(argument, 42);
         assert_eq!(result, 84);
     }
+
+    #[test]
+    fn lookup() {
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+        let mut module = Module::parse(
+            &context,
+            r#"
+            module {
+                func.func @add(%arg0 : i32) -> i32 attributes { llvm.emit_c_interface } {
+                    %res = arith.addi %arg0, %arg0 : i32
+                    return %res : i32
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        lower_module(&context, &mut module);
+
+        let engine = ExecutionEngine::new(&module, 2, &[]);
+        let add: extern "C" fn(i32) -> i32 =
+            unsafe { std::mem::transmute(engine.lookup("add").unwrap()) };
+
+        assert_eq!(add(1), 2);
+        assert_eq!(add(42), 84);
+        assert_eq!(engine.lookup("foo"), None);
+    }
+
+    #[test]
+    fn lookup_packed() {
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+        let mut module = Module::parse(
+            &context,
+            r#"
+            module {
+                func.func @add(%arg0 : i32) -> i32 attributes { llvm.emit_c_interface } {
+                    %res = arith.addi %arg0, %arg0 : i32
+                    return %res : i32
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        lower_module(&context, &mut module);
+
+        let engine = ExecutionEngine::new(&module, 2, &[]);
+        let add: extern "C" fn(*mut *mut ()) =
+            unsafe { std::mem::transmute(engine.lookup_packed("add").unwrap()) };
+
+        let mut argument = 42;
+        let mut result = -1;
+
+        add([
+            &mut argument as *mut i32 as *mut (),
+            &mut result as *mut i32 as *mut (),
+        ]
+        .as_mut_ptr());
+
+        assert_eq!(result, 84);
+        assert_eq!(engine.lookup_packed("foo"), None);
+    }
+
+    #[test]
+    fn invoke_async() {
+        struct ThreadWaker(thread::Thread);
+
+        impl Wake for ThreadWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+
+        let mut module = Module::parse(
+            &context,
+            r#"
+            module {
+                func.func @main() -> !async.token attributes { llvm.emit_c_interface } {
+                    %token = async.execute {
+                        %value = arith.constant 42 : i32
+                        async.yield
+                    }
+                    return %token : !async.token
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(preset::async_lowering(&context).run(&mut module), Ok(()));
+
+        let runtime = format!(
+            "{}/{}mlir_async_runtime{}",
+            env!("LLVM_LIBRARY_DIRECTORY"),
+            DLL_PREFIX,
+            DLL_SUFFIX
+        );
+        let engine = ExecutionEngine::new(&module, 2, &[&runtime]);
+
+        let future = unsafe { engine.invoke_async("main", &mut []).unwrap() };
+
+        fn assert_send(_: &impl Send) {}
+        assert_send(&future);
+
+        let mut future = pin!(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut task_context = task::Context::from_waker(&waker);
+
+        let result = loop {
+            if let Poll::Ready(result) = future.as_mut().poll(&mut task_context) {
+                break result;
+            }
+
+            thread::park();
+        };
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn invoke_jit() {
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+        let mut module = Module::parse(
+            &context,
+            r#"
+            module {
+                func.func @add(%arg0 : i64, %arg1 : i64) -> i64 {
+                    %res = arith.addi %arg0, %arg1 : i64
+                    return %res : i64
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        lower_module(&context, &mut module);
+
+        let engine = ExecutionEngine::new(&module, 2, &[]);
+        let add = unsafe { crate::invoke_jit!(engine, fn add(i64, i64) -> i64) }.unwrap();
+
+        assert_eq!(add.call(1, 2), 3);
+        assert_eq!(add.call(40, 2), 42);
+    }
+
+    #[test]
+    fn invoke_jit_without_function() {
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+        let mut module = Module::parse(&context, "module {}").unwrap();
+
+        lower_module(&context, &mut module);
+
+        let engine = ExecutionEngine::new(&module, 2, &[]);
+
+        assert!(matches!(
+            unsafe { crate::invoke_jit!(engine, fn foo()) },
+            Err(Error::LookupSymbol(name)) if name == "foo"
+        ));
+    }
+
+    #[test]
+    fn link() {
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+
+        let mut base_module = Module::parse(
+            &context,
+            r#"
+            module {
+                func.func @add_one(%arg0 : i32) -> i32 {
+                    %one = arith.constant 1 : i32
+                    %res = arith.addi %arg0, %one : i32
+                    return %res : i32
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        let mut module = Module::parse(
+            &context,
+            r#"
+            module {
+                func.func private @add_one(i32) -> i32
+
+                func.func @add_two(%arg0 : i32) -> i32 attributes { llvm.emit_c_interface } {
+                    %tmp = func.call @add_one(%arg0) : (i32) -> i32
+                    %res = func.call @add_one(%tmp) : (i32) -> i32
+                    return %res : i32
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        lower_module(&context, &mut base_module);
+        lower_module(&context, &mut module);
+
+        let base_engine = ExecutionEngine::new(&base_module, 2, &[]);
+        let engine = ExecutionEngine::new(&module, 2, &[]);
+
+        assert_eq!(unsafe { engine.link(&base_engine, &["add_one"]) }, Ok(()));
+
+        let mut argument = 42;
+        let mut result = -1;
+
+        assert_eq!(
+            unsafe {
+                engine.invoke_packed(
+                    "add_two",
+                    &mut [
+                        &mut argument as *mut i32 as *mut (),
+                        &mut result as *mut i32 as *mut (),
+                    ],
+                )
+            },
+            Ok(())
+        );
+
+        assert_eq!(result, 44);
+    }
+
+    #[test]
+    fn link_error() {
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+        let mut module = Module::parse(&context, "module {}").unwrap();
+
+        lower_module(&context, &mut module);
+
+        let base_engine = ExecutionEngine::new(&module, 2, &[]);
+        let engine = ExecutionEngine::new(&module, 2, &[]);
+
+        assert_eq!(
+            unsafe { engine.link(&base_engine, &["foo"]) },
+            Err(Error::LookupSymbol("foo".into()))
+        );
+    }
+
+    #[test]
+    fn register_symbol() {
+        extern "C" fn add_one(value: i32) -> i32 {
+            value + 1
+        }
+
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+
+        let mut module = Module::parse(
+            &context,
+            r#"
+            module {
+                func.func private @add_one(i32) -> i32
+
+                func.func @add_two(%arg0 : i32) -> i32 attributes { llvm.emit_c_interface } {
+                    %tmp = func.call @add_one(%arg0) : (i32) -> i32
+                    %res = func.call @add_one(%tmp) : (i32) -> i32
+                    return %res : i32
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        lower_module(&context, &mut module);
+
+        let engine = ExecutionEngine::new(&module, 2, &[]);
+
+        unsafe { engine.register_symbol("add_one", add_one as *mut ()) };
+
+        let mut argument = 42;
+        let mut result = -1;
+
+        assert_eq!(
+            unsafe {
+                engine.invoke_packed(
+                    "add_two",
+                    &mut [
+                        &mut argument as *mut i32 as *mut (),
+                        &mut result as *mut i32 as *mut (),
+                    ],
+                )
+            },
+            Ok(())
+        );
+
+        assert_eq!(result, 44);
+    }
+
+    #[test]
+    fn dump_to_object_file() {
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+
+        let mut module = Module::parse(
+            &context,
+            r#"
+            module {
+                func.func @foo() attributes { llvm.emit_c_interface } {
+                    return
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        lower_module(&context, &mut module);
+
+        let engine = ExecutionEngine::new(&module, 2, &[]);
+        let path = env::temp_dir().join("melior_execution_engine_dump_to_object_file.o");
+
+        assert_eq!(unsafe { engine.invoke_packed("foo", &mut []) }, Ok(()));
+
+        engine.dump_to_object_file(path.to_str().unwrap());
+
+        assert!(fs::metadata(&path).unwrap().len() > 0);
+
+        fs::remove_file(path).unwrap();
+    }
 }
@@ -0,0 +1,127 @@
+use super::ExecutionEngine;
+use crate::Error;
+use std::{
+    ffi::c_void,
+    future::Future,
+    marker::PhantomData,
+    mem::transmute,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+const AWAIT_TOKEN_AND_EXECUTE: &str = "mlirAsyncRuntimeAwaitTokenAndExecute";
+const IS_TOKEN_ERROR: &str = "mlirAsyncRuntimeIsTokenError";
+const DROP_REFERENCE: &str = "mlirAsyncRuntimeDropRef";
+
+type AwaitTokenAndExecute =
+    unsafe extern "C" fn(*mut c_void, *mut c_void, extern "C" fn(*mut c_void));
+type IsTokenError = unsafe extern "C" fn(*mut c_void) -> bool;
+type DropReference = unsafe extern "C" fn(*mut c_void, i64);
+
+#[derive(Default)]
+struct State {
+    completed: bool,
+    waker: Option<Waker>,
+}
+
+/// A future of an invocation of a JIT-compiled function returning an
+/// `!async.token` value.
+///
+/// It resolves when an async runtime of MLIR marks the token available or
+/// erroneous.
+pub struct AsyncInvocation<'a> {
+    token: *mut c_void,
+    state: Arc<Mutex<State>>,
+    is_token_error: IsTokenError,
+    drop_reference: DropReference,
+    _engine: PhantomData<&'a ExecutionEngine>,
+}
+
+impl<'a> AsyncInvocation<'a> {
+    pub(crate) unsafe fn new(
+        engine: &'a ExecutionEngine,
+        token: *mut c_void,
+    ) -> Result<Self, Error> {
+        let drop_reference =
+            transmute::<*mut (), DropReference>(lookup_runtime_function(engine, DROP_REFERENCE)?);
+        let functions = lookup_runtime_function(engine, AWAIT_TOKEN_AND_EXECUTE).and_then(
+            |await_token_and_execute| {
+                Ok((
+                    await_token_and_execute,
+                    lookup_runtime_function(engine, IS_TOKEN_ERROR)?,
+                ))
+            },
+        );
+        let (await_token_and_execute, is_token_error) = match functions {
+            Ok(functions) => functions,
+            Err(error) => {
+                drop_reference(token, 1);
+                return Err(error);
+            }
+        };
+
+        let invocation = Self {
+            token,
+            state: Default::default(),
+            is_token_error: transmute::<*mut (), IsTokenError>(is_token_error),
+            drop_reference,
+            _engine: Default::default(),
+        };
+
+        transmute::<*mut (), AwaitTokenAndExecute>(await_token_and_execute)(
+            token,
+            Arc::into_raw(invocation.state.clone()) as *mut c_void,
+            resume,
+        );
+
+        Ok(invocation)
+    }
+}
+
+// Tokens are reference-counted atomically by the async runtime and the
+// execution engine is only borrowed to keep its compiled code alive.
+unsafe impl<'a> Send for AsyncInvocation<'a> {}
+
+impl<'a> Future for AsyncInvocation<'a> {
+    type Output = Result<(), Error>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.completed {
+            Poll::Ready(if unsafe { (self.is_token_error)(self.token) } {
+                Err(Error::InvokeFunction)
+            } else {
+                Ok(())
+            })
+        } else {
+            state.waker = Some(context.waker().clone());
+
+            Poll::Pending
+        }
+    }
+}
+
+impl<'a> Drop for AsyncInvocation<'a> {
+    fn drop(&mut self) {
+        unsafe { (self.drop_reference)(self.token, 1) }
+    }
+}
+
+extern "C" fn resume(state: *mut c_void) {
+    let state = unsafe { Arc::from_raw(state as *const Mutex<State>) };
+    let mut state = state.lock().unwrap();
+
+    state.completed = true;
+
+    if let Some(waker) = state.waker.take() {
+        waker.wake();
+    }
+}
+
+fn lookup_runtime_function(engine: &ExecutionEngine, name: &str) -> Result<*mut (), Error> {
+    engine
+        .lookup(name)
+        .ok_or_else(|| Error::LookupSymbol(name.into()))
+}
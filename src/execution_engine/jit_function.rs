@@ -0,0 +1,115 @@
+use super::ExecutionEngine;
+use crate::Error;
+use std::{
+    marker::PhantomData,
+    mem::{transmute, MaybeUninit},
+};
+
+/// Creates a typed function of a JIT-compiled function in an execution
+/// engine.
+///
+/// It looks up a packed interface of the function once and fails with
+/// [`Error::LookupSymbol`] if it does not exist.
+///
+/// # Safety
+///
+/// The macro expands to an unsafe expression. A given signature must match
+/// the one of the JIT-compiled function.
+///
+/// # Examples
+///
+/// ```rust
+/// use melior::{
+///     invoke_jit, ir::Module, pass, utility::register_all_llvm_translations, Context,
+///     ExecutionEngine,
+/// };
+///
+/// let context = Context::new();
+/// register_all_llvm_translations(&context);
+///
+/// let mut module = Module::parse(
+///     &context,
+///     r#"
+///     func.func @add(%arg0 : i64, %arg1 : i64) -> i64 {
+///         %res = arith.addi %arg0, %arg1 : i64
+///         return %res : i64
+///     }
+///     "#,
+/// )
+/// .unwrap();
+///
+/// pass::preset::cpu_jit(&context).run(&mut module).unwrap();
+///
+/// let engine = ExecutionEngine::new(&module, 2, &[]);
+/// let add = unsafe { invoke_jit!(engine, fn add(i64, i64) -> i64) }.unwrap();
+///
+/// assert_eq!(add.call(1, 2), 3);
+/// ```
+#[macro_export]
+macro_rules! invoke_jit {
+    ($engine:expr, fn $name:ident($($argument:ty),* $(,)?) $(-> $result:ty)?) => {
+        $crate::JitFunction::<fn($($argument),*) $(-> $result)?>::new(
+            &$engine,
+            stringify!($name),
+        )
+    };
+}
+
+/// A typed function JIT-compiled by an execution engine.
+pub struct JitFunction<'a, F> {
+    function: extern "C" fn(*mut *mut ()),
+    _engine: PhantomData<&'a ExecutionEngine>,
+    _signature: PhantomData<F>,
+}
+
+impl<'a, F> JitFunction<'a, F> {
+    /// Creates a typed function.
+    ///
+    /// # Safety
+    ///
+    /// A signature of a type `F` must match the one of a JIT-compiled
+    /// function.
+    pub unsafe fn new(engine: &'a ExecutionEngine, name: &str) -> Result<Self, Error> {
+        Ok(Self {
+            function: transmute::<*mut (), extern "C" fn(*mut *mut ())>(
+                engine
+                    .lookup_packed(name)
+                    .ok_or_else(|| Error::LookupSymbol(name.into()))?,
+            ),
+            _engine: Default::default(),
+            _signature: Default::default(),
+        })
+    }
+}
+
+macro_rules! impl_call {
+    ($($argument:ident: $type:ident),*) => {
+        impl<'a, $($type,)* R> JitFunction<'a, fn($($type),*) -> R> {
+            /// Calls a function.
+            #[allow(clippy::too_many_arguments)]
+            pub fn call(&self, $(mut $argument: $type),*) -> R {
+                let mut result = MaybeUninit::<R>::uninit();
+
+                (self.function)(
+                    [
+                        $(&mut $argument as *mut $type as *mut (),)*
+                        result.as_mut_ptr() as *mut (),
+                    ]
+                    .as_mut_ptr(),
+                );
+
+                unsafe { result.assume_init() }
+            }
+        }
+    };
+}
+
+impl_call!();
+impl_call!(a0: A0);
+impl_call!(a0: A0, a1: A1);
+impl_call!(a0: A0, a1: A1, a2: A2);
+impl_call!(a0: A0, a1: A1, a2: A2, a3: A3);
+impl_call!(a0: A0, a1: A1, a2: A2, a3: A3, a4: A4);
+impl_call!(a0: A0, a1: A1, a2: A2, a3: A3, a4: A4, a5: A5);
+impl_call!(a0: A0, a1: A1, a2: A2, a3: A3, a4: A4, a5: A5, a6: A6);
+impl_call!(a0: A0, a1: A1, a2: A2, a3: A3, a4: A4, a5: A5, a6: A6, a7: A7);
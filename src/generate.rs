@@ -0,0 +1,221 @@
+//! Random IR generation for fuzzing and property tests.
+//!
+//! Generators are deterministic for given seeds so that fuzzers can drive them
+//! with their own inputs and failures can be reproduced.
+
+use crate::{
+    dialect::func,
+    ir::{operation, Attribute, Identifier, Location, Module, Operation, Type, Value},
+    Context, Error,
+};
+
+// TODO Generate operations of any loaded dialects.
+const BINARY_OPERATIONS: &[&str] = &[
+    "arith.addi",
+    "arith.andi",
+    "arith.muli",
+    "arith.ori",
+    "arith.subi",
+    "arith.xori",
+];
+const INTEGER_WIDTHS: &[u32] = &[1, 8, 16, 32, 64];
+
+/// A generator of random small well-typed modules.
+///
+/// Modules consist of `func` and `arith` dialect operations on integers and so
+/// the dialects need to be loaded into a context.
+pub struct Generator<'c> {
+    context: &'c Context,
+    state: u64,
+    function_count: usize,
+    operation_count: usize,
+}
+
+impl<'c> Generator<'c> {
+    /// Creates a generator with a seed.
+    pub fn new(context: &'c Context, seed: u64) -> Self {
+        Self {
+            context,
+            // Zero is a fixed point of xorshift.
+            state: seed | 1,
+            function_count: 4,
+            operation_count: 16,
+        }
+    }
+
+    /// Sets a maximum number of functions in a module.
+    pub fn function_count(mut self, count: usize) -> Self {
+        self.function_count = count;
+        self
+    }
+
+    /// Sets a maximum number of operations in a function.
+    pub fn operation_count(mut self, count: usize) -> Self {
+        self.operation_count = count;
+        self
+    }
+
+    /// Generates a module.
+    pub fn module(&mut self) -> Result<Module<'c>, Error> {
+        for name in ["func.func", "func.return", "arith.constant"]
+            .iter()
+            .chain(BINARY_OPERATIONS)
+        {
+            self.context.check_operation_name(name)?;
+        }
+
+        let location = Location::unknown(self.context);
+        let module = Module::new(location);
+
+        let function_count = if self.function_count == 0 {
+            0
+        } else {
+            self.choose(self.function_count) + 1
+        };
+
+        for index in 0..function_count {
+            module
+                .body()
+                .append_operation(self.function(&format!("f{}", index), location)?);
+        }
+
+        Ok(module)
+    }
+
+    fn function(&mut self, name: &str, location: Location<'c>) -> Result<Operation<'c>, Error> {
+        let context = self.context;
+        let width = INTEGER_WIDTHS[self.choose(INTEGER_WIDTHS.len())];
+        let r#type = Type::integer(context, width);
+        let inputs = vec![r#type; self.choose(3)];
+        let operation_count = self.choose(self.operation_count + 1);
+
+        func::Builder::new(context, name, &inputs, &[r#type], location).build(|block| {
+            let mut values = (0..inputs.len())
+                .map(|index| block.argument(index).map(Value::from))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if values.is_empty() || self.choose(4) == 0 {
+                // Values must fit in the integer type.
+                let value = self.next() % (1 << width.min(7));
+
+                values.push(
+                    block
+                        .append_operation(
+                            operation::Builder::new("arith.constant", location)
+                                .add_attributes(&[(
+                                    Identifier::new(context, "value"),
+                                    Attribute::parse(context, &format!("{} : {}", value, r#type))
+                                        .ok_or(Error::BuildOperation("arith.constant".into()))?,
+                                )])
+                                .add_results(&[r#type])
+                                .build()?,
+                        )
+                        .result(0)?
+                        .into(),
+                );
+            }
+
+            for _ in 0..operation_count {
+                let lhs = values[self.choose(values.len())];
+                let rhs = values[self.choose(values.len())];
+
+                values.push(
+                    block
+                        .append_operation(
+                            operation::Builder::new(
+                                BINARY_OPERATIONS[self.choose(BINARY_OPERATIONS.len())],
+                                location,
+                            )
+                            .add_operands(&[lhs, rhs])
+                            .add_results(&[r#type])
+                            .build()?,
+                        )
+                        .result(0)?
+                        .into(),
+                );
+            }
+
+            block.append_operation(func::r#return(&[values[values.len() - 1]], location)?);
+
+            Ok(())
+        })
+    }
+
+    fn choose(&mut self, count: usize) -> usize {
+        (self.next() % count.max(1) as u64) as usize
+    }
+
+    // xorshift64
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module() {
+        let context = Context::new();
+
+        for seed in 0..64 {
+            assert!(Generator::new(&context, seed)
+                .module()
+                .unwrap()
+                .as_operation()
+                .verify());
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        let context = Context::new();
+
+        assert_eq!(
+            Generator::new(&context, 42)
+                .module()
+                .unwrap()
+                .as_operation()
+                .to_string(),
+            Generator::new(&context, 42)
+                .module()
+                .unwrap()
+                .as_operation()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn operation_count() {
+        let context = Context::new();
+        let module = Generator::new(&context, 42)
+            .function_count(1)
+            .operation_count(0)
+            .module()
+            .unwrap();
+
+        assert_eq!(module.body().operation_count(), 1);
+    }
+
+    #[test]
+    fn function_count() {
+        let context = Context::new();
+        let module = Generator::new(&context, 42)
+            .function_count(0)
+            .module()
+            .unwrap();
+
+        assert_eq!(module.body().operation_count(), 0);
+    }
+
+    #[test]
+    fn dialect_not_loaded() {
+        let context = unsafe { Context::new_unchecked() };
+
+        assert!(Generator::new(&context, 42).module().is_err());
+    }
+}
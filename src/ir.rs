@@ -1,23 +1,39 @@
 //! IR objects and builders.
 
+mod affine_expr;
+mod affine_map;
+mod alias_table;
 mod attribute;
 pub mod block;
+mod constant_pool;
+pub(crate) mod generation;
 mod identifier;
+mod integer_set;
 mod location;
 mod module;
 pub mod operation;
+mod owned_module;
 mod region;
+mod symbol_table;
 pub mod r#type;
 mod value;
+pub mod walk;
 
 pub use self::{
-    attribute::Attribute,
+    affine_expr::AffineExpr,
+    affine_map::AffineMap,
+    alias_table::AliasTable,
+    attribute::{ArrayAttribute, Attribute, DictionaryAttribute, FloatAttribute, IntegerAttribute},
     block::{Block, BlockRef},
+    constant_pool::ConstantPool,
     identifier::Identifier,
+    integer_set::IntegerSet,
     location::Location,
     module::Module,
     operation::{Operation, OperationRef},
+    owned_module::OwnedModule,
     r#type::{Type, TypeLike},
     region::{Region, RegionRef},
+    symbol_table::SymbolTable,
     value::{Value, ValueLike},
 };
@@ -0,0 +1,585 @@
+use super::AffineMap;
+use crate::{
+    context::{Context, ContextRef},
+    utility::print_callback,
+};
+use mlir_sys::{
+    mlirAffineAddExprGet, mlirAffineBinaryOpExprGetLHS, mlirAffineBinaryOpExprGetRHS,
+    mlirAffineCeilDivExprGet, mlirAffineConstantExprGet, mlirAffineConstantExprGetValue,
+    mlirAffineDimExprGet, mlirAffineDimExprGetPosition, mlirAffineExprCompose, mlirAffineExprEqual,
+    mlirAffineExprGetContext, mlirAffineExprGetLargestKnownDivisor, mlirAffineExprIsAAdd,
+    mlirAffineExprIsABinary, mlirAffineExprIsACeilDiv, mlirAffineExprIsAConstant,
+    mlirAffineExprIsADim, mlirAffineExprIsAFloorDiv, mlirAffineExprIsAMod, mlirAffineExprIsAMul,
+    mlirAffineExprIsASymbol, mlirAffineExprIsFunctionOfDim, mlirAffineExprIsMultipleOf,
+    mlirAffineExprIsPureAffine, mlirAffineExprIsSymbolicOrConstant, mlirAffineExprPrint,
+    mlirAffineFloorDivExprGet, mlirAffineModExprGet, mlirAffineMulExprGet, mlirAffineSymbolExprGet,
+    mlirAffineSymbolExprGetPosition, MlirAffineExpr,
+};
+use std::{
+    ffi::c_void,
+    fmt::{self, Debug, Display, Formatter},
+    marker::PhantomData,
+    ops::{Add, Mul, Neg, Rem, Sub},
+};
+
+/// An affine expression.
+///
+/// Expressions are built with the arithmetic operators and simplified on
+/// construction, e.g. constants are folded and nested additions and
+/// multiplications of constants are merged.
+// TODO Simplify expressions with `simplifyAffineExpr`.
+// Affine expressions are always values but their internal storage is owned by
+// contexts.
+#[derive(Clone, Copy)]
+pub struct AffineExpr<'c> {
+    raw: MlirAffineExpr,
+    _context: PhantomData<&'c Context>,
+}
+
+impl<'c> AffineExpr<'c> {
+    /// Creates a dimension expression.
+    pub fn dimension(context: &'c Context, position: usize) -> Self {
+        unsafe { Self::from_raw(mlirAffineDimExprGet(context.to_raw(), position as isize)) }
+    }
+
+    /// Creates a symbol expression.
+    pub fn symbol(context: &'c Context, position: usize) -> Self {
+        unsafe { Self::from_raw(mlirAffineSymbolExprGet(context.to_raw(), position as isize)) }
+    }
+
+    /// Creates a constant expression.
+    pub fn constant(context: &'c Context, value: i64) -> Self {
+        unsafe { Self::from_raw(mlirAffineConstantExprGet(context.to_raw(), value)) }
+    }
+
+    /// Creates a floor division expression.
+    pub fn floor_div(self, other: Self) -> Self {
+        unsafe { Self::from_raw(mlirAffineFloorDivExprGet(self.raw, other.raw)) }
+    }
+
+    /// Creates a ceil division expression.
+    pub fn ceil_div(self, other: Self) -> Self {
+        unsafe { Self::from_raw(mlirAffineCeilDivExprGet(self.raw, other.raw)) }
+    }
+
+    /// Creates a modulo expression.
+    pub fn modulo(self, other: Self) -> Self {
+        self % other
+    }
+
+    /// Composes an expression with an affine map.
+    ///
+    /// Dimensions and symbols of the expression are replaced with results of
+    /// the map.
+    pub fn compose(self, map: AffineMap<'c>) -> Self {
+        unsafe { Self::from_raw(mlirAffineExprCompose(self.raw, map.to_raw())) }
+    }
+
+    /// Gets a context.
+    pub fn context(&self) -> ContextRef<'c> {
+        unsafe { ContextRef::from_raw(mlirAffineExprGetContext(self.raw)) }
+    }
+
+    /// Gets a position of a dimension expression.
+    pub fn dimension_position(&self) -> Option<usize> {
+        if self.is_dimension() {
+            Some(unsafe { mlirAffineDimExprGetPosition(self.raw) as usize })
+        } else {
+            None
+        }
+    }
+
+    /// Gets a position of a symbol expression.
+    pub fn symbol_position(&self) -> Option<usize> {
+        if self.is_symbol() {
+            Some(unsafe { mlirAffineSymbolExprGetPosition(self.raw) as usize })
+        } else {
+            None
+        }
+    }
+
+    /// Gets a value of a constant expression.
+    pub fn constant_value(&self) -> Option<i64> {
+        if self.is_constant() {
+            Some(unsafe { mlirAffineConstantExprGetValue(self.raw) })
+        } else {
+            None
+        }
+    }
+
+    /// Gets a left-hand side of a binary expression.
+    pub fn lhs(&self) -> Option<Self> {
+        if self.is_binary() {
+            Some(unsafe { Self::from_raw(mlirAffineBinaryOpExprGetLHS(self.raw)) })
+        } else {
+            None
+        }
+    }
+
+    /// Gets a right-hand side of a binary expression.
+    pub fn rhs(&self) -> Option<Self> {
+        if self.is_binary() {
+            Some(unsafe { Self::from_raw(mlirAffineBinaryOpExprGetRHS(self.raw)) })
+        } else {
+            None
+        }
+    }
+
+    /// Gets the largest known divisor.
+    pub fn largest_known_divisor(&self) -> i64 {
+        unsafe { mlirAffineExprGetLargestKnownDivisor(self.raw) }
+    }
+
+    /// Returns `true` if an expression is a multiple of a factor.
+    pub fn is_multiple_of(&self, factor: i64) -> bool {
+        unsafe { mlirAffineExprIsMultipleOf(self.raw, factor) }
+    }
+
+    /// Returns `true` if an expression depends on a dimension.
+    pub fn is_function_of_dimension(&self, position: usize) -> bool {
+        unsafe { mlirAffineExprIsFunctionOfDim(self.raw, position as isize) }
+    }
+
+    /// Returns `true` if an expression is pure affine.
+    pub fn is_pure_affine(&self) -> bool {
+        unsafe { mlirAffineExprIsPureAffine(self.raw) }
+    }
+
+    /// Returns `true` if an expression is made of only symbols and constants.
+    pub fn is_symbolic_or_constant(&self) -> bool {
+        unsafe { mlirAffineExprIsSymbolicOrConstant(self.raw) }
+    }
+
+    /// Returns `true` if an expression is a dimension.
+    pub fn is_dimension(&self) -> bool {
+        unsafe { mlirAffineExprIsADim(self.raw) }
+    }
+
+    /// Returns `true` if an expression is a symbol.
+    pub fn is_symbol(&self) -> bool {
+        unsafe { mlirAffineExprIsASymbol(self.raw) }
+    }
+
+    /// Returns `true` if an expression is a constant.
+    pub fn is_constant(&self) -> bool {
+        unsafe { mlirAffineExprIsAConstant(self.raw) }
+    }
+
+    /// Returns `true` if an expression is a binary operation.
+    pub fn is_binary(&self) -> bool {
+        unsafe { mlirAffineExprIsABinary(self.raw) }
+    }
+
+    /// Returns `true` if an expression is an addition.
+    pub fn is_add(&self) -> bool {
+        unsafe { mlirAffineExprIsAAdd(self.raw) }
+    }
+
+    /// Returns `true` if an expression is a multiplication.
+    pub fn is_mul(&self) -> bool {
+        unsafe { mlirAffineExprIsAMul(self.raw) }
+    }
+
+    /// Returns `true` if an expression is a modulo operation.
+    pub fn is_mod(&self) -> bool {
+        unsafe { mlirAffineExprIsAMod(self.raw) }
+    }
+
+    /// Returns `true` if an expression is a floor division.
+    pub fn is_floor_div(&self) -> bool {
+        unsafe { mlirAffineExprIsAFloorDiv(self.raw) }
+    }
+
+    /// Returns `true` if an expression is a ceil division.
+    pub fn is_ceil_div(&self) -> bool {
+        unsafe { mlirAffineExprIsACeilDiv(self.raw) }
+    }
+
+    // Creates a constant expression in the same context.
+    fn constant_like(&self, value: i64) -> Self {
+        unsafe {
+            Self::from_raw(mlirAffineConstantExprGet(
+                mlirAffineExprGetContext(self.raw),
+                value,
+            ))
+        }
+    }
+
+    pub(crate) unsafe fn from_raw(raw: MlirAffineExpr) -> Self {
+        Self {
+            raw,
+            _context: Default::default(),
+        }
+    }
+
+    pub(crate) unsafe fn to_raw(self) -> MlirAffineExpr {
+        self.raw
+    }
+}
+
+macro_rules! impl_binary_operator {
+    ($trait:ident, $method:ident, $function:ident $(, $is_kind:ident, $fold:ident)?) => {
+        impl<'c> $trait for AffineExpr<'c> {
+            type Output = Self;
+
+            fn $method(self, other: Self) -> Self {
+                $(check_folding(self, other, |expression| expression.$is_kind(), i64::$fold);)?
+
+                unsafe { Self::from_raw($function(self.raw, other.raw)) }
+            }
+        }
+
+        impl<'c> $trait<i64> for AffineExpr<'c> {
+            type Output = Self;
+
+            fn $method(self, other: i64) -> Self {
+                self.$method(self.constant_like(other))
+            }
+        }
+    };
+}
+
+impl_binary_operator!(Add, add, mlirAffineAddExprGet, is_add, checked_add);
+impl_binary_operator!(Mul, mul, mlirAffineMulExprGet, is_mul, checked_mul);
+impl_binary_operator!(Rem, rem, mlirAffineModExprGet);
+
+impl<'c> Sub for AffineExpr<'c> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl<'c> Sub<i64> for AffineExpr<'c> {
+    type Output = Self;
+
+    fn sub(self, other: i64) -> Self {
+        self.add(checked_neg(other))
+    }
+}
+
+impl<'c> Neg for AffineExpr<'c> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        match self.constant_value() {
+            // MLIR folds constants without overflow checks.
+            Some(value) => self.constant_like(checked_neg(value)),
+            None => self * -1,
+        }
+    }
+}
+
+fn checked_neg(value: i64) -> i64 {
+    value
+        .checked_neg()
+        .expect("attempt to negate affine constant with overflow")
+}
+
+// MLIR folds constants of binary expressions, e.g. `c1 + c2` and
+// `(d0 + c1) + c2`, without overflow checks.
+fn check_folding(
+    lhs: AffineExpr,
+    rhs: AffineExpr,
+    is_kind: impl Fn(&AffineExpr) -> bool,
+    fold: fn(i64, i64) -> Option<i64>,
+) {
+    let folded = match (lhs.constant_value(), rhs.constant_value()) {
+        (Some(lhs), Some(rhs)) => fold(lhs, rhs),
+        (Some(constant), None) | (None, Some(constant)) => {
+            let expression = if lhs.is_constant() { rhs } else { lhs };
+
+            match expression.rhs().and_then(|rhs| rhs.constant_value()) {
+                Some(other) if is_kind(&expression) => fold(other, constant),
+                _ => Some(constant),
+            }
+        }
+        (None, None) => return,
+    };
+
+    folded.expect("attempt to fold affine constants with overflow");
+}
+
+impl<'c> PartialEq for AffineExpr<'c> {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { mlirAffineExprEqual(self.raw, other.raw) }
+    }
+}
+
+impl<'c> Eq for AffineExpr<'c> {}
+
+impl<'c> Display for AffineExpr<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let mut data = (formatter, Ok(()));
+
+        unsafe {
+            mlirAffineExprPrint(
+                self.raw,
+                Some(print_callback),
+                &mut data as *mut _ as *mut c_void,
+            );
+        }
+
+        data.1
+    }
+}
+
+impl<'c> Debug for AffineExpr<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "AffineExpr(")?;
+        Display::fmt(self, formatter)?;
+        write!(formatter, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dimension() {
+        let context = Context::new();
+        let expression = AffineExpr::dimension(&context, 1);
+
+        assert!(expression.is_dimension());
+        assert_eq!(expression.dimension_position(), Some(1));
+        assert_eq!(expression.to_string(), "d1");
+    }
+
+    #[test]
+    fn symbol() {
+        let context = Context::new();
+        let expression = AffineExpr::symbol(&context, 2);
+
+        assert!(expression.is_symbol());
+        assert_eq!(expression.symbol_position(), Some(2));
+        assert_eq!(expression.dimension_position(), None);
+        assert_eq!(expression.to_string(), "s2");
+    }
+
+    #[test]
+    fn constant() {
+        let context = Context::new();
+        let expression = AffineExpr::constant(&context, 42);
+
+        assert!(expression.is_constant());
+        assert!(expression.is_symbolic_or_constant());
+        assert_eq!(expression.constant_value(), Some(42));
+        assert_eq!(expression.to_string(), "42");
+    }
+
+    #[test]
+    fn add() {
+        let context = Context::new();
+        let expression = AffineExpr::dimension(&context, 0) + AffineExpr::symbol(&context, 0);
+
+        assert!(expression.is_add());
+        assert!(expression.is_binary());
+        assert_eq!(expression.lhs(), Some(AffineExpr::dimension(&context, 0)));
+        assert_eq!(expression.rhs(), Some(AffineExpr::symbol(&context, 0)));
+        assert_eq!(expression.to_string(), "d0 + s0");
+    }
+
+    #[test]
+    fn add_constant() {
+        let context = Context::new();
+
+        assert_eq!(
+            (AffineExpr::dimension(&context, 0) + 1).to_string(),
+            "d0 + 1"
+        );
+    }
+
+    #[test]
+    fn sub() {
+        let context = Context::new();
+
+        assert_eq!(
+            (AffineExpr::dimension(&context, 0) - AffineExpr::dimension(&context, 1)).to_string(),
+            "d0 - d1"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn sub_overflow() {
+        let _ = AffineExpr::dimension(&Context::new(), 0) - i64::MIN;
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_overflow() {
+        let context = Context::new();
+
+        let _ = AffineExpr::constant(&context, i64::MAX) + 1;
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_nested_overflow() {
+        let context = Context::new();
+
+        let _ = (AffineExpr::dimension(&context, 0) + i64::MAX) + 1;
+    }
+
+    #[test]
+    #[should_panic]
+    fn mul_overflow() {
+        let context = Context::new();
+
+        let _ = (AffineExpr::dimension(&context, 0) * i64::MAX) * 2;
+    }
+
+    #[test]
+    #[should_panic]
+    fn neg_overflow() {
+        let _ = -AffineExpr::constant(&Context::new(), i64::MIN);
+    }
+
+    #[test]
+    fn mul() {
+        let context = Context::new();
+        let expression = AffineExpr::dimension(&context, 0) * 4;
+
+        assert!(expression.is_mul());
+        assert!(expression.is_multiple_of(2));
+        assert_eq!(expression.largest_known_divisor(), 4);
+        assert_eq!(expression.to_string(), "d0 * 4");
+    }
+
+    #[test]
+    fn rem() {
+        let context = Context::new();
+        let expression = AffineExpr::dimension(&context, 0) % 4;
+
+        assert!(expression.is_mod());
+        assert_eq!(expression.to_string(), "d0 mod 4");
+    }
+
+    #[test]
+    fn modulo() {
+        let context = Context::new();
+        let dimension = AffineExpr::dimension(&context, 0);
+        let constant = AffineExpr::constant(&context, 4);
+
+        assert_eq!(dimension.modulo(constant), dimension % 4);
+    }
+
+    #[test]
+    fn fold_constants() {
+        let context = Context::new();
+
+        assert_eq!(
+            AffineExpr::constant(&context, 2) * 3 + 1,
+            AffineExpr::constant(&context, 7)
+        );
+    }
+
+    #[test]
+    fn simplify() {
+        let context = Context::new();
+        let dimension = AffineExpr::dimension(&context, 0);
+
+        assert_eq!((dimension + 1 + 2).to_string(), "d0 + 3");
+        assert_eq!((dimension * 2 * 3).to_string(), "d0 * 6");
+        assert_eq!(dimension + 0, dimension);
+        assert_eq!(dimension * 1, dimension);
+    }
+
+    #[test]
+    fn layout_map() {
+        let context = Context::new();
+        let map = AffineMap::new(
+            &context,
+            2,
+            0,
+            &[AffineExpr::dimension(&context, 0) + AffineExpr::dimension(&context, 1) * 4],
+        );
+
+        assert_eq!(map.to_string(), "(d0, d1) -> (d0 + d1 * 4)");
+    }
+
+    #[test]
+    fn neg() {
+        let context = Context::new();
+
+        assert_eq!((-AffineExpr::dimension(&context, 0)).to_string(), "-d0");
+    }
+
+    #[test]
+    fn floor_div() {
+        let context = Context::new();
+        let expression =
+            AffineExpr::dimension(&context, 0).floor_div(AffineExpr::constant(&context, 2));
+
+        assert!(expression.is_floor_div());
+        assert!(expression.is_pure_affine());
+        assert_eq!(expression.to_string(), "d0 floordiv 2");
+    }
+
+    #[test]
+    fn ceil_div() {
+        let context = Context::new();
+        let expression =
+            AffineExpr::dimension(&context, 0).ceil_div(AffineExpr::constant(&context, 2));
+
+        assert!(expression.is_ceil_div());
+        assert_eq!(expression.to_string(), "d0 ceildiv 2");
+    }
+
+    #[test]
+    fn is_pure_affine() {
+        let context = Context::new();
+        let dimension = AffineExpr::dimension(&context, 0);
+
+        assert!((dimension * 2).is_pure_affine());
+        assert!(!(dimension * dimension).is_pure_affine());
+    }
+
+    #[test]
+    fn is_function_of_dimension() {
+        let context = Context::new();
+        let expression = AffineExpr::dimension(&context, 1) + 1;
+
+        assert!(expression.is_function_of_dimension(1));
+        assert!(!expression.is_function_of_dimension(0));
+    }
+
+    #[test]
+    fn compose() {
+        let context = Context::new();
+        let map = AffineMap::new(&context, 1, 0, &[AffineExpr::dimension(&context, 0) * 2]);
+
+        assert_eq!(
+            (AffineExpr::dimension(&context, 0) + 1)
+                .compose(map)
+                .to_string(),
+            "d0 * 2 + 1"
+        );
+    }
+
+    #[test]
+    fn equal() {
+        let context = Context::new();
+
+        assert_eq!(
+            AffineExpr::dimension(&context, 0),
+            AffineExpr::dimension(&context, 0)
+        );
+        assert_ne!(
+            AffineExpr::dimension(&context, 0),
+            AffineExpr::dimension(&context, 1)
+        );
+    }
+
+    #[test]
+    fn debug() {
+        let context = Context::new();
+
+        assert_eq!(
+            format!("{:?}", AffineExpr::dimension(&context, 0)),
+            "AffineExpr(d0)"
+        );
+    }
+}
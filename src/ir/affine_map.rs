@@ -0,0 +1,587 @@
+use super::{AffineExpr, Attribute};
+use crate::{
+    context::{Context, ContextRef},
+    utility::print_callback,
+    Error,
+};
+use mlir_sys::{
+    mlirAffineMapAttrGet, mlirAffineMapAttrGetValue, mlirAffineMapConstantGet,
+    mlirAffineMapEmptyGet, mlirAffineMapEqual, mlirAffineMapGet, mlirAffineMapGetContext,
+    mlirAffineMapGetMajorSubMap, mlirAffineMapGetMinorSubMap, mlirAffineMapGetNumDims,
+    mlirAffineMapGetNumInputs, mlirAffineMapGetNumResults, mlirAffineMapGetNumSymbols,
+    mlirAffineMapGetResult, mlirAffineMapGetSingleConstantResult, mlirAffineMapGetSubMap,
+    mlirAffineMapIsEmpty, mlirAffineMapIsIdentity, mlirAffineMapIsMinorIdentity,
+    mlirAffineMapIsPermutation, mlirAffineMapIsProjectedPermutation, mlirAffineMapIsSingleConstant,
+    mlirAffineMapMinorIdentityGet, mlirAffineMapMultiDimIdentityGet, mlirAffineMapPermutationGet,
+    mlirAffineMapPrint, mlirAffineMapReplace, mlirAffineMapZeroResultGet, mlirAffineSymbolExprGet,
+    MlirAffineMap, MlirContext,
+};
+use std::{
+    ffi::{c_uint, c_void},
+    fmt::{self, Debug, Display, Formatter},
+    marker::PhantomData,
+};
+
+/// An affine map.
+// Affine maps are always values but their internal storage is owned by
+// contexts.
+#[derive(Clone, Copy)]
+pub struct AffineMap<'c> {
+    raw: MlirAffineMap,
+    _context: PhantomData<&'c Context>,
+}
+
+impl<'c> AffineMap<'c> {
+    /// Creates an affine map.
+    pub fn new(
+        context: &'c Context,
+        dimension_count: usize,
+        symbol_count: usize,
+        expressions: &[AffineExpr<'c>],
+    ) -> Self {
+        unsafe {
+            Self::from_expressions(context.to_raw(), dimension_count, symbol_count, expressions)
+        }
+    }
+
+    /// Parses an affine map.
+    pub fn parse(context: &'c Context, source: &str) -> Option<Self> {
+        let attribute = Attribute::parse(context, &format!("affine_map<{}>", source))?;
+
+        if attribute.is_affine_map() {
+            Some(unsafe { Self::from_raw(mlirAffineMapAttrGetValue(attribute.to_raw())) })
+        } else {
+            None
+        }
+    }
+
+    /// Creates an empty affine map.
+    pub fn empty(context: &'c Context) -> Self {
+        unsafe { Self::from_raw(mlirAffineMapEmptyGet(context.to_raw())) }
+    }
+
+    /// Creates an affine map with no results.
+    pub fn zero_result(context: &'c Context, dimension_count: usize, symbol_count: usize) -> Self {
+        unsafe {
+            Self::from_raw(mlirAffineMapZeroResultGet(
+                context.to_raw(),
+                dimension_count as isize,
+                symbol_count as isize,
+            ))
+        }
+    }
+
+    /// Creates an affine map with a single constant result.
+    pub fn constant(context: &'c Context, value: i64) -> Self {
+        unsafe { Self::from_raw(mlirAffineMapConstantGet(context.to_raw(), value)) }
+    }
+
+    /// Creates an identity affine map.
+    pub fn identity(context: &'c Context, dimension_count: usize) -> Self {
+        unsafe {
+            Self::from_raw(mlirAffineMapMultiDimIdentityGet(
+                context.to_raw(),
+                dimension_count as isize,
+            ))
+        }
+    }
+
+    /// Creates an identity affine map of the trailing dimensions.
+    pub fn minor_identity(
+        context: &'c Context,
+        dimension_count: usize,
+        result_count: usize,
+    ) -> Self {
+        unsafe {
+            Self::from_raw(mlirAffineMapMinorIdentityGet(
+                context.to_raw(),
+                dimension_count as isize,
+                result_count as isize,
+            ))
+        }
+    }
+
+    /// Creates a permutation affine map.
+    ///
+    /// A permutation must contain each of positions from zero to its length
+    /// exactly once.
+    pub fn permutation(context: &'c Context, permutation: &[usize]) -> Result<Self, Error> {
+        let mut sorted = permutation.to_vec();
+        sorted.sort_unstable();
+
+        if !sorted.iter().copied().eq(0..permutation.len()) {
+            return Err(Error::PermutationExpected(format!("{:?}", permutation)));
+        }
+
+        let mut permutation = permutation
+            .iter()
+            .map(|&position| position as c_uint)
+            .collect::<Vec<_>>();
+
+        Ok(unsafe {
+            Self::from_raw(mlirAffineMapPermutationGet(
+                context.to_raw(),
+                permutation.len() as isize,
+                permutation.as_mut_ptr(),
+            ))
+        })
+    }
+
+    /// Gets a context.
+    pub fn context(&self) -> ContextRef<'c> {
+        unsafe { ContextRef::from_raw(mlirAffineMapGetContext(self.raw)) }
+    }
+
+    /// Gets a number of dimensions.
+    pub fn dimension_count(&self) -> usize {
+        unsafe { mlirAffineMapGetNumDims(self.raw) as usize }
+    }
+
+    /// Gets a number of symbols.
+    pub fn symbol_count(&self) -> usize {
+        unsafe { mlirAffineMapGetNumSymbols(self.raw) as usize }
+    }
+
+    /// Gets a number of inputs, which are dimensions and symbols.
+    pub fn input_count(&self) -> usize {
+        unsafe { mlirAffineMapGetNumInputs(self.raw) as usize }
+    }
+
+    /// Gets a result at a position.
+    pub fn result(&self, position: usize) -> Option<AffineExpr<'c>> {
+        if position < self.result_count() {
+            Some(unsafe {
+                AffineExpr::from_raw(mlirAffineMapGetResult(self.raw, position as isize))
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Gets a number of results.
+    pub fn result_count(&self) -> usize {
+        unsafe { mlirAffineMapGetNumResults(self.raw) as usize }
+    }
+
+    /// Gets results.
+    pub fn results(&self) -> impl Iterator<Item = AffineExpr<'c>> {
+        let raw = self.raw;
+
+        (0..self.result_count()).map(move |index| unsafe {
+            AffineExpr::from_raw(mlirAffineMapGetResult(raw, index as isize))
+        })
+    }
+
+    /// Gets a result of an affine map with a single constant result.
+    pub fn single_constant_result(&self) -> Option<i64> {
+        if self.is_single_constant() {
+            Some(unsafe { mlirAffineMapGetSingleConstantResult(self.raw) })
+        } else {
+            None
+        }
+    }
+
+    /// Composes affine maps.
+    ///
+    /// A result affine map applies another affine map first and then this
+    /// one. Symbols of this affine map come before ones of the other.
+    pub fn compose(self, other: Self) -> Self {
+        let context = unsafe { mlirAffineMapGetContext(other.raw) };
+        let symbol = |position: usize| unsafe {
+            AffineExpr::from_raw(mlirAffineSymbolExprGet(context, position as isize))
+        };
+        let dimension_count = other.dimension_count();
+        let symbol_count = self.symbol_count() + other.symbol_count();
+        let mut other = other;
+
+        // Symbols are shifted from the last one so that shifted ones are not
+        // replaced again.
+        for position in (0..other.symbol_count()).rev() {
+            other = other.replace(
+                symbol(position),
+                symbol(position + self.symbol_count()),
+                dimension_count,
+                symbol_count,
+            );
+        }
+
+        unsafe {
+            Self::from_expressions(
+                context,
+                dimension_count,
+                symbol_count,
+                &self
+                    .results()
+                    .map(|expression| expression.compose(other))
+                    .collect::<Vec<_>>(),
+            )
+        }
+    }
+
+    /// Gets an affine map of results at positions.
+    pub fn sub_map(&self, positions: &[usize]) -> Result<Self, Error> {
+        if let Some(&position) = positions
+            .iter()
+            .find(|&&position| position >= self.result_count())
+        {
+            return Err(Error::AffineMapResultPosition(self.to_string(), position));
+        }
+
+        let mut positions = positions
+            .iter()
+            .map(|&position| position as isize)
+            .collect::<Vec<_>>();
+
+        Ok(unsafe {
+            Self::from_raw(mlirAffineMapGetSubMap(
+                self.raw,
+                positions.len() as isize,
+                positions.as_mut_ptr(),
+            ))
+        })
+    }
+
+    /// Gets an affine map of leading results.
+    pub fn major_sub_map(&self, result_count: usize) -> Self {
+        unsafe { Self::from_raw(mlirAffineMapGetMajorSubMap(self.raw, result_count as isize)) }
+    }
+
+    /// Gets an affine map of trailing results.
+    pub fn minor_sub_map(&self, result_count: usize) -> Self {
+        unsafe { Self::from_raw(mlirAffineMapGetMinorSubMap(self.raw, result_count as isize)) }
+    }
+
+    /// Replaces an expression with another in results.
+    pub fn replace(
+        &self,
+        expression: AffineExpr<'c>,
+        replacement: AffineExpr<'c>,
+        dimension_count: usize,
+        symbol_count: usize,
+    ) -> Self {
+        unsafe {
+            Self::from_raw(mlirAffineMapReplace(
+                self.raw,
+                expression.to_raw(),
+                replacement.to_raw(),
+                dimension_count as isize,
+                symbol_count as isize,
+            ))
+        }
+    }
+
+    /// Returns `true` if an affine map is an identity.
+    pub fn is_identity(&self) -> bool {
+        unsafe { mlirAffineMapIsIdentity(self.raw) }
+    }
+
+    /// Returns `true` if an affine map is an identity of the trailing
+    /// dimensions.
+    pub fn is_minor_identity(&self) -> bool {
+        unsafe { mlirAffineMapIsMinorIdentity(self.raw) }
+    }
+
+    /// Returns `true` if an affine map is empty.
+    pub fn is_empty(&self) -> bool {
+        unsafe { mlirAffineMapIsEmpty(self.raw) }
+    }
+
+    /// Returns `true` if an affine map has a single constant result.
+    pub fn is_single_constant(&self) -> bool {
+        unsafe { mlirAffineMapIsSingleConstant(self.raw) }
+    }
+
+    /// Returns `true` if an affine map is a permutation.
+    pub fn is_permutation(&self) -> bool {
+        unsafe { mlirAffineMapIsPermutation(self.raw) }
+    }
+
+    /// Returns `true` if an affine map is a projected permutation.
+    pub fn is_projected_permutation(&self) -> bool {
+        unsafe { mlirAffineMapIsProjectedPermutation(self.raw) }
+    }
+
+    unsafe fn from_expressions(
+        context: MlirContext,
+        dimension_count: usize,
+        symbol_count: usize,
+        expressions: &[AffineExpr<'c>],
+    ) -> Self {
+        let mut expressions = expressions
+            .iter()
+            .map(|expression| expression.to_raw())
+            .collect::<Vec<_>>();
+
+        Self::from_raw(mlirAffineMapGet(
+            context,
+            dimension_count as isize,
+            symbol_count as isize,
+            expressions.len() as isize,
+            expressions.as_mut_ptr(),
+        ))
+    }
+
+    pub(crate) unsafe fn from_raw(raw: MlirAffineMap) -> Self {
+        Self {
+            raw,
+            _context: Default::default(),
+        }
+    }
+
+    pub(crate) unsafe fn to_raw(self) -> MlirAffineMap {
+        self.raw
+    }
+}
+
+impl<'c> PartialEq for AffineMap<'c> {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { mlirAffineMapEqual(self.raw, other.raw) }
+    }
+}
+
+impl<'c> Eq for AffineMap<'c> {}
+
+impl<'c> From<AffineMap<'c>> for Attribute<'c> {
+    fn from(map: AffineMap<'c>) -> Self {
+        unsafe { Self::from_raw(mlirAffineMapAttrGet(map.raw)) }
+    }
+}
+
+impl<'c> Display for AffineMap<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let mut data = (formatter, Ok(()));
+
+        unsafe {
+            mlirAffineMapPrint(
+                self.raw,
+                Some(print_callback),
+                &mut data as *mut _ as *mut c_void,
+            );
+        }
+
+        data.1
+    }
+}
+
+impl<'c> Debug for AffineMap<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "AffineMap(")?;
+        Display::fmt(self, formatter)?;
+        write!(formatter, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let context = Context::new();
+        let map = AffineMap::new(
+            &context,
+            2,
+            1,
+            &[
+                AffineExpr::dimension(&context, 0) + AffineExpr::symbol(&context, 0),
+                AffineExpr::dimension(&context, 1),
+            ],
+        );
+
+        assert_eq!(map.dimension_count(), 2);
+        assert_eq!(map.symbol_count(), 1);
+        assert_eq!(map.input_count(), 3);
+        assert_eq!(map.result_count(), 2);
+        assert_eq!(map.to_string(), "(d0, d1)[s0] -> (d0 + s0, d1)");
+    }
+
+    #[test]
+    fn parse() {
+        let context = Context::new();
+
+        assert_eq!(
+            AffineMap::parse(&context, "(d0, d1) -> (d1, d0)"),
+            Some(AffineMap::permutation(&context, &[1, 0]).unwrap())
+        );
+    }
+
+    #[test]
+    fn parse_none() {
+        assert_eq!(AffineMap::parse(&Context::new(), "(d0) ->"), None);
+    }
+
+    #[test]
+    fn empty() {
+        let context = Context::new();
+        let map = AffineMap::empty(&context);
+
+        assert!(map.is_empty());
+        assert_eq!(map.to_string(), "() -> ()");
+    }
+
+    #[test]
+    fn zero_result() {
+        let context = Context::new();
+
+        assert_eq!(
+            AffineMap::zero_result(&context, 2, 1).to_string(),
+            "(d0, d1)[s0] -> ()"
+        );
+    }
+
+    #[test]
+    fn constant() {
+        let context = Context::new();
+        let map = AffineMap::constant(&context, 42);
+
+        assert!(map.is_single_constant());
+        assert_eq!(map.single_constant_result(), Some(42));
+        assert_eq!(AffineMap::empty(&context).single_constant_result(), None);
+    }
+
+    #[test]
+    fn identity() {
+        let context = Context::new();
+        let map = AffineMap::identity(&context, 2);
+
+        assert!(map.is_identity());
+        assert!(map.is_minor_identity());
+        assert!(map.is_permutation());
+        assert_eq!(map.to_string(), "(d0, d1) -> (d0, d1)");
+    }
+
+    #[test]
+    fn minor_identity() {
+        let context = Context::new();
+        let map = AffineMap::minor_identity(&context, 3, 2);
+
+        assert!(map.is_minor_identity());
+        assert!(!map.is_identity());
+        assert!(map.is_projected_permutation());
+        assert_eq!(map.to_string(), "(d0, d1, d2) -> (d1, d2)");
+    }
+
+    #[test]
+    fn permutation() {
+        let context = Context::new();
+        let map = AffineMap::permutation(&context, &[1, 2, 0]).unwrap();
+
+        assert!(map.is_permutation());
+        assert_eq!(map.to_string(), "(d0, d1, d2) -> (d1, d2, d0)");
+    }
+
+    #[test]
+    fn permutation_with_invalid_positions() {
+        let context = Context::new();
+
+        assert_eq!(
+            AffineMap::permutation(&context, &[0, 0]),
+            Err(Error::PermutationExpected("[0, 0]".into()))
+        );
+        assert_eq!(
+            AffineMap::permutation(&context, &[1, 2]),
+            Err(Error::PermutationExpected("[1, 2]".into()))
+        );
+    }
+
+    #[test]
+    fn result() {
+        let context = Context::new();
+        let map = AffineMap::identity(&context, 2);
+
+        assert_eq!(map.result(1), Some(AffineExpr::dimension(&context, 1)));
+        assert_eq!(map.result(2), None);
+    }
+
+    #[test]
+    fn results() {
+        let context = Context::new();
+
+        assert_eq!(
+            AffineMap::identity(&context, 2)
+                .results()
+                .collect::<Vec<_>>(),
+            [
+                AffineExpr::dimension(&context, 0),
+                AffineExpr::dimension(&context, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn compose() {
+        let context = Context::new();
+        let map = AffineMap::parse(&context, "(d0, d1) -> (d0 + d1)").unwrap();
+        let other = AffineMap::parse(&context, "(d0)[s0] -> (d0 * 2, s0)").unwrap();
+
+        assert_eq!(
+            map.compose(other),
+            AffineMap::parse(&context, "(d0)[s0] -> (d0 * 2 + s0)").unwrap()
+        );
+    }
+
+    #[test]
+    fn compose_with_symbols() {
+        let context = Context::new();
+        let map = AffineMap::parse(&context, "(d0)[s0] -> (d0 + s0)").unwrap();
+        let other = AffineMap::parse(&context, "(d0)[s0] -> (d0 * s0)").unwrap();
+
+        assert_eq!(
+            map.compose(other),
+            AffineMap::parse(&context, "(d0)[s0, s1] -> (d0 * s1 + s0)").unwrap()
+        );
+    }
+
+    #[test]
+    fn sub_map() {
+        let context = Context::new();
+        let map = AffineMap::identity(&context, 3);
+
+        assert_eq!(
+            map.sub_map(&[2, 0]).unwrap().to_string(),
+            "(d0, d1, d2) -> (d2, d0)"
+        );
+        assert_eq!(
+            map.sub_map(&[3]),
+            Err(Error::AffineMapResultPosition(map.to_string(), 3))
+        );
+        assert_eq!(map.major_sub_map(1).to_string(), "(d0, d1, d2) -> (d0)");
+        assert_eq!(map.minor_sub_map(1).to_string(), "(d0, d1, d2) -> (d2)");
+    }
+
+    #[test]
+    fn replace() {
+        let context = Context::new();
+        let map = AffineMap::identity(&context, 2);
+
+        assert_eq!(
+            map.replace(
+                AffineExpr::dimension(&context, 1),
+                AffineExpr::constant(&context, 0),
+                2,
+                0
+            )
+            .to_string(),
+            "(d0, d1) -> (d0, 0)"
+        );
+    }
+
+    #[test]
+    fn attribute() {
+        let context = Context::new();
+        let attribute = Attribute::from(AffineMap::identity(&context, 1));
+
+        assert!(attribute.is_affine_map());
+        assert_eq!(attribute.to_string(), "affine_map<(d0) -> (d0)>");
+    }
+
+    #[test]
+    fn debug() {
+        let context = Context::new();
+
+        assert_eq!(
+            format!("{:?}", AffineMap::identity(&context, 1)),
+            "AffineMap((d0) -> (d0))"
+        );
+    }
+}
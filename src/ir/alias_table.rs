@@ -0,0 +1,281 @@
+use super::{Attribute, Module, Type};
+use crate::{context::Context, utility::string_literal_length};
+use std::{cmp::Reverse, collections::HashMap};
+
+/// A table of attribute and type aliases.
+///
+/// Attributes and types are parsed and printed with aliases of `#name` and
+/// `!name` defined in a table. Definitions are parsed only once when a table is
+/// created.
+#[derive(Debug)]
+pub struct AliasTable<'c> {
+    context: &'c Context,
+    attributes: HashMap<String, Attribute<'c>>,
+    types: HashMap<String, Type<'c>>,
+}
+
+impl<'c> AliasTable<'c> {
+    /// Parses alias definitions.
+    ///
+    /// Each definition is on its own line, e.g. `#map = affine_map<(d0) ->
+    /// (d0)>`, and can refer to aliases defined before it. Other lines and
+    /// location aliases are ignored, so that a whole module source can be
+    /// given. It returns `None` if any definition is invalid.
+    pub fn parse(context: &'c Context, source: &str) -> Option<Self> {
+        let mut table = Self {
+            context,
+            attributes: HashMap::new(),
+            types: HashMap::new(),
+        };
+
+        for (name, value) in source
+            .lines()
+            .filter(|line| line.starts_with('#') || line.starts_with('!'))
+            .filter_map(|line| line.split_once(" = "))
+            .filter(|(_, value)| !value.starts_with("loc("))
+        {
+            let name = name.to_owned();
+
+            if name.starts_with('#') {
+                let attribute = table.parse_attribute(value)?;
+                table.attributes.insert(name, attribute);
+            } else {
+                let r#type = table.parse_type(value)?;
+                table.types.insert(name, r#type);
+            }
+        }
+
+        Some(table)
+    }
+
+    /// Creates a table of aliases which a module is printed with.
+    ///
+    /// Aliases are named by the printer of MLIR, e.g. `#map0`, because names
+    /// in sources are not kept in modules.
+    pub fn from_module(context: &'c Context, module: &Module) -> Option<Self> {
+        Self::parse(context, &module.as_operation().to_string())
+    }
+
+    /// Parses an attribute.
+    pub fn parse_attribute(&self, source: &str) -> Option<Attribute<'c>> {
+        Attribute::parse(self.context, &self.expand(source))
+    }
+
+    /// Parses a type.
+    pub fn parse_type(&self, source: &str) -> Option<Type<'c>> {
+        Type::parse(self.context, &self.expand(source))
+    }
+
+    /// Prints an attribute with aliases.
+    pub fn print_attribute(&self, attribute: Attribute) -> String {
+        self.abbreviate(&attribute.to_string())
+    }
+
+    /// Prints a type with aliases.
+    pub fn print_type(&self, r#type: Type) -> String {
+        self.abbreviate(&r#type.to_string())
+    }
+
+    fn expand(&self, source: &str) -> String {
+        let mut expanded = String::with_capacity(source.len());
+        let mut rest = source;
+
+        while let Some(index) = rest.find(&['"', '#', '!'][..]) {
+            expanded.push_str(&rest[..index]);
+            rest = &rest[index..];
+
+            let length = if rest.starts_with('"') {
+                string_literal_length(rest)
+            } else {
+                1 + rest[1..]
+                    .find(|character| !is_identifier_character(character))
+                    .unwrap_or(rest.len() - 1)
+            };
+            let token = &rest[..length];
+
+            match (self.attributes.get(token), self.types.get(token)) {
+                (Some(attribute), _) => expanded.push_str(&attribute.to_string()),
+                (_, Some(r#type)) => expanded.push_str(&r#type.to_string()),
+                _ => expanded.push_str(token),
+            }
+
+            rest = &rest[length..];
+        }
+
+        expanded.push_str(rest);
+
+        expanded
+    }
+
+    fn abbreviate(&self, source: &str) -> String {
+        let mut aliases = self
+            .attributes
+            .iter()
+            .map(|(name, attribute)| (name, attribute.to_string()))
+            .chain(
+                self.types
+                    .iter()
+                    .map(|(name, r#type)| (name, r#type.to_string())),
+            )
+            .collect::<Vec<_>>();
+
+        // Longer values are replaced first as they can contain shorter ones.
+        aliases.sort_by_key(|(_, value)| Reverse(value.len()));
+
+        let mut abbreviated = String::with_capacity(source.len());
+        let mut index = 0;
+
+        while let Some(character) = source[index..].chars().next() {
+            let rest = &source[index..];
+            let alias = aliases.iter().find(|(_, value)| {
+                rest.starts_with(value.as_str())
+                    && is_boundary(source, index)
+                    && is_boundary(source, index + value.len())
+            });
+
+            let length = if character == '"' {
+                let length = string_literal_length(rest);
+                abbreviated.push_str(&rest[..length]);
+                length
+            } else if let Some((name, value)) = alias {
+                abbreviated.push_str(name);
+                value.len()
+            } else {
+                abbreviated.push(character);
+                character.len_utf8()
+            };
+
+            index += length;
+        }
+
+        abbreviated
+    }
+}
+
+fn is_identifier_character(character: char) -> bool {
+    character.is_ascii_alphanumeric() || matches!(character, '_' | '$' | '.')
+}
+
+// Values are not replaced in the middle of identifiers, e.g. `i32` in `si32`.
+fn is_boundary(source: &str, index: usize) -> bool {
+    let before = source[..index].chars().next_back();
+    let after = source[index..].chars().next();
+
+    !matches!((before, after), (Some(before), Some(after)) if is_identifier_character(before) && is_identifier_character(after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Location;
+
+    const SOURCE: &str = indoc::indoc!(
+        "
+        #map1 = affine_map<(d0) -> (d0 * 2)>
+        #map10 = affine_map<(d0) -> (d0 * 10)>
+        !memref = memref<4xf32, #map10>
+        func.func @foo(%arg0: !memref) {
+          return
+        }
+        "
+    );
+
+    #[test]
+    fn parse() {
+        let context = Context::new();
+        let table = AliasTable::parse(&context, SOURCE).unwrap();
+
+        assert_eq!(
+            table.parse_attribute("#map1"),
+            Attribute::parse(&context, "affine_map<(d0) -> (d0 * 2)>")
+        );
+        assert_eq!(
+            table.parse_type("!memref"),
+            Type::parse(&context, "memref<4xf32, affine_map<(d0) -> (d0 * 10)>>")
+        );
+    }
+
+    #[test]
+    fn parse_invalid_definition() {
+        let context = Context::new();
+
+        assert!(AliasTable::parse(&context, "#foo = bar").is_none());
+    }
+
+    #[test]
+    fn from_module() {
+        let context = Context::new();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let table = AliasTable::from_module(&context, &module).unwrap();
+
+        assert_eq!(
+            table.parse_type("memref<4xf32, #map0>"),
+            Type::parse(&context, "memref<4xf32, affine_map<(d0) -> (d0 * 10)>>")
+        );
+    }
+
+    #[test]
+    fn from_empty_module() {
+        let context = Context::new();
+        let module = Module::new(Location::unknown(&context));
+        let table = AliasTable::from_module(&context, &module).unwrap();
+
+        assert_eq!(table.parse_attribute("#foo"), None);
+    }
+
+    #[test]
+    fn parse_type_attribute() {
+        let context = Context::new();
+        let table = AliasTable::parse(&context, "!index = index").unwrap();
+
+        assert_eq!(
+            table.parse_attribute("!index"),
+            Some(Attribute::from_type(Type::index(&context)))
+        );
+    }
+
+    #[test]
+    fn parse_string_attribute() {
+        let context = Context::new();
+        let table = AliasTable::parse(&context, SOURCE).unwrap();
+
+        assert_eq!(
+            table.parse_attribute(r##""#map1""##),
+            Some(Attribute::string(&context, "#map1"))
+        );
+    }
+
+    #[test]
+    fn print_attribute() {
+        let context = Context::new();
+        let table = AliasTable::parse(&context, SOURCE).unwrap();
+
+        assert_eq!(
+            table.print_attribute(
+                Attribute::parse(&context, "affine_map<(d0) -> (d0 * 2)>").unwrap()
+            ),
+            "#map1"
+        );
+        assert_eq!(
+            table.print_attribute(Attribute::string(&context, "affine_map<(d0) -> (d0 * 2)>")),
+            r#""affine_map<(d0) -> (d0 * 2)>""#
+        );
+    }
+
+    #[test]
+    fn print_type() {
+        let context = Context::new();
+        let table = AliasTable::parse(&context, SOURCE).unwrap();
+
+        assert_eq!(
+            table.print_type(
+                Type::parse(
+                    &context,
+                    "tuple<memref<4xf32, affine_map<(d0) -> (d0 * 10)>>>"
+                )
+                .unwrap()
+            ),
+            "tuple<!memref>"
+        );
+    }
+}
@@ -1,22 +1,35 @@
+mod array;
+mod dictionary;
+mod float;
+mod integer;
+
+pub use self::{
+    array::ArrayAttribute, dictionary::DictionaryAttribute, float::FloatAttribute,
+    integer::IntegerAttribute,
+};
 use super::{r#type, Type, TypeLike};
 use crate::{
     context::{Context, ContextRef},
     string_ref::StringRef,
-    utility::{print_callback, into_raw_array},
+    utility::print_callback,
+    Error,
 };
 use mlir_sys::{
     mlirAttributeDump, mlirAttributeEqual, mlirAttributeGetContext, mlirAttributeGetNull,
     mlirAttributeGetType, mlirAttributeGetTypeID, mlirAttributeIsAAffineMap, mlirAttributeIsAArray,
     mlirAttributeIsABool, mlirAttributeIsADenseElements, mlirAttributeIsADenseFPElements,
     mlirAttributeIsADenseIntElements, mlirAttributeIsADictionary, mlirAttributeIsAElements,
-    mlirAttributeIsAFloat, mlirAttributeIsAInteger, mlirAttributeIsAIntegerSet,
-    mlirAttributeIsAOpaque, mlirAttributeIsAOpaqueElements, mlirAttributeIsASparseElements,
-    mlirAttributeIsAString, mlirAttributeIsASymbolRef, mlirAttributeIsAType, mlirAttributeIsAUnit,
-    mlirAttributeParseGet, mlirAttributePrint, MlirAttribute, mlirDenseElementsAttrInt32Get,
+    mlirAttributeIsAFlatSymbolRef, mlirAttributeIsAFloat, mlirAttributeIsAInteger,
+    mlirAttributeIsAIntegerSet, mlirAttributeIsAOpaque, mlirAttributeIsAOpaqueElements,
+    mlirAttributeIsASparseElements, mlirAttributeIsAString, mlirAttributeIsASymbolRef,
+    mlirAttributeIsAType, mlirAttributeIsAUnit, mlirAttributeParseGet, mlirAttributePrint,
+    mlirBoolAttrGet, mlirDenseElementsAttrInt32Get, mlirDenseElementsAttrRawBufferGet,
+    mlirStringAttrGet, mlirTypeAttrGet, mlirUnitAttrGet, MlirAttribute,
 };
 use std::{
-    ffi::c_void,
+    ffi::{c_void, CString},
     fmt::{self, Display, Formatter},
+    hash::{Hash, Hasher},
     marker::PhantomData,
 };
 
@@ -31,10 +44,12 @@ pub struct Attribute<'c> {
 impl<'c> Attribute<'c> {
     /// Parses an attribute.
     pub fn parse(context: &'c Context, source: &str) -> Option<Self> {
+        let source = CString::new(source).ok()?;
+
         unsafe {
             Self::from_option_raw(mlirAttributeParseGet(
                 context.to_raw(),
-                StringRef::from(source).to_raw(),
+                StringRef::from(source.as_c_str()).to_raw(),
             ))
         }
     }
@@ -44,12 +59,66 @@ impl<'c> Attribute<'c> {
         unsafe { Self::from_raw(mlirAttributeGetNull()) }
     }
 
+    /// Creates a unit attribute.
+    pub fn unit(context: &'c Context) -> Self {
+        unsafe { Self::from_raw(mlirUnitAttrGet(context.to_raw())) }
+    }
+
+    /// Creates a boolean attribute.
+    pub fn bool(context: &'c Context, value: bool) -> Self {
+        unsafe { Self::from_raw(mlirBoolAttrGet(context.to_raw(), value as i32)) }
+    }
+
+    /// Creates a string attribute.
+    pub fn string(context: &'c Context, value: &str) -> Self {
+        unsafe {
+            Self::from_raw(mlirStringAttrGet(
+                context.to_raw(),
+                StringRef::from(value).to_raw(),
+            ))
+        }
+    }
+
+    /// Creates an integer attribute.
+    pub fn integer(value: i64, r#type: Type<'c>) -> Result<Self, Error> {
+        IntegerAttribute::new(value, r#type).map(Self::from)
+    }
+
+    /// Creates a type attribute.
+    pub fn from_type(r#type: Type<'c>) -> Self {
+        unsafe { Self::from_raw(mlirTypeAttrGet(r#type.to_raw())) }
+    }
+
+    /// Creates a dense elements attribute of 32-bit integers.
     pub fn dense_elements_i32(r#type: Type, elements: &[i32]) -> Self {
         unsafe {
             Self::from_raw(mlirDenseElementsAttrInt32Get(
                 r#type.to_raw(),
                 elements.len() as isize,
-                into_raw_array(elements.iter().map(|i| *i).collect()),
+                elements.as_ptr(),
+            ))
+        }
+    }
+
+    /// Creates a dense elements attribute from a raw buffer.
+    ///
+    /// A buffer is copied into a context directly, so it can be, e.g. a
+    /// memory-mapped file of large constants. Elements are laid out in the
+    /// row-major order with the host endianness, and booleans are packed into
+    /// bits. A buffer of a single element is splat into all elements. It
+    /// returns `None` if the type is not shaped with a static shape or the
+    /// buffer size matches neither.
+    // TODO Fill elements from iterators.
+    pub fn dense_elements_raw(r#type: Type, buffer: &[u8]) -> Option<Self> {
+        if !r#type::Shaped::try_from(r#type).ok()?.has_static_shape() {
+            return None;
+        }
+
+        unsafe {
+            Self::from_option_raw(mlirDenseElementsAttrRawBufferGet(
+                r#type.to_raw(),
+                buffer.len(),
+                buffer.as_ptr() as *const c_void,
             ))
         }
     }
@@ -117,6 +186,11 @@ impl<'c> Attribute<'c> {
         !self.is_null() && unsafe { mlirAttributeIsAElements(self.raw) }
     }
 
+    /// Returns `true` if an attribute is a flat symbol reference.
+    pub fn is_flat_symbol(&self) -> bool {
+        !self.is_null() && unsafe { mlirAttributeIsAFlatSymbolRef(self.raw) }
+    }
+
     /// Returns `true` if an attribute is a float.
     pub fn is_float(&self) -> bool {
         !self.is_null() && unsafe { mlirAttributeIsAFloat(self.raw) }
@@ -167,6 +241,11 @@ impl<'c> Attribute<'c> {
         !self.is_null() && unsafe { mlirAttributeIsAUnit(self.raw) }
     }
 
+    /// Downcasts an attribute into a specific one, e.g. `IntegerAttribute`.
+    pub fn try_downcast<T: TryFrom<Self, Error = Error>>(self) -> Result<T, Error> {
+        T::try_from(self)
+    }
+
     /// Gets a context.
     pub fn context(&self) -> ContextRef<'c> {
         unsafe { ContextRef::from_raw(mlirAttributeGetContext(self.raw)) }
@@ -177,14 +256,14 @@ impl<'c> Attribute<'c> {
         unsafe { mlirAttributeDump(self.raw) }
     }
 
-    unsafe fn from_raw(raw: MlirAttribute) -> Self {
+    pub(crate) unsafe fn from_raw(raw: MlirAttribute) -> Self {
         Self {
             raw,
             _context: Default::default(),
         }
     }
 
-    unsafe fn from_option_raw(raw: MlirAttribute) -> Option<Self> {
+    pub(crate) unsafe fn from_option_raw(raw: MlirAttribute) -> Option<Self> {
         if raw.ptr.is_null() {
             None
         } else {
@@ -205,6 +284,13 @@ impl<'c> PartialEq for Attribute<'c> {
 
 impl<'c> Eq for Attribute<'c> {}
 
+// Attributes are uniqued in contexts and so are their pointers.
+impl<'c> Hash for Attribute<'c> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.raw.ptr.hash(hasher);
+    }
+}
+
 impl<'c> Display for Attribute<'c> {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         let mut data = (formatter, Ok(()));
@@ -243,6 +329,60 @@ mod tests {
         assert_eq!(Attribute::null().to_string(), "<<NULL ATTRIBUTE>>");
     }
 
+    #[test]
+    fn unit() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::unit(&context),
+            Attribute::parse(&context, "unit").unwrap()
+        );
+    }
+
+    #[test]
+    fn bool() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::bool(&context, true),
+            Attribute::parse(&context, "true").unwrap()
+        );
+        assert_eq!(
+            Attribute::bool(&context, false),
+            Attribute::parse(&context, "false").unwrap()
+        );
+    }
+
+    #[test]
+    fn string() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::string(&context, "foo\"bar"),
+            Attribute::parse(&context, r#""foo\22bar""#).unwrap()
+        );
+    }
+
+    #[test]
+    fn integer() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::integer(42, Type::integer(&context, 64)).unwrap(),
+            Attribute::parse(&context, "42 : i64").unwrap()
+        );
+    }
+
+    #[test]
+    fn from_type() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::from_type(Type::index(&context)),
+            Attribute::parse(&context, "index").unwrap()
+        );
+    }
+
     #[test]
     fn context() {
         Attribute::parse(&Context::new(), "unit").unwrap().context();
@@ -292,6 +432,69 @@ mod tests {
             .is_bool());
     }
 
+    #[test]
+    fn dense_elements_i32() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::dense_elements_i32(Type::parse(&context, "tensor<2xi32>").unwrap(), &[1, 2])
+                .to_string(),
+            "dense<[1, 2]> : tensor<2xi32>"
+        );
+    }
+
+    #[test]
+    fn dense_elements_raw() {
+        let context = Context::new();
+        let r#type = Type::parse(&context, "tensor<2xi32>").unwrap();
+
+        assert_eq!(
+            Attribute::dense_elements_raw(
+                r#type,
+                &[1i32, 2]
+                    .iter()
+                    .flat_map(|element| element.to_ne_bytes())
+                    .collect::<Vec<_>>()
+            ),
+            Some(Attribute::dense_elements_i32(r#type, &[1, 2]))
+        );
+    }
+
+    #[test]
+    fn dense_elements_raw_splat() {
+        let context = Context::new();
+        let r#type = Type::parse(&context, "tensor<2xi32>").unwrap();
+
+        assert_eq!(
+            Attribute::dense_elements_raw(r#type, &42i32.to_ne_bytes()),
+            Attribute::parse(&context, "dense<42> : tensor<2xi32>")
+        );
+    }
+
+    #[test]
+    fn dense_elements_raw_with_non_shaped_type() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::dense_elements_raw(Type::index(&context), &[0; 8]),
+            None
+        );
+        assert_eq!(
+            Attribute::dense_elements_raw(Type::parse(&context, "tensor<?xi32>").unwrap(), &[0; 4]),
+            None
+        );
+    }
+
+    #[test]
+    fn dense_elements_raw_with_invalid_size() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::dense_elements_raw(Type::parse(&context, "tensor<2xi32>").unwrap(), &[0; 3]),
+            None
+        );
+    }
+
     #[test]
     fn is_dense_elements() {
         assert!(
@@ -378,6 +581,30 @@ mod tests {
             .is_type());
     }
 
+    #[test]
+    fn try_downcast() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::parse(&context, "42")
+                .unwrap()
+                .try_downcast::<IntegerAttribute>()
+                .unwrap()
+                .value(),
+            42
+        );
+    }
+
+    #[test]
+    fn try_downcast_fail() {
+        assert_eq!(
+            Attribute::parse(&Context::new(), "unit")
+                .unwrap()
+                .try_downcast::<IntegerAttribute>(),
+            Err(Error::IntegerAttributeExpected("unit".into()))
+        );
+    }
+
     #[test]
     fn is_unit() {
         assert!(Attribute::parse(&Context::new(), "unit").unwrap().is_unit());
@@ -395,6 +622,16 @@ mod tests {
             .is_symbol());
     }
 
+    #[test]
+    fn is_flat_symbol() {
+        let context = Context::new();
+
+        assert!(Attribute::parse(&context, "@foo").unwrap().is_flat_symbol());
+        assert!(!Attribute::parse(&context, "@foo::@bar")
+            .unwrap()
+            .is_flat_symbol());
+    }
+
     #[test]
     fn equal() {
         let context = Context::new();
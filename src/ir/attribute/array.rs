@@ -0,0 +1,217 @@
+use super::{Attribute, IntegerAttribute};
+use crate::{context::Context, ir::Type, Error};
+use mlir_sys::{
+    mlirArrayAttrGet, mlirArrayAttrGetElement, mlirArrayAttrGetNumElements, MlirAttribute,
+};
+use std::fmt::{self, Display, Formatter};
+
+// TODO Add dense array attributes.
+/// An array attribute.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ArrayAttribute<'c> {
+    attribute: Attribute<'c>,
+}
+
+impl<'c> ArrayAttribute<'c> {
+    /// Creates an array attribute.
+    pub fn new(context: &'c Context, elements: &[Attribute<'c>]) -> Self {
+        unsafe {
+            Self::from_raw(mlirArrayAttrGet(
+                context.to_raw(),
+                elements.len() as isize,
+                elements
+                    .iter()
+                    .map(|attribute| attribute.to_raw())
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
+            ))
+        }
+    }
+
+    /// Creates an array attribute of 64-bit integers.
+    pub fn i64(context: &'c Context, elements: &[i64]) -> Self {
+        let r#type = Type::integer(context, 64);
+
+        Self::new(
+            context,
+            &elements
+                .iter()
+                // Integer types are always valid.
+                .map(|&element| IntegerAttribute::new(element, r#type).unwrap().into())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Gets an element at a position.
+    pub fn element(&self, position: usize) -> Result<Attribute<'c>, Error> {
+        if position < self.len() {
+            unsafe {
+                Ok(Attribute::from_raw(mlirArrayAttrGetElement(
+                    self.attribute.to_raw(),
+                    position as isize,
+                )))
+            }
+        } else {
+            Err(Error::ArrayElementPosition(self.to_string(), position))
+        }
+    }
+
+    /// Gets a number of elements.
+    pub fn len(&self) -> usize {
+        unsafe { mlirArrayAttrGetNumElements(self.attribute.to_raw()) as usize }
+    }
+
+    /// Returns `true` if an array has no element.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets elements.
+    pub fn elements(&self) -> impl Iterator<Item = Attribute<'c>> {
+        let array = *self;
+
+        (0..self.len()).map(move |position| array.element(position).unwrap())
+    }
+
+    pub(crate) unsafe fn from_raw(raw: MlirAttribute) -> Self {
+        Self {
+            attribute: Attribute::from_raw(raw),
+        }
+    }
+}
+
+impl<'c> Display for ArrayAttribute<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.attribute.fmt(formatter)
+    }
+}
+
+impl<'c> From<ArrayAttribute<'c>> for Attribute<'c> {
+    fn from(array: ArrayAttribute<'c>) -> Self {
+        array.attribute
+    }
+}
+
+impl<'c> TryFrom<Attribute<'c>> for ArrayAttribute<'c> {
+    type Error = Error;
+
+    fn try_from(attribute: Attribute<'c>) -> Result<Self, Self::Error> {
+        if attribute.is_array() {
+            Ok(Self { attribute })
+        } else {
+            Err(Error::ArrayExpected(attribute.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let context = Context::new();
+        let array = ArrayAttribute::new(
+            &context,
+            &[
+                Attribute::parse(&context, "42 : i64").unwrap(),
+                Attribute::parse(&context, "\"foo\"").unwrap(),
+            ],
+        );
+
+        assert_eq!(
+            Attribute::from(array),
+            Attribute::parse(&context, "[42 : i64, \"foo\"]").unwrap()
+        );
+    }
+
+    #[test]
+    fn i64() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::from(ArrayAttribute::i64(&context, &[1, 2])),
+            Attribute::parse(&context, "[1, 2]").unwrap()
+        );
+    }
+
+    #[test]
+    fn element() {
+        let context = Context::new();
+        let array = ArrayAttribute::new(
+            &context,
+            &[
+                Attribute::parse(&context, "42 : i64").unwrap(),
+                Attribute::parse(&context, "\"foo\"").unwrap(),
+            ],
+        );
+
+        assert_eq!(
+            array.element(1),
+            Ok(Attribute::parse(&context, "\"foo\"").unwrap())
+        );
+    }
+
+    #[test]
+    fn element_error() {
+        let context = Context::new();
+        let array = ArrayAttribute::new(
+            &context,
+            &[
+                Attribute::parse(&context, "42 : i64").unwrap(),
+                Attribute::parse(&context, "\"foo\"").unwrap(),
+            ],
+        );
+
+        assert_eq!(
+            array.element(2),
+            Err(Error::ArrayElementPosition(array.to_string(), 2))
+        );
+    }
+
+    #[test]
+    fn len() {
+        let context = Context::new();
+        let array = ArrayAttribute::new(
+            &context,
+            &[
+                Attribute::parse(&context, "42 : i64").unwrap(),
+                Attribute::parse(&context, "\"foo\"").unwrap(),
+            ],
+        );
+
+        assert_eq!(array.len(), 2);
+        assert!(ArrayAttribute::new(&context, &[]).is_empty());
+    }
+
+    #[test]
+    fn elements() {
+        let context = Context::new();
+        let array = ArrayAttribute::new(
+            &context,
+            &[
+                Attribute::parse(&context, "42 : i64").unwrap(),
+                Attribute::parse(&context, "\"foo\"").unwrap(),
+            ],
+        );
+
+        assert_eq!(
+            array
+                .elements()
+                .map(|attribute| attribute.to_string())
+                .collect::<Vec<_>>(),
+            ["42 : i64", "\"foo\""]
+        );
+    }
+
+    #[test]
+    fn try_from() {
+        let context = Context::new();
+        let attribute = Attribute::parse(&context, "unit").unwrap();
+
+        assert_eq!(
+            ArrayAttribute::try_from(attribute),
+            Err(Error::ArrayExpected(attribute.to_string()))
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use super::Attribute;
+use crate::{context::Context, ir::Identifier, string_ref::StringRef, Error};
+use mlir_sys::{
+    mlirDictionaryAttrGet, mlirDictionaryAttrGetElement, mlirDictionaryAttrGetElementByName,
+    mlirDictionaryAttrGetNumElements, mlirNamedAttributeGet, MlirAttribute, MlirContext,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// A dictionary attribute.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DictionaryAttribute<'c> {
+    attribute: Attribute<'c>,
+}
+
+impl<'c> DictionaryAttribute<'c> {
+    /// Creates a dictionary attribute.
+    ///
+    /// It returns an error if elements have duplicate names.
+    pub fn new(
+        context: &'c Context,
+        elements: &[(Identifier<'c>, Attribute<'c>)],
+    ) -> Result<Self, Error> {
+        for (index, (name, _)) in elements.iter().enumerate() {
+            if elements[..index].iter().any(|(other, _)| other == name) {
+                return Err(Error::DuplicateAttribute(
+                    name.as_string_ref().as_str().unwrap().into(),
+                ));
+            }
+        }
+
+        Ok(unsafe { Self::from_raw(Self::get_raw(context.to_raw(), elements)) })
+    }
+
+    /// Gets an element at a position.
+    pub fn element(&self, position: usize) -> Result<(Identifier<'c>, Attribute<'c>), Error> {
+        if position < self.element_count() {
+            unsafe {
+                let element =
+                    mlirDictionaryAttrGetElement(self.attribute.to_raw(), position as isize);
+
+                Ok((
+                    Identifier::from_raw(element.name),
+                    Attribute::from_raw(element.attribute),
+                ))
+            }
+        } else {
+            Err(Error::DictionaryElementPosition(self.to_string(), position))
+        }
+    }
+
+    /// Gets an element of a name.
+    pub fn get(&self, name: &str) -> Option<Attribute<'c>> {
+        unsafe {
+            Attribute::from_option_raw(mlirDictionaryAttrGetElementByName(
+                self.attribute.to_raw(),
+                StringRef::from(name).to_raw(),
+            ))
+        }
+    }
+
+    /// Gets a number of elements.
+    pub fn element_count(&self) -> usize {
+        unsafe { mlirDictionaryAttrGetNumElements(self.attribute.to_raw()) as usize }
+    }
+
+    /// Returns `true` if a dictionary has no element.
+    pub fn is_empty(&self) -> bool {
+        self.element_count() == 0
+    }
+
+    /// Gets elements sorted by their names.
+    pub fn elements(&self) -> impl Iterator<Item = (Identifier<'c>, Attribute<'c>)> {
+        let dictionary = *self;
+
+        (0..self.element_count()).map(move |position| dictionary.element(position).unwrap())
+    }
+
+    pub(crate) unsafe fn get_raw(
+        context: MlirContext,
+        elements: &[(Identifier<'c>, Attribute<'c>)],
+    ) -> MlirAttribute {
+        mlirDictionaryAttrGet(
+            context,
+            elements.len() as isize,
+            elements
+                .iter()
+                .map(|(name, attribute)| mlirNamedAttributeGet(name.to_raw(), attribute.to_raw()))
+                .collect::<Vec<_>>()
+                .as_ptr(),
+        )
+    }
+
+    pub(crate) unsafe fn from_raw(raw: MlirAttribute) -> Self {
+        Self {
+            attribute: Attribute::from_raw(raw),
+        }
+    }
+}
+
+impl<'c> Display for DictionaryAttribute<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.attribute.fmt(formatter)
+    }
+}
+
+impl<'c> From<DictionaryAttribute<'c>> for Attribute<'c> {
+    fn from(dictionary: DictionaryAttribute<'c>) -> Self {
+        dictionary.attribute
+    }
+}
+
+impl<'c> TryFrom<Attribute<'c>> for DictionaryAttribute<'c> {
+    type Error = Error;
+
+    fn try_from(attribute: Attribute<'c>) -> Result<Self, Self::Error> {
+        if attribute.is_dictionary() {
+            Ok(Self { attribute })
+        } else {
+            Err(Error::DictionaryExpected(attribute.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let context = Context::new();
+        let dictionary = DictionaryAttribute::new(
+            &context,
+            &[
+                (
+                    Identifier::new(&context, "foo"),
+                    Attribute::parse(&context, "42 : i64").unwrap(),
+                ),
+                (
+                    Identifier::new(&context, "bar"),
+                    Attribute::parse(&context, "\"baz\"").unwrap(),
+                ),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            Attribute::from(dictionary),
+            Attribute::parse(&context, "{bar = \"baz\", foo = 42 : i64}").unwrap()
+        );
+    }
+
+    #[test]
+    fn new_with_duplicate_names() {
+        let context = Context::new();
+        let name = Identifier::new(&context, "foo");
+        let attribute = Attribute::unit(&context);
+
+        assert_eq!(
+            DictionaryAttribute::new(&context, &[(name, attribute), (name, attribute)]),
+            Err(Error::DuplicateAttribute("foo".into()))
+        );
+    }
+
+    #[test]
+    fn element() {
+        let context = Context::new();
+        let dictionary = DictionaryAttribute::new(
+            &context,
+            &[
+                (
+                    Identifier::new(&context, "foo"),
+                    Attribute::parse(&context, "42 : i64").unwrap(),
+                ),
+                (
+                    Identifier::new(&context, "bar"),
+                    Attribute::parse(&context, "\"baz\"").unwrap(),
+                ),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            dictionary.element(0),
+            Ok((
+                Identifier::new(&context, "bar"),
+                Attribute::parse(&context, "\"baz\"").unwrap()
+            ))
+        );
+        assert_eq!(
+            dictionary.element(2),
+            Err(Error::DictionaryElementPosition(dictionary.to_string(), 2))
+        );
+    }
+
+    #[test]
+    fn get() {
+        let context = Context::new();
+        let dictionary = DictionaryAttribute::new(
+            &context,
+            &[
+                (
+                    Identifier::new(&context, "foo"),
+                    Attribute::parse(&context, "42 : i64").unwrap(),
+                ),
+                (
+                    Identifier::new(&context, "bar"),
+                    Attribute::parse(&context, "\"baz\"").unwrap(),
+                ),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            dictionary.get("foo"),
+            Attribute::parse(&context, "42 : i64")
+        );
+        assert_eq!(dictionary.get("qux"), None);
+    }
+
+    #[test]
+    fn elements() {
+        let context = Context::new();
+        let dictionary = DictionaryAttribute::new(
+            &context,
+            &[
+                (
+                    Identifier::new(&context, "foo"),
+                    Attribute::parse(&context, "42 : i64").unwrap(),
+                ),
+                (
+                    Identifier::new(&context, "bar"),
+                    Attribute::parse(&context, "\"baz\"").unwrap(),
+                ),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(dictionary.element_count(), 2);
+        assert_eq!(
+            dictionary
+                .elements()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            [
+                Identifier::new(&context, "bar"),
+                Identifier::new(&context, "foo")
+            ]
+        );
+        assert!(DictionaryAttribute::new(&context, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn try_from() {
+        let context = Context::new();
+        let attribute = Attribute::parse(&context, "42 : i64").unwrap();
+
+        assert_eq!(
+            DictionaryAttribute::try_from(attribute),
+            Err(Error::DictionaryExpected(attribute.to_string()))
+        );
+    }
+}
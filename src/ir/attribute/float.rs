@@ -0,0 +1,154 @@
+use super::Attribute;
+use crate::{
+    context::Context,
+    ir::{Location, Type, TypeLike},
+    Error,
+};
+use mlir_sys::{mlirFloatAttrDoubleGetChecked, mlirFloatAttrGetValueDouble, MlirAttribute};
+use std::fmt::{self, Display, Formatter};
+
+/// A float attribute.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FloatAttribute<'c> {
+    attribute: Attribute<'c>,
+}
+
+impl<'c> FloatAttribute<'c> {
+    /// Creates a float attribute.
+    ///
+    /// A value is converted into a float type without going through its
+    /// textual representation.
+    pub fn new(context: &'c Context, value: f64, r#type: Type<'c>) -> Result<Self, Error> {
+        let raw = unsafe {
+            mlirFloatAttrDoubleGetChecked(
+                Location::unknown(context).to_raw(),
+                r#type.to_raw(),
+                value,
+            )
+        };
+
+        if raw.ptr.is_null() {
+            Err(Error::FloatExpected(r#type.to_string()))
+        } else {
+            Ok(unsafe { Self::from_raw(raw) })
+        }
+    }
+
+    /// Gets a value.
+    pub fn value(&self) -> f64 {
+        unsafe { mlirFloatAttrGetValueDouble(self.attribute.to_raw()) }
+    }
+
+    pub(crate) unsafe fn from_raw(raw: MlirAttribute) -> Self {
+        Self {
+            attribute: Attribute::from_raw(raw),
+        }
+    }
+}
+
+impl<'c> Display for FloatAttribute<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.attribute.fmt(formatter)
+    }
+}
+
+impl<'c> From<FloatAttribute<'c>> for Attribute<'c> {
+    fn from(float: FloatAttribute<'c>) -> Self {
+        float.attribute
+    }
+}
+
+impl<'c> TryFrom<Attribute<'c>> for FloatAttribute<'c> {
+    type Error = Error;
+
+    fn try_from(attribute: Attribute<'c>) -> Result<Self, Self::Error> {
+        if attribute.is_float() {
+            Ok(Self { attribute })
+        } else {
+            Err(Error::FloatExpected(attribute.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::from(FloatAttribute::new(&context, 42.0, Type::float64(&context)).unwrap()),
+            Attribute::parse(&context, "42.0 : f64").unwrap()
+        );
+    }
+
+    #[test]
+    fn value() {
+        let context = Context::new();
+
+        assert_eq!(
+            FloatAttribute::new(&context, 0.1, Type::float64(&context))
+                .unwrap()
+                .value(),
+            0.1
+        );
+    }
+
+    #[test]
+    fn value_with_float32() {
+        let context = Context::new();
+
+        assert_eq!(
+            FloatAttribute::new(&context, 0.5, Type::float32(&context))
+                .unwrap()
+                .value(),
+            0.5
+        );
+    }
+
+    #[test]
+    fn new_with_integer() {
+        let context = Context::new();
+        let r#type = Type::integer(&context, 64);
+
+        assert_eq!(
+            FloatAttribute::new(&context, 42.0, r#type),
+            Err(Error::FloatExpected(r#type.to_string()))
+        );
+    }
+
+    #[test]
+    fn new_with_float80() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::from(FloatAttribute::new(&context, 1.5, Type::float80(&context)).unwrap()),
+            Attribute::parse(&context, "1.5 : f80").unwrap()
+        );
+    }
+
+    #[test]
+    fn try_from() {
+        let context = Context::new();
+
+        assert_eq!(
+            FloatAttribute::try_from(Attribute::parse(&context, "1.5 : f32").unwrap())
+                .unwrap()
+                .value(),
+            1.5
+        );
+    }
+
+    #[test]
+    fn try_from_error() {
+        let context = Context::new();
+        let attribute = Attribute::parse(&context, "42 : i64").unwrap();
+
+        assert_eq!(
+            FloatAttribute::try_from(attribute),
+            Err(Error::FloatExpected(attribute.to_string()))
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use super::Attribute;
+use crate::{
+    context::Context,
+    ir::{Type, TypeLike},
+    Error,
+};
+use mlir_sys::{mlirIntegerAttrGet, mlirIntegerAttrGetValueInt, MlirAttribute};
+use std::fmt::{self, Display, Formatter};
+
+/// An integer attribute.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct IntegerAttribute<'c> {
+    attribute: Attribute<'c>,
+}
+
+impl<'c> IntegerAttribute<'c> {
+    /// Creates an integer attribute of an integer or `index` type.
+    pub fn new(value: i64, r#type: Type<'c>) -> Result<Self, Error> {
+        if r#type.is_integer() || r#type.is_index() {
+            Ok(unsafe { Self::from_raw(mlirIntegerAttrGet(r#type.to_raw(), value)) })
+        } else {
+            Err(Error::IntegerExpected(r#type.to_string()))
+        }
+    }
+
+    /// Creates an integer attribute of the `index` type.
+    pub fn index(context: &'c Context, value: i64) -> Self {
+        // Index types are always valid.
+        Self::new(value, Type::index(context)).unwrap()
+    }
+
+    /// Gets a value.
+    pub fn value(&self) -> i64 {
+        unsafe { mlirIntegerAttrGetValueInt(self.attribute.to_raw()) }
+    }
+
+    pub(crate) unsafe fn from_raw(raw: MlirAttribute) -> Self {
+        Self {
+            attribute: Attribute::from_raw(raw),
+        }
+    }
+}
+
+impl<'c> Display for IntegerAttribute<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.attribute.fmt(formatter)
+    }
+}
+
+impl<'c> From<IntegerAttribute<'c>> for Attribute<'c> {
+    fn from(integer: IntegerAttribute<'c>) -> Self {
+        integer.attribute
+    }
+}
+
+impl<'c> TryFrom<Attribute<'c>> for IntegerAttribute<'c> {
+    type Error = Error;
+
+    fn try_from(attribute: Attribute<'c>) -> Result<Self, Self::Error> {
+        if attribute.is_integer() {
+            Ok(Self { attribute })
+        } else {
+            Err(Error::IntegerAttributeExpected(attribute.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::from(IntegerAttribute::new(42, Type::integer(&context, 64)).unwrap()),
+            Attribute::parse(&context, "42 : i64").unwrap()
+        );
+    }
+
+    #[test]
+    fn new_with_float() {
+        let context = Context::new();
+        let r#type = Type::float32(&context);
+
+        assert_eq!(
+            IntegerAttribute::new(42, r#type),
+            Err(Error::IntegerExpected(r#type.to_string()))
+        );
+    }
+
+    #[test]
+    fn index() {
+        let context = Context::new();
+
+        assert_eq!(
+            Attribute::from(IntegerAttribute::index(&context, 0)),
+            Attribute::parse(&context, "0 : index").unwrap()
+        );
+    }
+
+    #[test]
+    fn value() {
+        let context = Context::new();
+
+        assert_eq!(IntegerAttribute::index(&context, -42).value(), -42);
+    }
+
+    #[test]
+    fn try_from_error() {
+        let context = Context::new();
+        let attribute = Attribute::parse(&context, "1.5 : f32").unwrap();
+
+        assert_eq!(
+            IntegerAttribute::try_from(attribute),
+            Err(Error::IntegerAttributeExpected(attribute.to_string()))
+        );
+    }
+}
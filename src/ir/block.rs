@@ -3,12 +3,8 @@
 mod argument;
 
 pub use self::argument::Argument;
-use super::{Location, Operation, OperationRef, RegionRef, Type, TypeLike, Value};
-use crate::{
-    context::Context,
-    utility::{into_raw_array, print_callback},
-    Error,
-};
+use super::{generation, Location, Operation, OperationRef, RegionRef, Type, TypeLike, Value};
+use crate::{context::Context, utility::print_callback, Error};
 use mlir_sys::{
     mlirBlockAddArgument, mlirBlockAppendOwnedOperation, mlirBlockCreate, mlirBlockDestroy,
     mlirBlockDetach, mlirBlockEqual, mlirBlockGetArgument, mlirBlockGetFirstOperation,
@@ -20,6 +16,7 @@ use mlir_sys::{
 use std::{
     ffi::c_void,
     fmt::{self, Debug, Display, Formatter},
+    iter::successors,
     marker::PhantomData,
     mem::forget,
     ops::Deref,
@@ -28,7 +25,7 @@ use std::{
 /// A block
 #[derive(Debug)]
 pub struct Block<'c> {
-    r#ref: BlockRef<'static>,
+    r#ref: BlockRef<'c>,
     _context: PhantomData<&'c Context>,
 }
 
@@ -38,18 +35,16 @@ impl<'c> Block<'c> {
         unsafe {
             Self::from_raw(mlirBlockCreate(
                 arguments.len() as isize,
-                into_raw_array(
-                    arguments
-                        .iter()
-                        .map(|(argument, _)| argument.to_raw())
-                        .collect(),
-                ),
-                into_raw_array(
-                    arguments
-                        .iter()
-                        .map(|(_, location)| location.to_raw())
-                        .collect(),
-                ),
+                arguments
+                    .iter()
+                    .map(|(argument, _)| argument.to_raw())
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
+                arguments
+                    .iter()
+                    .map(|(_, location)| location.to_raw())
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
             ))
         }
     }
@@ -68,11 +63,37 @@ impl<'c> Block<'c> {
 
         block
     }
+
+    #[cfg(feature = "drop-diagnostics")]
+    fn operation_names(&self) -> String {
+        self.operations()
+            .map(|operation| {
+                operation
+                    .name()
+                    .as_string_ref()
+                    .as_str()
+                    .unwrap_or("<invalid>")
+                    .to_owned()
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 }
 
 impl<'c> Drop for Block<'c> {
     fn drop(&mut self) {
-        unsafe { mlirBlockDestroy(self.raw) };
+        #[cfg(feature = "drop-diagnostics")]
+        if self.operation_count() > 0 && !std::thread::panicking() {
+            eprintln!(
+                "melior: detached block dropped with operations: {}",
+                self.operation_names()
+            );
+        }
+
+        unsafe {
+            generation::invalidate_block(self.raw);
+            mlirBlockDestroy(self.raw);
+        }
     }
 }
 
@@ -85,7 +106,7 @@ impl<'c> PartialEq for Block<'c> {
 impl<'c> Eq for Block<'c> {}
 
 impl<'c> Deref for Block<'c> {
-    type Target = BlockRef<'static>;
+    type Target = BlockRef<'c>;
 
     fn deref(&self) -> &Self::Target {
         &self.r#ref
@@ -96,16 +117,18 @@ impl<'c> Deref for Block<'c> {
 #[derive(Clone, Copy)]
 pub struct BlockRef<'a> {
     raw: MlirBlock,
+    #[cfg(debug_assertions)]
+    generation: generation::Generation,
     _reference: PhantomData<&'a Block<'a>>,
 }
 
 impl<'c> BlockRef<'c> {
     /// Gets an argument at a position.
-    pub fn argument(&self, position: usize) -> Result<Argument, Error> {
+    pub fn argument(&self, position: usize) -> Result<Argument<'c>, Error> {
         unsafe {
             if position < self.argument_count() as usize {
                 Ok(Argument::from_raw(mlirBlockGetArgument(
-                    self.raw,
+                    self.to_raw(),
                     position as isize,
                 )))
             } else {
@@ -116,13 +139,13 @@ impl<'c> BlockRef<'c> {
 
     /// Gets a number of arguments.
     pub fn argument_count(&self) -> usize {
-        unsafe { mlirBlockGetNumArguments(self.raw) as usize }
+        unsafe { mlirBlockGetNumArguments(self.to_raw()) as usize }
     }
 
     /// Gets the first operation.
     pub fn first_operation(&self) -> Option<OperationRef> {
         unsafe {
-            let operation = mlirBlockGetFirstOperation(self.raw);
+            let operation = mlirBlockGetFirstOperation(self.to_raw());
 
             if operation.ptr.is_null() {
                 None
@@ -132,26 +155,39 @@ impl<'c> BlockRef<'c> {
         }
     }
 
+    /// Gets operations.
+    pub fn operations(&self) -> impl Iterator<Item = OperationRef> {
+        successors(self.first_operation(), |operation| {
+            operation.next_in_block()
+        })
+    }
+
+    /// Gets a number of operations.
+    pub fn operation_count(&self) -> usize {
+        self.operations().count()
+    }
+
     /// Gets a terminator operation.
     pub fn terminator(&self) -> Option<OperationRef> {
-        unsafe { OperationRef::from_option_raw(mlirBlockGetTerminator(self.raw)) }
+        unsafe { OperationRef::from_option_raw(mlirBlockGetTerminator(self.to_raw())) }
     }
 
     /// Gets a parent region.
     pub fn parent_region(&self) -> Option<RegionRef> {
-        unsafe { RegionRef::from_option_raw(mlirBlockGetParentRegion(self.raw)) }
+        unsafe { RegionRef::from_option_raw(mlirBlockGetParentRegion(self.to_raw())) }
     }
 
     /// Gets a parent operation.
     pub fn parent_operation(&self) -> Option<OperationRef> {
-        unsafe { OperationRef::from_option_raw(mlirBlockGetParentOperation(self.raw)) }
+        unsafe { OperationRef::from_option_raw(mlirBlockGetParentOperation(self.to_raw())) }
     }
 
     /// Adds an argument.
+    // TODO Insert and erase arguments at positions.
     pub fn add_argument(&self, r#type: Type<'c>, location: Location<'c>) -> Value {
         unsafe {
             Value::from_raw(mlirBlockAddArgument(
-                self.raw,
+                self.to_raw(),
                 r#type.to_raw(),
                 location.to_raw(),
             ))
@@ -159,76 +195,199 @@ impl<'c> BlockRef<'c> {
     }
 
     /// Appends an operation.
-    pub fn append_operation(&self, operation: Operation) -> OperationRef<'c> {
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operation belongs to a context different from the one of
+    /// the parent operation of the block.
+    pub fn append_operation(&self, operation: Operation<'c>) -> OperationRef<'c> {
+        self.check_context(&operation);
+
         unsafe {
             let operation = operation.into_raw();
 
-            mlirBlockAppendOwnedOperation(self.raw, operation);
+            mlirBlockAppendOwnedOperation(self.to_raw(), operation);
 
             OperationRef::from_raw(operation)
         }
     }
 
     /// Inserts an operation.
-    // TODO How can we make those update functions take `&mut self`?
-    // TODO Use cells?
-    pub fn insert_operation(&self, position: usize, operation: Operation) -> OperationRef {
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operation belongs to a context different from the one of
+    /// the parent operation of the block.
+    pub fn insert_operation(&self, position: usize, operation: Operation<'c>) -> OperationRef<'c> {
+        self.check_context(&operation);
+
         unsafe {
             let operation = operation.into_raw();
 
-            mlirBlockInsertOwnedOperation(self.raw, position as isize, operation);
+            mlirBlockInsertOwnedOperation(self.to_raw(), position as isize, operation);
 
             OperationRef::from_raw(operation)
         }
     }
 
     /// Inserts an operation after another.
-    pub fn insert_operation_after(&self, one: OperationRef, other: Operation) -> OperationRef {
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operation belongs to a context different from the one of
+    /// the parent operation of the block.
+    pub fn insert_operation_after(
+        &self,
+        one: OperationRef,
+        other: Operation<'c>,
+    ) -> OperationRef<'c> {
+        self.check_context(&other);
+
         unsafe {
             let other = other.into_raw();
 
-            mlirBlockInsertOwnedOperationAfter(self.raw, one.to_raw(), other);
+            mlirBlockInsertOwnedOperationAfter(self.to_raw(), one.to_raw(), other);
 
             OperationRef::from_raw(other)
         }
     }
 
     /// Inserts an operation before another.
-    pub fn insert_operation_before(&self, one: OperationRef, other: Operation) -> OperationRef {
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operation belongs to a context different from the one of
+    /// the parent operation of the block.
+    pub fn insert_operation_before(
+        &self,
+        one: OperationRef,
+        other: Operation<'c>,
+    ) -> OperationRef<'c> {
+        self.check_context(&other);
+
         unsafe {
             let other = other.into_raw();
 
-            mlirBlockInsertOwnedOperationBefore(self.raw, one.to_raw(), other);
+            mlirBlockInsertOwnedOperationBefore(self.to_raw(), one.to_raw(), other);
 
             OperationRef::from_raw(other)
         }
     }
 
+    // Lifetimes alone cannot tell contexts apart because they can be shortened.
+    fn check_context(&self, operation: &Operation) {
+        if let Some(parent) = self.parent_operation() {
+            assert!(
+                parent.context() == operation.context(),
+                "operation from another context"
+            );
+        }
+    }
+
     /// Detaches a block from a region and assumes its ownership.
     ///
     /// # Safety
     ///
-    /// This function might invalidate existing references to the block if you
-    /// drop it too early.
-    // TODO Implement this for BlockRefMut instead and mark it safe.
+    /// This function might invalidate existing references to the block and
+    /// values in it if you drop it too early. References to the block and its
+    /// operations are checked in debug builds but values are not.
+    // TODO Mark this safe when values are tracked as well.
     pub unsafe fn detach(&self) -> Option<Block> {
         if self.parent_region().is_some() {
-            mlirBlockDetach(self.raw);
+            mlirBlockDetach(self.to_raw());
 
-            Some(Block::from_raw(self.raw))
+            Some(Block::from_raw(self.to_raw()))
         } else {
             None
         }
     }
 
+    /// Splits a block before an operation.
+    ///
+    /// The operation and all operations after it are moved into a new block
+    /// without arguments, which is inserted after the block in its region and
+    /// returned. The block is left without a terminator.
+    pub fn split_before(&self, operation: OperationRef) -> Result<BlockRef<'c>, Error> {
+        let region = match self.parent_region() {
+            Some(region) if operation.block() == Some(*self) => region,
+            _ => return Err(Error::SplitBlock(self.to_string())),
+        };
+
+        let operations =
+            successors(Some(operation), |operation| operation.next_in_block()).collect::<Vec<_>>();
+        let block = Block::new(&[]);
+        let r#ref = unsafe { BlockRef::from_raw(block.to_raw()) };
+
+        region.insert_block_after(*self, block);
+
+        for operation in operations {
+            // Operations are only moved and never dropped.
+            r#ref.append_operation(unsafe { operation.remove_from_parent() }.unwrap());
+        }
+
+        Ok(r#ref)
+    }
+
+    /// Merges a successor into a block.
+    ///
+    /// A terminator of the block must branch only to the successor without
+    /// operands or results, and the successor must have no arguments and no other
+    /// predecessors. The terminator is erased, operations of the successor are
+    /// moved to the end of the block, and the successor is erased.
+    pub fn merge_with_successor(&self) -> Result<(), Error> {
+        let error = || Error::MergeBlock(self.to_string());
+        let region = self.parent_region().ok_or_else(error)?;
+        let terminator = self.terminator().ok_or_else(error)?;
+
+        if terminator.successor_count() != 1
+            || terminator.operand_count() != 0
+            || terminator.result_count() != 0
+        {
+            return Err(error());
+        }
+
+        let successor = terminator.successor(0)?;
+        let mut predecessor_count = 0;
+
+        for block in region.blocks() {
+            if let Some(terminator) = block.terminator() {
+                predecessor_count += terminator
+                    .successors()
+                    .filter(|&block| block == successor)
+                    .count();
+            }
+        }
+
+        if successor == *self || successor.argument_count() != 0 || predecessor_count != 1 {
+            return Err(error());
+        }
+
+        let operations = successor.operations().collect::<Vec<_>>();
+
+        // The terminator has neither operands nor results.
+        unsafe { terminator.erase() };
+
+        for operation in operations {
+            // Operations are only moved and never dropped.
+            self.append_operation(unsafe { operation.remove_from_parent() }.unwrap());
+        }
+
+        // The successor has neither arguments nor operations anymore.
+        drop(unsafe { successor.detach() });
+
+        Ok(())
+    }
+
     /// Gets a next block in a region.
-    pub fn next_in_region(&self) -> Option<BlockRef> {
-        unsafe { BlockRef::from_option_raw(mlirBlockGetNextInRegion(self.raw)) }
+    pub fn next_in_region(&self) -> Option<BlockRef<'c>> {
+        unsafe { BlockRef::from_option_raw(mlirBlockGetNextInRegion(self.to_raw())) }
     }
 
     pub(crate) unsafe fn from_raw(raw: MlirBlock) -> Self {
         Self {
             raw,
+            #[cfg(debug_assertions)]
+            generation: generation::Generation::new(raw.ptr),
             _reference: Default::default(),
         }
     }
@@ -242,6 +401,9 @@ impl<'c> BlockRef<'c> {
     }
 
     pub(crate) unsafe fn to_raw(self) -> MlirBlock {
+        #[cfg(debug_assertions)]
+        self.generation.check(self.raw.ptr, "block");
+
         self.raw
     }
 }
@@ -260,7 +422,7 @@ impl<'a> Display for BlockRef<'a> {
 
         unsafe {
             mlirBlockPrint(
-                self.raw,
+                self.to_raw(),
                 Some(print_callback),
                 &mut data as *mut _ as *mut c_void,
             );
@@ -364,7 +526,9 @@ mod tests {
         let block = Block::new(&[]);
 
         let operation = block.append_operation(
-            operation::Builder::new("func.return", Location::unknown(&context)).build(),
+            operation::Builder::new("func.return", Location::unknown(&context))
+                .build()
+                .unwrap(),
         );
 
         assert_eq!(block.terminator(), Some(operation));
@@ -380,8 +544,11 @@ mod tests {
         let context = Context::new();
         let block = Block::new(&[]);
 
-        let operation = block
-            .append_operation(operation::Builder::new("foo", Location::unknown(&context)).build());
+        let operation = block.append_operation(
+            operation::Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
 
         assert_eq!(block.first_operation(), Some(operation));
     }
@@ -393,12 +560,64 @@ mod tests {
         assert_eq!(block.first_operation(), None);
     }
 
+    #[test]
+    fn operations() {
+        let context = Context::new();
+        let block = Block::new(&[]);
+
+        let first_operation = block.append_operation(
+            operation::Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
+        let second_operation = block.append_operation(
+            operation::Builder::new("bar", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
+
+        assert_eq!(
+            block.operations().collect::<Vec<_>>(),
+            vec![first_operation, second_operation]
+        );
+    }
+
+    #[test]
+    fn operations_empty() {
+        assert_eq!(Block::new(&[]).operations().next(), None);
+    }
+
+    #[test]
+    fn operation_count() {
+        let context = Context::new();
+        let block = Block::new(&[]);
+
+        assert_eq!(block.operation_count(), 0);
+
+        block.append_operation(
+            operation::Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
+        block.append_operation(
+            operation::Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
+
+        assert_eq!(block.operation_count(), 2);
+    }
+
     #[test]
     fn append_operation() {
         let context = Context::new();
         let block = Block::new(&[]);
 
-        block.append_operation(operation::Builder::new("foo", Location::unknown(&context)).build());
+        block.append_operation(
+            operation::Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
     }
 
     #[test]
@@ -408,7 +627,9 @@ mod tests {
 
         block.insert_operation(
             0,
-            operation::Builder::new("foo", Location::unknown(&context)).build(),
+            operation::Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
         );
     }
 
@@ -417,11 +638,16 @@ mod tests {
         let context = Context::new();
         let block = Block::new(&[]);
 
-        let first_operation = block
-            .append_operation(operation::Builder::new("foo", Location::unknown(&context)).build());
+        let first_operation = block.append_operation(
+            operation::Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
         let second_operation = block.insert_operation_after(
             first_operation,
-            operation::Builder::new("foo", Location::unknown(&context)).build(),
+            operation::Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
         );
 
         assert_eq!(block.first_operation(), Some(first_operation));
@@ -436,11 +662,16 @@ mod tests {
         let context = Context::new();
         let block = Block::new(&[]);
 
-        let second_operation = block
-            .append_operation(operation::Builder::new("foo", Location::unknown(&context)).build());
+        let second_operation = block.append_operation(
+            operation::Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
         let first_operation = block.insert_operation_before(
             second_operation,
-            operation::Builder::new("foo", Location::unknown(&context)).build(),
+            operation::Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
         );
 
         assert_eq!(block.first_operation(), Some(first_operation));
@@ -478,6 +709,125 @@ mod tests {
         assert!(unsafe { block.detach() }.is_none());
     }
 
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "reference to destroyed block")]
+    fn use_dropped_block() {
+        let region = Region::new();
+        let block = region.append_block(Block::new(&[]));
+
+        drop(unsafe { block.detach() });
+
+        block.argument_count();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "reference to destroyed operation")]
+    fn use_operation_in_dropped_block() {
+        let context = Context::new();
+        let block = Block::new(&[]);
+        let operation = block.append_operation(
+            operation::Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
+
+        drop(block);
+
+        operation.name();
+    }
+
+    #[test]
+    fn split_before() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let region = Region::new();
+        let block = region.append_block(Block::new(&[]));
+
+        block.append_operation(operation::Builder::new("foo", location).build().unwrap());
+        let operation =
+            block.append_operation(operation::Builder::new("bar", location).build().unwrap());
+        block.append_operation(operation::Builder::new("baz", location).build().unwrap());
+
+        let new_block = block.split_before(operation).unwrap();
+
+        assert_eq!(block.next_in_region(), Some(new_block));
+        assert_eq!(block.operation_count(), 1);
+        assert_eq!(new_block.operation_count(), 2);
+        assert_eq!(new_block.first_operation(), Some(operation));
+        assert_eq!(operation.block(), Some(new_block));
+    }
+
+    #[test]
+    fn split_before_operation_in_other_block() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let region = Region::new();
+        let block = region.append_block(Block::new(&[]));
+        let other_block = region.append_block(Block::new(&[]));
+        let operation =
+            other_block.append_operation(operation::Builder::new("foo", location).build().unwrap());
+
+        assert_eq!(
+            block.split_before(operation),
+            Err(Error::SplitBlock(block.to_string()))
+        );
+    }
+
+    #[test]
+    fn merge_with_successor() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let region = Region::new();
+        let block = region.append_block(Block::new(&[]));
+        let successor = region.append_block(Block::new(&[]));
+
+        block.append_operation(operation::Builder::new("foo", location).build().unwrap());
+        block.append_operation(dialect::cf::br(successor, &[], location).unwrap());
+        successor.append_operation(
+            operation::Builder::new("func.return", location)
+                .build()
+                .unwrap(),
+        );
+
+        block.merge_with_successor().unwrap();
+
+        assert_eq!(region.blocks().count(), 1);
+        assert_eq!(block.operation_count(), 2);
+        assert!(block.terminator().unwrap().is("func.return"));
+    }
+
+    #[test]
+    fn merge_with_successor_with_other_predecessor() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let region = Region::new();
+        let block = region.append_block(Block::new(&[]));
+        let other_block = region.append_block(Block::new(&[]));
+        let successor = region.append_block(Block::new(&[]));
+
+        block.append_operation(dialect::cf::br(successor, &[], location).unwrap());
+        other_block.append_operation(dialect::cf::br(successor, &[], location).unwrap());
+
+        assert_eq!(
+            block.merge_with_successor(),
+            Err(Error::MergeBlock(block.to_string()))
+        );
+        assert_eq!(region.blocks().count(), 3);
+    }
+
+    #[test]
+    fn merge_with_successor_without_terminator() {
+        let region = Region::new();
+        let block = region.append_block(Block::new(&[]));
+
+        assert_eq!(
+            block.merge_with_successor(),
+            Err(Error::MergeBlock(block.to_string()))
+        );
+    }
+
     #[test]
     fn display() {
         assert_eq!(Block::new(&[]).to_string(), "<<UNLINKED BLOCK>>\n");
@@ -490,4 +840,17 @@ mod tests {
             "BlockRef(\n<<UNLINKED BLOCK>>\n)"
         );
     }
+
+    #[test]
+    #[cfg(feature = "drop-diagnostics")]
+    fn drop_with_operations() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[]);
+
+        block.append_operation(operation::Builder::new("foo", location).build().unwrap());
+        block.append_operation(operation::Builder::new("bar", location).build().unwrap());
+
+        assert_eq!(block.operation_names(), "foo, bar");
+    }
 }
@@ -9,6 +9,7 @@ use mlir_sys::{
 use std::fmt::{self, Display, Formatter};
 
 /// A block argument.
+// TODO Get and set locations of block arguments.
 #[derive(Clone, Copy, Debug)]
 pub struct Argument<'a> {
     value: Value<'a>,
@@ -34,7 +35,7 @@ impl<'a> Argument<'a> {
     }
 }
 
-impl<'a> ValueLike for Argument<'a> {
+impl<'a> ValueLike<'a> for Argument<'a> {
     fn to_raw(&self) -> MlirValue {
         self.value.to_raw()
     }
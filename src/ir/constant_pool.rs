@@ -0,0 +1,255 @@
+use super::{
+    operation, Attribute, BlockRef, Identifier, Location, OperationRef, Type, Value, ValueLike,
+};
+use crate::context::Context;
+use std::collections::HashMap;
+
+/// A constant pool.
+///
+/// It deduplicates constant operations in a block. Constant operations are
+/// inserted at the beginning of the block so that their values dominate the
+/// other operations in it. They must not be erased while the pool is used.
+#[derive(Debug)]
+pub struct ConstantPool<'c> {
+    context: &'c Context,
+    block: BlockRef<'c>,
+    values: HashMap<String, HashMap<(Attribute<'c>, Type<'c>), Value<'c>>>,
+}
+
+impl<'c> ConstantPool<'c> {
+    /// Creates a constant pool of a block.
+    pub fn new(context: &'c Context, block: BlockRef<'c>) -> Self {
+        Self {
+            context,
+            block,
+            values: HashMap::new(),
+        }
+    }
+
+    /// Gets a value of an `arith.constant` operation.
+    pub fn arith_constant(
+        &mut self,
+        value: Attribute<'c>,
+        r#type: Type<'c>,
+        location: Location<'c>,
+    ) -> Value<'c> {
+        self.constant("arith.constant", value, r#type, location)
+    }
+
+    /// Gets a value of an `llvm.mlir.constant` operation.
+    pub fn llvm_constant(
+        &mut self,
+        value: Attribute<'c>,
+        r#type: Type<'c>,
+        location: Location<'c>,
+    ) -> Value<'c> {
+        self.constant("llvm.mlir.constant", value, r#type, location)
+    }
+
+    /// Gets a value of a constant operation with a `value` attribute.
+    ///
+    /// An existing value is returned if the same constant is already in a
+    /// pool, in which case a location is ignored.
+    pub fn constant(
+        &mut self,
+        name: &str,
+        value: Attribute<'c>,
+        r#type: Type<'c>,
+        location: Location<'c>,
+    ) -> Value<'c> {
+        if let Some(value) = self
+            .values
+            .get(name)
+            .and_then(|values| values.get(&(value, r#type)))
+        {
+            return *value;
+        }
+
+        let operation = operation::Builder::new(name, location)
+            .add_attributes(&[(Identifier::new(self.context, "value"), value)])
+            .add_results(&[r#type])
+            .build()
+            .unwrap();
+        // Constants are inserted after the leading ones in the pool because
+        // other operations can be inserted or moved in the block meanwhile.
+        let position = self
+            .block
+            .operations()
+            .take_while(|&operation| self.contains(operation))
+            .count();
+        let operation = self.block.insert_operation(position, operation);
+        let constant = operation.result(0).unwrap().into();
+
+        self.values
+            .entry(name.into())
+            .or_default()
+            .insert((value, r#type), constant);
+
+        constant
+    }
+
+    fn contains(&self, operation: OperationRef) -> bool {
+        match (
+            operation.name().as_string_ref().as_str(),
+            operation.attribute("value"),
+            operation.result(0),
+        ) {
+            (Ok(name), Some(value), Ok(result)) => {
+                self.values
+                    .get(name)
+                    .and_then(|values| values.get(&(value, result.r#type())))
+                    == Some(&result.into())
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Block;
+
+    fn operation_names(block: &Block) -> Vec<String> {
+        block
+            .operations()
+            .map(|operation| {
+                operation
+                    .name()
+                    .as_string_ref()
+                    .as_str()
+                    .unwrap()
+                    .to_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn new() {
+        ConstantPool::new(&Context::new(), *Block::new(&[]));
+    }
+
+    #[test]
+    fn arith_constant() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::integer(&context, 64);
+        let block = Block::new(&[]);
+        let mut pool = ConstantPool::new(&context, *block);
+
+        let value = pool.arith_constant(
+            Attribute::parse(&context, "42 : i64").unwrap(),
+            r#type,
+            location,
+        );
+
+        assert_eq!(value.r#type(), r#type);
+        assert_eq!(operation_names(&block), ["arith.constant"]);
+        assert!(block.first_operation().unwrap().verify());
+    }
+
+    #[test]
+    fn deduplicate_constants() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::integer(&context, 64);
+        let block = Block::new(&[]);
+        let mut pool = ConstantPool::new(&context, *block);
+        let attribute = Attribute::parse(&context, "42 : i64").unwrap();
+
+        assert_eq!(
+            pool.arith_constant(attribute, r#type, location),
+            pool.arith_constant(attribute, r#type, location)
+        );
+        assert_eq!(block.operation_count(), 1);
+    }
+
+    #[test]
+    fn distinguish_constants() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::integer(&context, 64);
+        let block = Block::new(&[]);
+        let mut pool = ConstantPool::new(&context, *block);
+        let attribute = Attribute::parse(&context, "42 : i64").unwrap();
+
+        assert_ne!(
+            pool.arith_constant(attribute, r#type, location),
+            pool.arith_constant(
+                Attribute::parse(&context, "0 : i64").unwrap(),
+                r#type,
+                location
+            )
+        );
+        assert_ne!(
+            pool.arith_constant(attribute, r#type, location),
+            pool.llvm_constant(attribute, r#type, location)
+        );
+        assert_eq!(
+            operation_names(&block),
+            ["arith.constant", "arith.constant", "llvm.mlir.constant"]
+        );
+    }
+
+    #[test]
+    fn insert_constants_at_beginning() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::integer(&context, 64);
+        let block = Block::new(&[]);
+        let mut pool = ConstantPool::new(&context, *block);
+
+        block.append_operation(operation::Builder::new("foo", location).build().unwrap());
+
+        pool.arith_constant(
+            Attribute::parse(&context, "0 : i64").unwrap(),
+            r#type,
+            location,
+        );
+        pool.arith_constant(
+            Attribute::parse(&context, "1 : i64").unwrap(),
+            r#type,
+            location,
+        );
+
+        assert_eq!(
+            operation_names(&block),
+            ["arith.constant", "arith.constant", "foo"]
+        );
+        assert_eq!(
+            block.first_operation().unwrap().to_string(),
+            "%c0_i64 = arith.constant 0 : i64\n"
+        );
+    }
+    #[test]
+    fn insert_constants_after_moving_constants() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::integer(&context, 64);
+        let block = Block::new(&[]);
+        let mut pool = ConstantPool::new(&context, *block);
+
+        let foo = block.append_operation(operation::Builder::new("foo", location).build().unwrap());
+
+        pool.arith_constant(
+            Attribute::parse(&context, "0 : i64").unwrap(),
+            r#type,
+            location,
+        );
+        block.first_operation().unwrap().move_after(foo);
+        pool.arith_constant(
+            Attribute::parse(&context, "1 : i64").unwrap(),
+            r#type,
+            location,
+        );
+
+        assert_eq!(
+            operation_names(&block),
+            ["arith.constant", "foo", "arith.constant"]
+        );
+        assert_eq!(
+            block.first_operation().unwrap().to_string(),
+            "%c1_i64 = arith.constant 1 : i64\n"
+        );
+    }
+}
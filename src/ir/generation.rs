@@ -0,0 +1,219 @@
+//! Generations of IR objects.
+//!
+//! References to operations, blocks, and regions record generations of their
+//! objects when they are created in debug builds. Data maps record ones of
+//! operations they have data of in all builds. Generations are removed when
+//! the objects are destroyed through owned IR objects or may be destroyed by
+//! passes, so that uses of invalidated references panic instead of accessing
+//! freed memory.
+//!
+//! Values are not tracked. Their uses after their owners are destroyed are
+//! still undefined behavior.
+//!
+//! IR objects are tracked per thread because their references cannot be sent
+//! across threads.
+
+use mlir_sys::{
+    mlirBlockGetFirstOperation, mlirBlockGetNextInRegion, mlirOperationGetNextInBlock,
+    mlirOperationGetNumRegions, mlirOperationGetRegion, mlirRegionGetFirstBlock, MlirBlock,
+    MlirOperation, MlirRegion,
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    ffi::c_void,
+};
+
+thread_local! {
+    static GENERATIONS: RefCell<HashMap<usize, u64>> = Default::default();
+    static NEXT_GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A generation of an IR object.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Generation(u64);
+
+impl Generation {
+    /// Gets a generation of an IR object, or starts a new one if it is not
+    /// tracked yet.
+    pub fn new(pointer: *const c_void) -> Self {
+        Self(
+            GENERATIONS
+                .try_with(|generations| {
+                    *generations
+                        .borrow_mut()
+                        .entry(pointer as usize)
+                        .or_insert_with(|| {
+                            NEXT_GENERATION.with(|next| {
+                                let generation = next.get();
+
+                                next.set(generation + 1);
+
+                                generation
+                            })
+                        })
+                })
+                .unwrap_or(u64::MAX),
+        )
+    }
+
+    /// Checks if an IR object is still alive.
+    ///
+    /// # Panics
+    ///
+    /// It panics if an IR object was destroyed after its reference was
+    /// created.
+    #[cfg(debug_assertions)]
+    pub fn check(self, pointer: *const c_void, kind: &str) {
+        if !self.is_alive(pointer) {
+            panic!("reference to destroyed {}", kind);
+        }
+    }
+
+    /// Returns `true` if an IR object was not destroyed after its reference
+    /// was created.
+    ///
+    /// Untracked objects are considered destroyed.
+    pub fn is_alive(self, pointer: *const c_void) -> bool {
+        GENERATIONS
+            .try_with(|generations| generations.borrow().get(&(pointer as usize)) == Some(&self.0))
+            .unwrap_or(false)
+    }
+}
+
+/// Invalidates references to an operation and its nested objects.
+pub(crate) unsafe fn invalidate_operation(operation: MlirOperation) {
+    if is_empty() {
+        return;
+    }
+
+    invalidate(operation.ptr);
+    invalidate_nested(operation);
+}
+
+/// Invalidates references to objects nested in an operation, e.g. before
+/// passes which might destroy them run on the operation.
+pub(crate) unsafe fn invalidate_nested(operation: MlirOperation) {
+    if is_empty() {
+        return;
+    }
+
+    for index in 0..mlirOperationGetNumRegions(operation) {
+        invalidate_region(mlirOperationGetRegion(operation, index));
+    }
+}
+
+/// Invalidates references to a block and its nested objects.
+pub(crate) unsafe fn invalidate_block(block: MlirBlock) {
+    if is_empty() {
+        return;
+    }
+
+    invalidate(block.ptr);
+
+    let mut operation = mlirBlockGetFirstOperation(block);
+
+    while !operation.ptr.is_null() {
+        invalidate_operation(operation);
+        operation = mlirOperationGetNextInBlock(operation);
+    }
+}
+
+/// Invalidates references to a region and its nested objects.
+pub(crate) unsafe fn invalidate_region(region: MlirRegion) {
+    if is_empty() {
+        return;
+    }
+
+    invalidate(region.ptr);
+
+    let mut block = mlirRegionGetFirstBlock(region);
+
+    while !block.ptr.is_null() {
+        invalidate_block(block);
+        block = mlirBlockGetNextInRegion(block);
+    }
+}
+
+// Nested objects are not walked if nothing is tracked, e.g. in release builds
+// without data maps.
+fn is_empty() -> bool {
+    GENERATIONS
+        .try_with(|generations| generations.borrow().is_empty())
+        .unwrap_or(true)
+}
+
+fn invalidate(pointer: *const c_void) {
+    // Thread-local storage might be already destroyed when IR objects are
+    // dropped at thread exits.
+    let _ =
+        GENERATIONS.try_with(|generations| generations.borrow_mut().remove(&(pointer as usize)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let object = 0;
+        let pointer = &object as *const _ as *const c_void;
+
+        assert_eq!(Generation::new(pointer), Generation::new(pointer));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn check() {
+        let object = 0;
+        let pointer = &object as *const _ as *const c_void;
+
+        Generation::new(pointer).check(pointer, "object");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "reference to destroyed object")]
+    fn check_invalidated() {
+        let object = 0;
+        let pointer = &object as *const _ as *const c_void;
+        let generation = Generation::new(pointer);
+
+        invalidate(pointer);
+
+        generation.check(pointer, "object");
+    }
+
+    #[test]
+    fn is_alive() {
+        let object = 0;
+        let pointer = &object as *const _ as *const c_void;
+        let generation = Generation::new(pointer);
+
+        assert!(generation.is_alive(pointer));
+
+        invalidate(pointer);
+
+        assert!(!generation.is_alive(pointer));
+    }
+
+    #[test]
+    fn is_alive_untracked() {
+        let object = 0;
+        let pointer = &object as *const _ as *const c_void;
+        let other = 0;
+
+        assert!(!Generation::new(pointer).is_alive(&other as *const _ as *const c_void));
+    }
+
+    #[test]
+    fn renew_invalidated() {
+        let object = 0;
+        let pointer = &object as *const _ as *const c_void;
+        let generation = Generation::new(pointer);
+
+        invalidate(pointer);
+
+        assert_ne!(Generation::new(pointer), generation);
+    }
+}
@@ -6,7 +6,11 @@ use mlir_sys::{
     mlirIdentifierEqual, mlirIdentifierGet, mlirIdentifierGetContext, mlirIdentifierStr,
     MlirIdentifier,
 };
-use std::marker::PhantomData;
+use std::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    str::Utf8Error,
+};
 
 /// An identifier.
 #[derive(Clone, Copy, Debug)]
@@ -17,7 +21,7 @@ pub struct Identifier<'c> {
 
 impl<'c> Identifier<'c> {
     /// Creates an identifier.
-    pub fn new(context: &Context, name: &str) -> Self {
+    pub fn new(context: &'c Context, name: &str) -> Self {
         unsafe {
             Self::from_raw(mlirIdentifierGet(
                 context.to_raw(),
@@ -31,11 +35,18 @@ impl<'c> Identifier<'c> {
         unsafe { ContextRef::from_raw(mlirIdentifierGetContext(self.raw)) }
     }
 
-    /// Converts an identifier into a string reference.
-    pub fn as_string_ref(&self) -> StringRef {
+    /// Converts an identifier into a string reference which lives as long as
+    /// its context.
+    // Identifiers are uniqued and owned by contexts.
+    pub fn as_string_ref(&self) -> StringRef<'c> {
         unsafe { StringRef::from_raw(mlirIdentifierStr(self.raw)) }
     }
 
+    /// Converts an identifier into a `str`.
+    pub fn as_str(&self) -> Result<&'c str, Utf8Error> {
+        self.as_string_ref().as_str()
+    }
+
     pub(crate) unsafe fn from_raw(raw: MlirIdentifier) -> Self {
         Self {
             raw,
@@ -56,6 +67,12 @@ impl<'c> PartialEq for Identifier<'c> {
 
 impl<'c> Eq for Identifier<'c> {}
 
+impl<'c> Display for Identifier<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.as_str().unwrap_or_default())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,6 +87,26 @@ mod tests {
         Identifier::new(&Context::new(), "foo").context();
     }
 
+    #[test]
+    fn as_str() {
+        assert_eq!(Identifier::new(&Context::new(), "foo").as_str(), Ok("foo"));
+    }
+
+    #[test]
+    fn as_string_ref() {
+        let context = Context::new();
+
+        assert_eq!(
+            Identifier::new(&context, "foo").as_string_ref(),
+            StringRef::from("foo")
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Identifier::new(&Context::new(), "foo").to_string(), "foo");
+    }
+
     #[test]
     fn equal() {
         let context = Context::new();
@@ -0,0 +1,354 @@
+use super::AffineExpr;
+use crate::{
+    context::{Context, ContextRef},
+    utility::print_callback,
+    Error,
+};
+use mlir_sys::{
+    mlirIntegerSetEmptyGet, mlirIntegerSetEqual, mlirIntegerSetGet, mlirIntegerSetGetConstraint,
+    mlirIntegerSetGetContext, mlirIntegerSetGetNumConstraints, mlirIntegerSetGetNumDims,
+    mlirIntegerSetGetNumEqualities, mlirIntegerSetGetNumInequalities, mlirIntegerSetGetNumInputs,
+    mlirIntegerSetGetNumSymbols, mlirIntegerSetIsCanonicalEmpty, mlirIntegerSetIsConstraintEq,
+    mlirIntegerSetPrint, mlirIntegerSetReplaceGet, MlirIntegerSet,
+};
+use std::{
+    ffi::c_void,
+    fmt::{self, Debug, Display, Formatter},
+    marker::PhantomData,
+};
+
+// TODO Parse integer sets and convert them into attributes.
+/// An integer set.
+///
+/// Each constraint of an integer set is an affine expression which is either
+/// equal to zero or greater than or equal to zero.
+#[derive(Clone, Copy)]
+pub struct IntegerSet<'c> {
+    raw: MlirIntegerSet,
+    _context: PhantomData<&'c Context>,
+}
+
+impl<'c> IntegerSet<'c> {
+    /// Creates an integer set.
+    ///
+    /// Each constraint is a pair of an expression and a flag which is `true`
+    /// for an equality.
+    pub fn new(
+        context: &'c Context,
+        dimension_count: usize,
+        symbol_count: usize,
+        constraints: &[(AffineExpr<'c>, bool)],
+    ) -> Self {
+        let (expressions, equalities): (Vec<_>, Vec<_>) = constraints
+            .iter()
+            .map(|(expression, equality)| (unsafe { expression.to_raw() }, *equality))
+            .unzip();
+
+        unsafe {
+            Self::from_raw(mlirIntegerSetGet(
+                context.to_raw(),
+                dimension_count as isize,
+                symbol_count as isize,
+                constraints.len() as isize,
+                expressions.as_ptr(),
+                equalities.as_ptr(),
+            ))
+        }
+    }
+
+    /// Creates a canonical empty integer set.
+    pub fn empty(context: &'c Context, dimension_count: usize, symbol_count: usize) -> Self {
+        unsafe {
+            Self::from_raw(mlirIntegerSetEmptyGet(
+                context.to_raw(),
+                dimension_count as isize,
+                symbol_count as isize,
+            ))
+        }
+    }
+
+    /// Gets a context.
+    pub fn context(&self) -> ContextRef<'c> {
+        unsafe { ContextRef::from_raw(mlirIntegerSetGetContext(self.raw)) }
+    }
+
+    /// Gets a number of dimensions.
+    pub fn dimension_count(&self) -> usize {
+        unsafe { mlirIntegerSetGetNumDims(self.raw) as usize }
+    }
+
+    /// Gets a number of symbols.
+    pub fn symbol_count(&self) -> usize {
+        unsafe { mlirIntegerSetGetNumSymbols(self.raw) as usize }
+    }
+
+    /// Gets a number of inputs, which are dimensions and symbols.
+    pub fn input_count(&self) -> usize {
+        unsafe { mlirIntegerSetGetNumInputs(self.raw) as usize }
+    }
+
+    /// Gets a constraint at a position and whether it is an equality.
+    pub fn constraint(&self, position: usize) -> Option<(AffineExpr<'c>, bool)> {
+        if position < self.constraint_count() {
+            Some(unsafe { self.constraint_unchecked(position) })
+        } else {
+            None
+        }
+    }
+
+    /// Gets a number of constraints.
+    pub fn constraint_count(&self) -> usize {
+        unsafe { mlirIntegerSetGetNumConstraints(self.raw) as usize }
+    }
+
+    /// Gets constraints.
+    pub fn constraints(&self) -> impl Iterator<Item = (AffineExpr<'c>, bool)> {
+        let set = *self;
+
+        (0..self.constraint_count()).map(move |index| unsafe { set.constraint_unchecked(index) })
+    }
+
+    /// Gets a number of equalities.
+    pub fn equality_count(&self) -> usize {
+        unsafe { mlirIntegerSetGetNumEqualities(self.raw) as usize }
+    }
+
+    /// Gets a number of inequalities.
+    pub fn inequality_count(&self) -> usize {
+        unsafe { mlirIntegerSetGetNumInequalities(self.raw) as usize }
+    }
+
+    /// Replaces dimensions and symbols with expressions.
+    ///
+    /// It returns an error if numbers of expressions do not match ones of
+    /// dimensions and symbols.
+    pub fn replace(
+        &self,
+        dimensions: &[AffineExpr<'c>],
+        symbols: &[AffineExpr<'c>],
+        dimension_count: usize,
+        symbol_count: usize,
+    ) -> Result<Self, Error> {
+        if dimensions.len() != self.dimension_count() {
+            return Err(Error::DimensionCount(
+                self.dimension_count(),
+                dimensions.len(),
+            ));
+        } else if symbols.len() != self.symbol_count() {
+            return Err(Error::SymbolCount(self.symbol_count(), symbols.len()));
+        }
+
+        let dimensions = dimensions
+            .iter()
+            .map(|expression| unsafe { expression.to_raw() })
+            .collect::<Vec<_>>();
+        let symbols = symbols
+            .iter()
+            .map(|expression| unsafe { expression.to_raw() })
+            .collect::<Vec<_>>();
+
+        Ok(unsafe {
+            Self::from_raw(mlirIntegerSetReplaceGet(
+                self.raw,
+                dimensions.as_ptr(),
+                symbols.as_ptr(),
+                dimension_count as isize,
+                symbol_count as isize,
+            ))
+        })
+    }
+
+    /// Returns `true` if an integer set is a canonical empty set.
+    pub fn is_canonical_empty(&self) -> bool {
+        unsafe { mlirIntegerSetIsCanonicalEmpty(self.raw) }
+    }
+
+    unsafe fn constraint_unchecked(&self, position: usize) -> (AffineExpr<'c>, bool) {
+        (
+            AffineExpr::from_raw(mlirIntegerSetGetConstraint(self.raw, position as isize)),
+            mlirIntegerSetIsConstraintEq(self.raw, position as isize),
+        )
+    }
+
+    pub(crate) unsafe fn from_raw(raw: MlirIntegerSet) -> Self {
+        Self {
+            raw,
+            _context: Default::default(),
+        }
+    }
+}
+
+impl<'c> PartialEq for IntegerSet<'c> {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { mlirIntegerSetEqual(self.raw, other.raw) }
+    }
+}
+
+impl<'c> Eq for IntegerSet<'c> {}
+
+impl<'c> Display for IntegerSet<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let mut data = (formatter, Ok(()));
+
+        unsafe {
+            mlirIntegerSetPrint(
+                self.raw,
+                Some(print_callback),
+                &mut data as *mut _ as *mut c_void,
+            );
+        }
+
+        data.1
+    }
+}
+
+impl<'c> Debug for IntegerSet<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "IntegerSet(")?;
+        Display::fmt(self, formatter)?;
+        write!(formatter, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let context = Context::new();
+        let dimension = AffineExpr::dimension(&context, 0);
+        let symbol = AffineExpr::symbol(&context, 0);
+        let set = IntegerSet::new(
+            &context,
+            1,
+            1,
+            &[(dimension - 1, true), (symbol - dimension, false)],
+        );
+
+        assert_eq!(set.dimension_count(), 1);
+        assert_eq!(set.symbol_count(), 1);
+        assert_eq!(set.input_count(), 2);
+        assert_eq!(set.constraint_count(), 2);
+        assert_eq!(set.equality_count(), 1);
+        assert_eq!(set.inequality_count(), 1);
+        assert_eq!(set.to_string(), "(d0)[s0] : (d0 - 1 == 0, -d0 + s0 >= 0)");
+    }
+
+    #[test]
+    fn empty() {
+        let context = Context::new();
+        let dimension = AffineExpr::dimension(&context, 0);
+        let symbol = AffineExpr::symbol(&context, 0);
+        let set = IntegerSet::new(
+            &context,
+            1,
+            1,
+            &[(dimension - 1, true), (symbol - dimension, false)],
+        );
+
+        assert!(IntegerSet::empty(&context, 1, 0).is_canonical_empty());
+        assert!(!set.is_canonical_empty());
+    }
+
+    #[test]
+    fn constraint() {
+        let context = Context::new();
+        let dimension = AffineExpr::dimension(&context, 0);
+        let symbol = AffineExpr::symbol(&context, 0);
+        let set = IntegerSet::new(
+            &context,
+            1,
+            1,
+            &[(dimension - 1, true), (symbol - dimension, false)],
+        );
+
+        assert_eq!(set.constraint(0), Some((dimension - 1, true)));
+        assert_eq!(set.constraint(2), None);
+    }
+
+    #[test]
+    fn constraints() {
+        let context = Context::new();
+        let dimension = AffineExpr::dimension(&context, 0);
+        let symbol = AffineExpr::symbol(&context, 0);
+        let set = IntegerSet::new(
+            &context,
+            1,
+            1,
+            &[(dimension - 1, true), (symbol - dimension, false)],
+        );
+
+        assert_eq!(
+            set.constraints()
+                .map(|(_, equality)| equality)
+                .collect::<Vec<_>>(),
+            [true, false]
+        );
+    }
+
+    #[test]
+    fn replace() {
+        let context = Context::new();
+        let dimension = AffineExpr::dimension(&context, 0);
+        let symbol = AffineExpr::symbol(&context, 0);
+        let set = IntegerSet::new(
+            &context,
+            1,
+            1,
+            &[(dimension - 1, true), (symbol - dimension, false)],
+        );
+
+        assert_eq!(
+            set.replace(&[dimension * 2], &[AffineExpr::constant(&context, 4)], 1, 0,)
+                .unwrap()
+                .to_string(),
+            "(d0) : (d0 * 2 - 1 == 0, d0 * -2 + 4 >= 0)"
+        );
+    }
+
+    #[test]
+    fn replace_with_wrong_counts() {
+        let context = Context::new();
+        let dimension = AffineExpr::dimension(&context, 0);
+        let symbol = AffineExpr::symbol(&context, 0);
+        let set = IntegerSet::new(
+            &context,
+            1,
+            1,
+            &[(dimension - 1, true), (symbol - dimension, false)],
+        );
+        let expression = AffineExpr::constant(&context, 0);
+
+        assert_eq!(
+            set.replace(&[], &[expression], 0, 0),
+            Err(Error::DimensionCount(1, 0))
+        );
+        assert_eq!(
+            set.replace(&[expression], &[], 0, 0),
+            Err(Error::SymbolCount(1, 0))
+        );
+    }
+
+    #[test]
+    fn equal() {
+        let context = Context::new();
+        let dimension = AffineExpr::dimension(&context, 0);
+        let symbol = AffineExpr::symbol(&context, 0);
+        let constraints = [(dimension - 1, true), (symbol - dimension, false)];
+        let set = IntegerSet::new(&context, 1, 1, &constraints);
+
+        assert_eq!(set, IntegerSet::new(&context, 1, 1, &constraints));
+        assert_ne!(set, IntegerSet::empty(&context, 1, 1));
+    }
+
+    #[test]
+    fn debug() {
+        let context = Context::new();
+
+        assert_eq!(
+            format!("{:?}", IntegerSet::empty(&context, 1, 0)),
+            "IntegerSet((d0) : (1 == 0))"
+        );
+    }
+}
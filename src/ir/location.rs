@@ -1,15 +1,17 @@
 use crate::{
     context::{Context, ContextRef},
+    diagnostic::SourcePosition,
     ir::Attribute,
     string_ref::StringRef,
-    utility::{into_raw_array, print_callback},
+    utility::print_callback,
 };
 use mlir_sys::{
-    mlirLocationEqual, mlirLocationFileLineColGet, mlirLocationFusedGet, mlirLocationGetContext,
-    mlirLocationNameGet, mlirLocationPrint, mlirLocationUnknownGet, MlirLocation,
+    mlirEmitError, mlirLocationCallSiteGet, mlirLocationEqual, mlirLocationFileLineColGet,
+    mlirLocationFusedGet, mlirLocationGetContext, mlirLocationNameGet, mlirLocationPrint,
+    mlirLocationUnknownGet, MlirLocation,
 };
 use std::{
-    ffi::c_void,
+    ffi::{c_void, CString},
     fmt::{self, Display, Formatter},
     marker::PhantomData,
 };
@@ -34,20 +36,45 @@ impl<'c> Location<'c> {
         }
     }
 
+    /// Creates a location with a filename and a source range.
+    ///
+    /// Ranged file locations are unsupported on MLIR 15. The end of a range is
+    /// ignored and a location at its start is created instead, which is the
+    /// same as [`Location::new`].
+    // TODO Use `mlirLocationFileLineColRangeGet` and keep the end of a range
+    // when MLIR 20 is supported.
+    pub fn range(
+        context: &'c Context,
+        filename: &str,
+        start: (usize, usize),
+        _end: (usize, usize),
+    ) -> Self {
+        Self::new(context, filename, start.0, start.1)
+    }
+
+    /// Creates a call site location.
+    pub fn call_site(callee: Self, caller: Self) -> Self {
+        unsafe { Self::from_raw(mlirLocationCallSiteGet(callee.to_raw(), caller.to_raw())) }
+    }
+
     /// Creates a fused location.
-    pub fn fused(context: &Context, locations: &[Self], attribute: Attribute) -> Self {
+    pub fn fused(context: &'c Context, locations: &[Self], attribute: Attribute<'c>) -> Self {
         unsafe {
             Self::from_raw(mlirLocationFusedGet(
                 context.to_raw(),
                 locations.len() as isize,
-                into_raw_array(locations.iter().map(|location| location.to_raw()).collect()),
+                locations
+                    .iter()
+                    .map(|location| location.to_raw())
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
                 attribute.to_raw(),
             ))
         }
     }
 
     /// Creates a name location.
-    pub fn name(context: &Context, name: &str, child: Location) -> Self {
+    pub fn name(context: &'c Context, name: &str, child: Self) -> Self {
         unsafe {
             Self::from_raw(mlirLocationNameGet(
                 context.to_raw(),
@@ -58,7 +85,7 @@ impl<'c> Location<'c> {
     }
 
     /// Creates an unknown location.
-    pub fn unknown(context: &Context) -> Self {
+    pub fn unknown(context: &'c Context) -> Self {
         unsafe { Self::from_raw(mlirLocationUnknownGet(context.to_raw())) }
     }
 
@@ -67,6 +94,20 @@ impl<'c> Location<'c> {
         unsafe { ContextRef::from_raw(mlirLocationGetContext(self.raw)) }
     }
 
+    /// Gets a source position if a location is a file location.
+    pub fn position(&self) -> Option<SourcePosition> {
+        SourcePosition::parse(&self.to_string())
+    }
+
+    /// Emits an error diagnostic at a location.
+    ///
+    /// It is reported to diagnostic handlers attached to a context.
+    pub fn emit_error(&self, message: &str) {
+        let message = CString::new(message.replace('\0', "")).unwrap();
+
+        unsafe { mlirEmitError(self.raw, message.as_ptr()) }
+    }
+
     pub(crate) unsafe fn from_raw(raw: MlirLocation) -> Self {
         Self {
             raw,
@@ -111,6 +152,37 @@ mod tests {
         Location::new(&Context::new(), "foo", 42, 42);
     }
 
+    #[test]
+    fn range() {
+        let context = Context::new();
+
+        assert_eq!(
+            Location::range(&context, "foo", (1, 2), (3, 4)),
+            Location::new(&context, "foo", 1, 2)
+        );
+    }
+
+    #[test]
+    fn position() {
+        let context = Context::new();
+
+        assert_eq!(
+            Location::new(&context, "foo", 1, 2).position(),
+            Some(SourcePosition::new("foo", 1, 2))
+        );
+        assert_eq!(Location::unknown(&context).position(), None);
+    }
+
+    #[test]
+    fn call_site() {
+        let context = Context::new();
+
+        Location::call_site(
+            Location::new(&context, "foo", 1, 1),
+            Location::new(&context, "bar", 2, 2),
+        );
+    }
+
     #[test]
     fn fused() {
         let context = Context::new();
@@ -142,6 +214,20 @@ mod tests {
         Location::new(&Context::new(), "foo", 42, 42).context();
     }
 
+    #[test]
+    fn emit_error() {
+        let context = Context::new();
+
+        Location::new(&context, "foo", 42, 42).emit_error("bar");
+    }
+
+    #[test]
+    fn emit_error_with_null_character() {
+        let context = Context::new();
+
+        Location::unknown(&context).emit_error("foo\0bar");
+    }
+
     #[test]
     fn equal() {
         let context = Context::new();
@@ -173,4 +259,28 @@ mod tests {
             "loc(\"foo\":42:42)"
         );
     }
+
+    #[test]
+    fn display_call_site() {
+        let context = Context::new();
+
+        assert_eq!(
+            Location::call_site(
+                Location::new(&context, "foo", 1, 1),
+                Location::new(&context, "bar", 2, 2),
+            )
+            .to_string(),
+            "loc(callsite(\"foo\":1:1 at \"bar\":2:2))"
+        );
+    }
+
+    #[test]
+    fn display_name() {
+        let context = Context::new();
+
+        assert_eq!(
+            Location::name(&context, "foo", Location::unknown(&context)).to_string(),
+            "loc(\"foo\")"
+        );
+    }
 }
@@ -1,13 +1,17 @@
-use super::{BlockRef, Location, Operation, OperationRef};
+use super::{generation, operation::PrintingFlags, BlockRef, Location, Operation, OperationRef};
 use crate::{
     context::{Context, ContextRef},
+    diagnostic::{CapturedDiagnostic, Severity, SourcePosition, SourceRange},
+    dialect::func,
     string_ref::StringRef,
+    utility::{escape_string, with_diagnostics},
+    Error,
 };
 use mlir_sys::{
     mlirModuleCreateEmpty, mlirModuleCreateParse, mlirModuleDestroy, mlirModuleFromOperation,
     mlirModuleGetBody, mlirModuleGetContext, mlirModuleGetOperation, MlirModule,
 };
-use std::marker::PhantomData;
+use std::{ffi::CString, marker::PhantomData, mem::forget};
 
 /// A module.
 #[derive(Debug)]
@@ -18,21 +22,121 @@ pub struct Module<'c> {
 
 impl<'c> Module<'c> {
     /// Creates a module.
-    pub fn new(location: Location) -> Self {
+    pub fn new(location: Location<'c>) -> Self {
         unsafe { Self::from_raw(mlirModuleCreateEmpty(location.to_raw())) }
     }
 
     /// Parses a module.
-    pub fn parse(context: &Context, source: &str) -> Option<Self> {
-        // TODO Should we allocate StringRef locally because sources can be big?
+    pub fn parse(context: &'c Context, source: &str) -> Option<Self> {
+        let source = CString::new(source).ok()?;
+
         unsafe {
             Self::from_option_raw(mlirModuleCreateParse(
                 context.to_raw(),
-                StringRef::from(source).to_raw(),
+                StringRef::from(source.as_c_str()).to_raw(),
             ))
         }
     }
 
+    /// Parses a module with a filename.
+    ///
+    /// Locations in a parsed module refer to the filename. Ranges in a
+    /// returned error span tokens at which parsing fails.
+    ///
+    /// A source is parsed twice to rename locations in a parsed module, so
+    /// attributes and types that are not printed as parsed might not
+    /// round-trip.
+    pub fn parse_with_filename(
+        context: &'c Context,
+        source: &str,
+        filename: &str,
+    ) -> Result<Self, Error> {
+        Self::parse_with_line_offset(context, source, filename, 0)
+    }
+
+    // TODO Name a source buffer instead of parsing a module twice. This is
+    // lossy for anything that does not round-trip through the generic
+    // operation form.
+    pub(crate) fn parse_with_line_offset(
+        context: &'c Context,
+        source: &str,
+        filename: &str,
+        line_offset: usize,
+    ) -> Result<Self, Error> {
+        let (module, diagnostics) = with_diagnostics(context, || Self::parse(context, source));
+
+        // An empty module is located at the start of a source buffer.
+        let buffer = Self::parse(context, "").and_then(|module| {
+            module
+                .as_operation()
+                .location()
+                .to_string()
+                .strip_prefix("loc(")?
+                .rsplitn(3, ':')
+                .nth(2)
+                .map(ToOwned::to_owned)
+        });
+        let relocate_source = |source: &str| match &buffer {
+            Some(buffer) => relocate(source, buffer, filename, line_offset),
+            None => source.into(),
+        };
+
+        let module = module.ok_or_else(|| {
+            let diagnostic = diagnostics
+                .iter()
+                .find(|diagnostic| diagnostic.severity() == Severity::Error);
+
+            Error::ParseModule(
+                diagnostic
+                    .and_then(|diagnostic| diagnostic.position())
+                    .map(|position| {
+                        SourceRange::token(
+                            source,
+                            SourcePosition::new(filename, position.line(), position.column()),
+                        )
+                        .with_line_offset(line_offset)
+                    }),
+                diagnostic
+                    .map(|diagnostic| diagnostic.message().into())
+                    .unwrap_or_else(|| "unknown error".into()),
+                diagnostics
+                    .iter()
+                    .map(|diagnostic| {
+                        CapturedDiagnostic::from(diagnostic).map_locations(&relocate_source)
+                    })
+                    .collect(),
+            )
+        })?;
+
+        if buffer.is_none() {
+            return Ok(module);
+        }
+
+        let source = relocate_source(
+            &module.as_operation().to_string_with_flags(
+                &PrintingFlags::new()
+                    .enable_debug_info(true, false)
+                    .print_generic_operation_form(),
+            ),
+        );
+        let (module, diagnostics) = with_diagnostics(context, || Self::parse(context, &source));
+
+        module.ok_or_else(|| {
+            Error::ParseModule(
+                None,
+                format!(
+                    "failed to relocate module: {}",
+                    diagnostics
+                        .iter()
+                        .find(|diagnostic| diagnostic.severity() == Severity::Error)
+                        .map(|diagnostic| diagnostic.message())
+                        .unwrap_or("unknown error")
+                ),
+                diagnostics.iter().map(CapturedDiagnostic::from).collect(),
+            )
+        })
+    }
+
     /// Converts a module into an operation.
     pub fn as_operation(&self) -> OperationRef {
         unsafe { OperationRef::from_raw(mlirModuleGetOperation(self.raw)) }
@@ -48,12 +152,42 @@ impl<'c> Module<'c> {
         unsafe { BlockRef::from_raw(mlirModuleGetBody(self.raw)) }
     }
 
+    /// Appends an operation to a module body.
+    pub fn append_operation(&self, operation: Operation<'c>) -> OperationRef {
+        self.body().append_operation(operation)
+    }
+
+    /// Builds and appends a `func.func` operation to a module body.
+    ///
+    /// See [`func::Builder::build`] for how a callback builds a function body.
+    pub fn append_function(
+        &self,
+        builder: func::Builder<'c>,
+        build_body: impl FnOnce(BlockRef<'c>) -> Result<(), Error>,
+    ) -> Result<OperationRef, Error> {
+        Ok(self.append_operation(builder.build(build_body)?))
+    }
+
     /// Converts an operation into a module.
     pub fn from_operation(operation: Operation) -> Option<Self> {
         unsafe { Self::from_option_raw(mlirModuleFromOperation(operation.into_raw())) }
     }
 
+    /// Converts a module into an operation.
+    ///
+    /// This is the inverse of [`Module::from_operation`].
+    pub fn into_operation(self) -> Operation<'c> {
+        let operation = unsafe { mlirModuleGetOperation(self.raw) };
+
+        self.context().decrement_module_count();
+        forget(self);
+
+        unsafe { Operation::from_raw(operation) }
+    }
+
     unsafe fn from_raw(raw: MlirModule) -> Self {
+        ContextRef::from_raw(mlirModuleGetContext(raw)).increment_module_count();
+
         Self {
             raw,
             _context: Default::default(),
@@ -75,10 +209,48 @@ impl<'c> Module<'c> {
 
 impl<'c> Drop for Module<'c> {
     fn drop(&mut self) {
-        unsafe { mlirModuleDestroy(self.raw) };
+        unsafe {
+            generation::invalidate_operation(mlirModuleGetOperation(self.raw));
+            self.context().decrement_module_count();
+            mlirModuleDestroy(self.raw);
+        }
     }
 }
 
+// Renames a quoted buffer name in printed locations of `loc("buffer":line:column)`
+// and shifts their lines.
+fn relocate(source: &str, buffer: &str, filename: &str, line_offset: usize) -> String {
+    let prefix = format!("loc({}:", buffer);
+    let filename = escape_string(filename);
+    let mut relocated = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(index) = rest.find(&prefix) {
+        relocated.push_str(&rest[..index]);
+        rest = &rest[index + prefix.len()..];
+
+        let length = rest
+            .find(|character: char| !character.is_ascii_digit())
+            .unwrap_or(rest.len());
+
+        match rest[..length].parse::<usize>() {
+            Ok(line) => {
+                relocated.push_str(&format!(
+                    "loc(\"{}\":{}",
+                    filename,
+                    line.saturating_sub(line_offset)
+                ));
+                rest = &rest[length..];
+            }
+            Err(_) => relocated.push_str(&prefix),
+        }
+    }
+
+    relocated.push_str(rest);
+
+    relocated
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,6 +276,132 @@ mod tests {
         assert!(Module::parse(&Context::new(), "module{").is_none());
     }
 
+    #[test]
+    fn parse_with_filename() {
+        assert!(Module::parse_with_filename(&Context::new(), "module{}", "foo.mlir").is_ok());
+    }
+
+    #[test]
+    fn parse_with_filename_location() {
+        let context = Context::new();
+        let module = Module::parse_with_filename(&context, "module {\n}", "foo.mlir").unwrap();
+
+        assert_eq!(
+            module.as_operation().location().to_string(),
+            "loc(\"foo.mlir\":1:1)"
+        );
+    }
+
+    #[test]
+    fn parse_with_filename_range() {
+        let error = Module::parse_with_filename(
+            &Context::new(),
+            "func.func @foo() {\n  return %bar : index\n}",
+            "foo.mlir",
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::ParseModule(Some(range), _, _)
+                if range.start().line() == 2
+                    && range.start().column() == 10
+                    && range.end().column() == 14
+        ));
+    }
+
+    #[test]
+    fn parse_with_filename_error() {
+        let error =
+            Module::parse_with_filename(&Context::new(), "module{\n  foo", "foo.mlir").unwrap_err();
+
+        match &error {
+            Error::ParseModule(range, _, diagnostics) => {
+                let range = range.as_ref().unwrap();
+
+                assert_eq!(range.start().filename(), "foo.mlir");
+                assert_eq!(range.start().line(), 2);
+                assert!(range.start().column() > 0);
+                assert!(range.end().column() >= range.start().column());
+                assert_eq!(diagnostics[0].position().unwrap().filename(), "foo.mlir");
+            }
+            _ => panic!("unexpected error: {}", error),
+        }
+
+        assert!(error
+            .to_string()
+            .starts_with("failed to parse module: foo.mlir:2:"));
+    }
+
+    #[test]
+    fn parse_with_escaped_filename() {
+        let context = Context::new();
+        let module = Module::parse_with_filename(&context, "module {\n}", "foo\"bar.mlir").unwrap();
+
+        assert_eq!(
+            module
+                .as_operation()
+                .location()
+                .position()
+                .unwrap()
+                .filename(),
+            "foo\"bar.mlir"
+        );
+    }
+
+    #[test]
+    fn relocate() {
+        assert_eq!(
+            super::relocate(
+                "foo loc(\"\":2:3) loc(\"bar\":4:5) #loc = loc(\"\":6:7)",
+                "\"\"",
+                "foo\".mlir",
+                1
+            ),
+            "foo loc(\"foo\\22.mlir\":1:3) loc(\"bar\":4:5) #loc = loc(\"foo\\22.mlir\":5:7)"
+        );
+    }
+
+    #[test]
+    fn append_operation() {
+        let context = Context::new();
+        context.set_allow_unregistered_dialects(true);
+        let module = Module::new(Location::unknown(&context));
+
+        let operation = module.append_operation(
+            operation::Builder::new("foo.bar", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
+
+        assert_eq!(module.body().first_operation(), Some(operation));
+    }
+
+    #[test]
+    fn append_function() {
+        let context = Context::new();
+
+        let location = Location::unknown(&context);
+        let module = Module::new(location);
+
+        module
+            .append_function(
+                func::Builder::new(&context, "foo", &[], &[], location),
+                |block| {
+                    block.append_operation(func::r#return(&[], location)?);
+
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+        assert!(module.as_operation().verify());
+        assert_eq!(
+            module.as_operation().to_string(),
+            "module {\n  func.func @foo() {\n    return\n  }\n}\n"
+        );
+    }
+
     #[test]
     fn from_operation() {
         let context = Context::new();
@@ -114,7 +412,8 @@ mod tests {
         let module = Module::from_operation(
             operation::Builder::new("builtin.module", Location::unknown(&context))
                 .add_regions(vec![region])
-                .build(),
+                .build()
+                .unwrap(),
         )
         .unwrap();
 
@@ -122,12 +421,57 @@ mod tests {
         assert_eq!(module.as_operation().to_string(), "module {\n}\n")
     }
 
+    #[test]
+    fn from_cloned_operation() {
+        let context = Context::new();
+        let module = Module::parse(&context, "module {\n  func.func private @foo()\n}").unwrap();
+
+        let other = Module::from_operation(module.as_operation().to_owned()).unwrap();
+
+        assert_eq!(context.module_count(), 2);
+        assert_eq!(
+            other.as_operation().to_string(),
+            module.as_operation().to_string()
+        );
+    }
+
+    #[test]
+    fn from_nested_operation() {
+        let context = Context::new();
+        let module = Module::parse(&context, "module {\n  module @foo {\n  }\n}").unwrap();
+
+        let body = module.body();
+        let nested = unsafe { body.first_operation().unwrap().remove_from_parent() }.unwrap();
+
+        assert_eq!(
+            Module::from_operation(nested)
+                .unwrap()
+                .as_operation()
+                .to_string(),
+            "module @foo {\n}\n"
+        );
+    }
+
+    #[test]
+    fn into_operation() {
+        let context = Context::new();
+        let module = Module::new(Location::unknown(&context));
+
+        let operation = module.into_operation();
+
+        assert_eq!(context.module_count(), 0);
+        assert_eq!(operation.to_string(), "module {\n}\n");
+        assert!(Module::from_operation(operation).is_some());
+    }
+
     #[test]
     fn from_operation_fail() {
         let context = Context::new();
 
         assert!(Module::from_operation(
-            operation::Builder::new("func.func", Location::unknown(&context),).build()
+            operation::Builder::new("func.func", Location::unknown(&context),)
+                .build()
+                .unwrap()
         )
         .is_none());
     }
@@ -1,31 +1,68 @@
 //! Operations and operation builders.
 
 mod builder;
+mod data_map;
+pub mod matcher;
+mod printing_flags;
 mod result;
+mod statistics;
+mod value_mapping;
+mod verification_error;
 
-pub use self::{builder::Builder, result::ResultValue};
-use super::{BlockRef, Identifier, RegionRef, Value};
+pub use self::{
+    builder::Builder, data_map::DataMap, matcher::Matcher, printing_flags::PrintingFlags,
+    result::ResultValue, statistics::Statistics, value_mapping::ValueMapping,
+    verification_error::VerificationError,
+};
+use super::{
+    generation,
+    walk::{self, WalkOrder, WalkResult},
+    Attribute, BlockRef, DictionaryAttribute, Identifier, Location, Module, Region, RegionRef,
+    Value, ValueLike,
+};
 use crate::{
     context::{Context, ContextRef},
-    utility::print_callback,
+    diagnostic::Severity,
+    string_ref::StringRef,
+    utility::{capture_diagnostics, print_callback},
     Error,
 };
 use core::fmt;
 use mlir_sys::{
-    mlirOperationClone, mlirOperationDestroy, mlirOperationDump, mlirOperationEqual,
-    mlirOperationGetBlock, mlirOperationGetContext, mlirOperationGetName,
-    mlirOperationGetNextInBlock, mlirOperationGetNumRegions, mlirOperationGetNumResults,
-    mlirOperationGetRegion, mlirOperationGetResult, mlirOperationPrint, mlirOperationVerify,
-    MlirOperation,
+    mlirIdentifierStr, mlirLocationUnknownGet, mlirOperationClone, mlirOperationDestroy,
+    mlirOperationDump, mlirOperationEqual, mlirOperationGetAttribute,
+    mlirOperationGetAttributeByName, mlirOperationGetBlock, mlirOperationGetContext,
+    mlirOperationGetLocation, mlirOperationGetName, mlirOperationGetNextInBlock,
+    mlirOperationGetNumAttributes, mlirOperationGetNumOperands, mlirOperationGetNumRegions,
+    mlirOperationGetNumResults, mlirOperationGetNumSuccessors, mlirOperationGetOperand,
+    mlirOperationGetParentOperation, mlirOperationGetRegion, mlirOperationGetResult,
+    mlirOperationGetSuccessor, mlirOperationMoveAfter, mlirOperationMoveBefore, mlirOperationPrint,
+    mlirOperationPrintWithFlags, mlirOperationRemoveAttributeByName, mlirOperationRemoveFromParent,
+    mlirOperationSetAttributeByName, mlirOperationSetOperand, mlirOperationVerify,
+    mlirStringAttrGet, MlirOperation,
 };
 use std::{
+    collections::HashMap,
     ffi::c_void,
     fmt::{Debug, Display, Formatter},
+    iter::successors,
     marker::PhantomData,
     mem::forget,
     ops::Deref,
+    slice, str,
 };
 
+const DETERMINISTIC_ELEMENT_LIMIT: usize = 16;
+const DETERMINISTIC_ATTRIBUTE_LIMIT: usize = 1024;
+
+/// Splits an operation name into a dialect namespace and an operation
+/// mnemonic.
+///
+/// It returns `None` if the name does not have a namespace.
+pub fn split_name(name: &str) -> Option<(&str, &str)> {
+    name.split_once('.')
+}
+
 /// An operation.
 #[derive(Debug)]
 pub struct Operation<'c> {
@@ -34,6 +71,47 @@ pub struct Operation<'c> {
 }
 
 impl<'c> Operation<'c> {
+    /// Parses an operation.
+    ///
+    /// Locations in a parsed operation and ranges in a returned error refer to
+    /// the source with the source name.
+    pub fn parse(context: &'c Context, source: &str, source_name: &str) -> Result<Self, Error> {
+        // Wrap the source so that operations of any kind including modules are
+        // nested in a module.
+        let module = Module::parse_with_line_offset(
+            context,
+            &format!("module {{\n{}\n}}", source),
+            source_name,
+            1,
+        )
+        .map_err(|error| match error {
+            Error::ParseModule(range, message, diagnostics) => {
+                Error::ParseOperation(range, message, diagnostics)
+            }
+            error => error,
+        })?;
+        let body = module.body();
+
+        if body.operation_count() != 1 {
+            return Err(Error::ParseOperation(
+                None,
+                format!(
+                    "single operation expected but {} found",
+                    body.operation_count()
+                ),
+                vec![],
+            ));
+        }
+
+        let operation = body
+            .first_operation()
+            // The operation is moved out of the module before it is dropped.
+            .and_then(|operation| unsafe { operation.remove_from_parent() })
+            .ok_or_else(|| Error::ParseOperation(None, "operation expected".into(), vec![]))?;
+
+        Ok(unsafe { Self::from_raw(operation.into_raw()) })
+    }
+
     pub(crate) unsafe fn from_raw(raw: MlirOperation) -> Self {
         Self {
             r#ref: OperationRef::from_raw(raw),
@@ -41,6 +119,14 @@ impl<'c> Operation<'c> {
         }
     }
 
+    pub(crate) unsafe fn from_option_raw(raw: MlirOperation) -> Option<Self> {
+        if raw.ptr.is_null() {
+            None
+        } else {
+            Some(Self::from_raw(raw))
+        }
+    }
+
     pub(crate) unsafe fn into_raw(self) -> MlirOperation {
         let operation = self.raw;
 
@@ -52,7 +138,10 @@ impl<'c> Operation<'c> {
 
 impl<'c> Drop for Operation<'c> {
     fn drop(&mut self) {
-        unsafe { mlirOperationDestroy(self.raw) };
+        unsafe {
+            generation::invalidate_operation(self.raw);
+            mlirOperationDestroy(self.raw);
+        }
     }
 }
 
@@ -78,23 +167,64 @@ impl<'c> Deref for Operation<'c> {
 #[derive(Clone, Copy)]
 pub struct OperationRef<'c> {
     raw: MlirOperation,
+    #[cfg(debug_assertions)]
+    generation: generation::Generation,
     _reference: PhantomData<&'c Context>,
 }
 
 impl<'a> OperationRef<'a> {
     /// Gets a context.
     pub fn context(&self) -> ContextRef {
-        unsafe { ContextRef::from_raw(mlirOperationGetContext(self.raw)) }
+        unsafe { ContextRef::from_raw(mlirOperationGetContext(self.to_raw())) }
     }
 
     /// Gets a name.
     pub fn name(&self) -> Identifier {
-        unsafe { Identifier::from_raw(mlirOperationGetName(self.raw)) }
+        unsafe { Identifier::from_raw(mlirOperationGetName(self.to_raw())) }
+    }
+
+    /// Gets a name as a string.
+    pub fn name_str(&self) -> &'a str {
+        // Operation names are uniqued and live as long as contexts.
+        unsafe {
+            let string = mlirIdentifierStr(mlirOperationGetName(self.to_raw()));
+
+            str::from_utf8(slice::from_raw_parts(
+                string.data as *const u8,
+                string.length as usize,
+            ))
+            .unwrap_or_default()
+        }
+    }
+
+    /// Gets a dialect name.
+    pub fn dialect_name(&self) -> Option<&'a str> {
+        split_name(self.name_str()).map(|(dialect, _)| dialect)
+    }
+
+    /// Returns `true` if an operation has a name.
+    pub fn is(&self, name: &str) -> bool {
+        self.name_str() == name
+    }
+
+    /// Returns `true` if an operation matches a matcher.
+    pub fn matches(&self, matcher: &impl Matcher) -> bool {
+        matcher.matches(*self)
+    }
+
+    /// Gets a location.
+    pub fn location(&self) -> Location<'a> {
+        unsafe { Location::from_raw(mlirOperationGetLocation(self.to_raw())) }
     }
 
     /// Gets a block.
     pub fn block(&self) -> Option<BlockRef> {
-        unsafe { BlockRef::from_option_raw(mlirOperationGetBlock(self.raw)) }
+        unsafe { BlockRef::from_option_raw(mlirOperationGetBlock(self.to_raw())) }
+    }
+
+    /// Gets a parent operation.
+    pub fn parent_operation(&self) -> Option<OperationRef<'a>> {
+        unsafe { OperationRef::from_option_raw(mlirOperationGetParentOperation(self.to_raw())) }
     }
 
     /// Gets a result at a position.
@@ -102,7 +232,7 @@ impl<'a> OperationRef<'a> {
         unsafe {
             if position < self.result_count() as usize {
                 Ok(result::ResultValue::from_raw(mlirOperationGetResult(
-                    self.raw,
+                    self.to_raw(),
                     position as isize,
                 )))
             } else {
@@ -113,7 +243,75 @@ impl<'a> OperationRef<'a> {
 
     /// Gets a number of results.
     pub fn result_count(&self) -> usize {
-        unsafe { mlirOperationGetNumResults(self.raw) as usize }
+        unsafe { mlirOperationGetNumResults(self.to_raw()) as usize }
+    }
+
+    /// Gets results.
+    pub fn results(&self) -> impl Iterator<Item = ResultValue<'a>> {
+        let raw = unsafe { self.to_raw() };
+
+        (0..self.result_count()).map(move |index| unsafe {
+            ResultValue::from_raw(mlirOperationGetResult(raw, index as isize))
+        })
+    }
+
+    /// Gets an operand at a position.
+    pub fn operand(&self, position: usize) -> Result<Value<'a>, Error> {
+        unsafe {
+            if position < self.operand_count() {
+                Ok(Value::from_raw(mlirOperationGetOperand(
+                    self.to_raw(),
+                    position as isize,
+                )))
+            } else {
+                Err(Error::OperationOperandPosition(self.to_string(), position))
+            }
+        }
+    }
+
+    /// Gets a number of operands.
+    pub fn operand_count(&self) -> usize {
+        unsafe { mlirOperationGetNumOperands(self.to_raw()) as usize }
+    }
+
+    /// Gets operands.
+    pub fn operands(&self) -> impl Iterator<Item = Value<'a>> {
+        let raw = unsafe { self.to_raw() };
+
+        (0..self.operand_count()).map(move |index| unsafe {
+            Value::from_raw(mlirOperationGetOperand(raw, index as isize))
+        })
+    }
+
+    /// Sets an operand at a position.
+    pub fn set_operand(&self, position: usize, value: Value) -> Result<(), Error> {
+        if position < self.operand_count() {
+            unsafe { mlirOperationSetOperand(self.to_raw(), position as isize, value.to_raw()) }
+
+            Ok(())
+        } else {
+            Err(Error::OperationOperandPosition(self.to_string(), position))
+        }
+    }
+
+    /// Sets consecutive operands from a position.
+    // TODO Change a number of operands.
+    pub fn set_operands(&self, position: usize, values: &[Value]) -> Result<(), Error> {
+        match position.checked_add(values.len()) {
+            Some(end) if end <= self.operand_count() => {}
+            _ => {
+                return Err(Error::OperationOperandPosition(
+                    self.to_string(),
+                    self.operand_count().max(position),
+                ))
+            }
+        }
+
+        for (index, &value) in values.iter().enumerate() {
+            self.set_operand(position + index, value)?;
+        }
+
+        Ok(())
     }
 
     /// Gets a result at an index.
@@ -121,7 +319,7 @@ impl<'a> OperationRef<'a> {
         unsafe {
             if index < self.region_count() as usize {
                 Some(RegionRef::from_raw(mlirOperationGetRegion(
-                    self.raw,
+                    self.to_raw(),
                     index as isize,
                 )))
             } else {
@@ -132,13 +330,129 @@ impl<'a> OperationRef<'a> {
 
     /// Gets a number of regions.
     pub fn region_count(&self) -> usize {
-        unsafe { mlirOperationGetNumRegions(self.raw) as usize }
+        unsafe { mlirOperationGetNumRegions(self.to_raw()) as usize }
+    }
+
+    /// Gets regions.
+    pub fn regions(&self) -> impl Iterator<Item = RegionRef> {
+        let raw = unsafe { self.to_raw() };
+
+        (0..self.region_count()).map(move |index| unsafe {
+            RegionRef::from_raw(mlirOperationGetRegion(raw, index as isize))
+        })
+    }
+
+    /// Gets an attribute with a name.
+    pub fn attribute(&self, name: &str) -> Option<Attribute<'a>> {
+        unsafe {
+            Attribute::from_option_raw(mlirOperationGetAttributeByName(
+                self.to_raw(),
+                StringRef::from(name).to_raw(),
+            ))
+        }
+    }
+
+    /// Gets a dictionary of attributes.
+    pub fn attribute_dictionary(&self) -> DictionaryAttribute<'a> {
+        unsafe {
+            DictionaryAttribute::from_raw(DictionaryAttribute::get_raw(
+                mlirOperationGetContext(self.to_raw()),
+                &self.attributes().collect::<Vec<_>>(),
+            ))
+        }
+    }
+
+    /// Gets a number of attributes.
+    pub fn attribute_count(&self) -> usize {
+        unsafe { mlirOperationGetNumAttributes(self.to_raw()) as usize }
+    }
+
+    /// Gets attributes.
+    pub fn attributes(&self) -> impl Iterator<Item = (Identifier<'a>, Attribute<'a>)> {
+        let raw = unsafe { self.to_raw() };
+
+        (0..self.attribute_count()).map(move |index| unsafe {
+            let attribute = mlirOperationGetAttribute(raw, index as isize);
+
+            (
+                Identifier::from_raw(attribute.name),
+                Attribute::from_raw(attribute.attribute),
+            )
+        })
+    }
+
+    /// Gets discardable attributes.
+    ///
+    /// Discardable attributes are ones whose names are prefixed with dialect
+    /// namespaces.
+    // TODO Use separate attribute storage of operations.
+    pub fn discardable_attributes(&self) -> impl Iterator<Item = (Identifier<'a>, Attribute<'a>)> {
+        self.attributes()
+            .filter(|(identifier, _)| is_discardable_attribute_name(identifier))
+    }
+
+    /// Gets inherent attributes.
+    ///
+    /// Inherent attributes are ones whose names are not prefixed with dialect
+    /// namespaces.
+    pub fn inherent_attributes(&self) -> impl Iterator<Item = (Identifier<'a>, Attribute<'a>)> {
+        self.attributes()
+            .filter(|(identifier, _)| !is_discardable_attribute_name(identifier))
+    }
+
+    /// Sets an attribute with a name.
+    pub fn set_attribute(&self, name: &str, attribute: Attribute) {
+        unsafe {
+            mlirOperationSetAttributeByName(
+                self.to_raw(),
+                StringRef::from(name).to_raw(),
+                attribute.to_raw(),
+            )
+        }
+    }
+
+    /// Removes an attribute with a name.
+    ///
+    /// It returns `false` if the attribute does not exist.
+    pub fn remove_attribute(&self, name: &str) -> bool {
+        unsafe { mlirOperationRemoveAttributeByName(self.to_raw(), StringRef::from(name).to_raw()) }
+    }
+
+    /// Gets a successor block at a position.
+    pub fn successor(&self, position: usize) -> Result<BlockRef, Error> {
+        if position < self.successor_count() {
+            unsafe {
+                Ok(BlockRef::from_raw(mlirOperationGetSuccessor(
+                    self.to_raw(),
+                    position as isize,
+                )))
+            }
+        } else {
+            Err(Error::OperationSuccessorPosition(
+                self.to_string(),
+                position,
+            ))
+        }
+    }
+
+    /// Gets a number of successor blocks.
+    pub fn successor_count(&self) -> usize {
+        unsafe { mlirOperationGetNumSuccessors(self.to_raw()) as usize }
+    }
+
+    /// Gets successor blocks.
+    pub fn successors(&self) -> impl Iterator<Item = BlockRef> {
+        let raw = unsafe { self.to_raw() };
+
+        (0..self.successor_count()).map(move |index| unsafe {
+            BlockRef::from_raw(mlirOperationGetSuccessor(raw, index as isize))
+        })
     }
 
     /// Gets the next operation in the same block.
-    pub fn next_in_block(&self) -> Option<OperationRef> {
+    pub fn next_in_block(&self) -> Option<OperationRef<'a>> {
         unsafe {
-            let operation = mlirOperationGetNextInBlock(self.raw);
+            let operation = mlirOperationGetNextInBlock(self.to_raw());
 
             if operation.ptr.is_null() {
                 None
@@ -148,28 +462,291 @@ impl<'a> OperationRef<'a> {
         }
     }
 
+    /// Moves an operation after another one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either operation is not in a block.
+    pub fn move_after(&self, other: OperationRef) {
+        assert!(self.block().is_some() && other.block().is_some());
+
+        unsafe { mlirOperationMoveAfter(self.to_raw(), other.to_raw()) }
+    }
+
+    /// Moves an operation before another one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either operation is not in a block.
+    pub fn move_before(&self, other: OperationRef) {
+        assert!(self.block().is_some() && other.block().is_some());
+
+        unsafe { mlirOperationMoveBefore(self.to_raw(), other.to_raw()) }
+    }
+
+    /// Replaces uses of results of an operation with values.
+    ///
+    /// This walks all operations in the outermost region or block of the
+    /// operation, which takes time linear to their number.
+    pub fn replace_uses(&self, values: &[Value]) -> Result<(), Error> {
+        if values.len() != self.result_count() {
+            return Err(Error::ResultCount(self.result_count(), values.len()));
+        }
+
+        let replacements = self
+            .results()
+            .map(Value::from)
+            .zip(values.iter().copied())
+            .collect::<HashMap<_, _>>();
+        let root = successors(Some(*self), |operation| operation.parent_operation())
+            .last()
+            .unwrap_or(*self);
+        let replace = |operation: OperationRef| {
+            operation.walk(WalkOrder::PreOrder, |operation| {
+                for (position, operand) in operation.operands().enumerate() {
+                    if let Some(&value) = replacements.get(&operand) {
+                        // Positions of existing operands are always valid.
+                        operation.set_operand(position, value).unwrap();
+                    }
+                }
+
+                WalkResult::Advance
+            });
+        };
+
+        // Uses can be in siblings of the outermost operation if it is in a
+        // detached block or region.
+        match root.block() {
+            Some(block) => match block.parent_region() {
+                Some(region) => {
+                    for block in region.blocks() {
+                        block.operations().for_each(replace);
+                    }
+                }
+                None => block.operations().for_each(replace),
+            },
+            None => replace(root),
+        }
+
+        Ok(())
+    }
+
+    /// Removes an operation from a block and assumes its ownership.
+    ///
+    /// It returns `None` if the operation is not in a block.
+    ///
+    /// # Safety
+    ///
+    /// This function might invalidate existing references to the operation
+    /// and its results if you drop the returned operation too early. Dropping
+    /// an operation whose results are still used aborts. References to the
+    /// operation are checked in debug builds but values are not.
+    pub unsafe fn remove_from_parent(&self) -> Option<Operation<'a>> {
+        self.block()?;
+
+        mlirOperationRemoveFromParent(self.to_raw());
+
+        Some(Operation::from_raw(self.to_raw()))
+    }
+
+    /// Erases an operation in a block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operation is not in a block, in which case it is owned by
+    /// [`Operation`] instead.
+    ///
+    /// # Safety
+    ///
+    /// Values of the operation must not be used afterwards and its results
+    /// must have no uses. Replace them with [`OperationRef::replace_uses`]
+    /// first.
+    pub unsafe fn erase(self) {
+        drop(self.remove_from_parent().expect("operation in block"));
+    }
+
+    /// Walks an operation and its nested operations recursively.
+    ///
+    /// It returns `WalkResult::Interrupt` if a walk is interrupted, or
+    /// `WalkResult::Advance` otherwise.
+    pub fn walk(
+        &self,
+        order: WalkOrder,
+        mut callback: impl FnMut(OperationRef<'a>) -> WalkResult,
+    ) -> WalkResult {
+        unsafe {
+            walk::walk(self.to_raw(), order, &mut |operation| {
+                callback(OperationRef::from_raw(operation))
+            })
+        }
+    }
+
+    /// Gets statistics of an operation and its nested IR objects.
+    pub fn statistics(&self) -> Statistics {
+        let mut statistics = Statistics::default();
+
+        self.walk(WalkOrder::PreOrder, |operation| {
+            statistics.add_operation(
+                operation.region_count(),
+                operation
+                    .regions()
+                    .map(|region| region.blocks().count())
+                    .sum(),
+            );
+
+            WalkResult::Advance
+        });
+
+        statistics
+    }
+
     /// Verifies an operation.
+    // TODO Map verification diagnostics to typed errors of operation views,
+    // e.g. `func.func` or `scf.for` ones, when we have such views.
     pub fn verify(&self) -> bool {
-        unsafe { mlirOperationVerify(self.raw) }
+        unsafe { mlirOperationVerify(self.to_raw()) }
+    }
+
+    /// Verifies an operation and returns errors reported by verifiers.
+    ///
+    /// Each error is associated with a nested operation which has the same
+    /// location as its diagnostic. Diagnostics are still passed to other
+    /// diagnostic handlers.
+    pub fn verify_with_diagnostics(&self) -> Result<(), Vec<VerificationError<'a>>> {
+        let (verified, diagnostics) = capture_diagnostics(self.context(), || self.verify());
+
+        if verified {
+            return Ok(());
+        }
+
+        let unknown =
+            unsafe { Location::from_raw(mlirLocationUnknownGet(self.context().to_raw())) }
+                .to_string();
+        let mut operations = vec![];
+
+        self.walk(WalkOrder::PreOrder, |operation| {
+            operations.push((operation, operation.location().to_string()));
+            WalkResult::Advance
+        });
+
+        Err(diagnostics
+            .into_iter()
+            .filter(|diagnostic| diagnostic.severity() == Severity::Error)
+            .map(|diagnostic| {
+                let mut candidates = operations
+                    .iter()
+                    .filter(|(_, location)| location == diagnostic.location());
+
+                VerificationError::new(
+                    match (candidates.next(), candidates.next()) {
+                        (Some(&(operation, _)), None) if diagnostic.location() != unknown => {
+                            Some(operation)
+                        }
+                        _ => None,
+                    },
+                    diagnostic,
+                )
+            })
+            .collect())
     }
 
     /// Dumps an operation.
     pub fn dump(&self) {
-        unsafe { mlirOperationDump(self.raw) }
+        unsafe { mlirOperationDump(self.to_raw()) }
+    }
+
+    /// Converts an operation into a string with printing flags.
+    pub fn to_string_with_flags(&self, flags: &PrintingFlags) -> String {
+        if let Some(limit) = flags.large_attribute_limit() {
+            let operation = self.to_owned();
+
+            unsafe { elide_large_attributes(operation.raw, limit) };
+
+            FlaggedOperation::new(*operation, flags).to_string()
+        } else {
+            FlaggedOperation::new(*self, flags).to_string()
+        }
+    }
+
+    /// Converts an operation into a string for snapshot tests.
+    ///
+    /// Operations are printed in generic form without locations, and large
+    /// attributes are elided. The output is more stable across MLIR versions
+    /// than custom forms of operations.
+    pub fn print_generic_deterministic(&self) -> String {
+        self.to_string_with_flags(
+            &PrintingFlags::new()
+                .elide_large_elements_attributes(DETERMINISTIC_ELEMENT_LIMIT)
+                .elide_large_attributes(DETERMINISTIC_ATTRIBUTE_LIMIT)
+                .enable_debug_info(false, false)
+                .print_generic_operation_form(),
+        )
     }
 
     /// Clones an operation.
-    pub fn to_owned(&self) -> Operation {
-        unsafe { Operation::from_raw(mlirOperationClone(self.raw)) }
+    pub fn to_owned(&self) -> Operation<'a> {
+        unsafe { Operation::from_raw(mlirOperationClone(self.to_raw())) }
+    }
+
+    /// Clones an operation with its regions left empty.
+    ///
+    /// Operands, results, attributes, and successors are kept as they are.
+    pub fn clone_without_regions(&self) -> Operation<'a> {
+        Builder::new(self.name_str(), self.location())
+            .add_operands(&self.operands().collect::<Vec<_>>())
+            .add_results(
+                &self
+                    .results()
+                    .map(|result| result.r#type())
+                    .collect::<Vec<_>>(),
+            )
+            .add_attributes(&self.attributes().collect::<Vec<_>>())
+            .add_successors(&self.successors().collect::<Vec<_>>())
+            .add_regions((0..self.region_count()).map(|_| Region::new()).collect())
+            .build()
+            // Operations with explicit result types are always built.
+            .unwrap()
+    }
+
+    /// Clones an operation with operands remapped.
+    ///
+    /// Operands of the operation and its nested operations are replaced with
+    /// values which they are mapped to. Values defined inside the operation
+    /// are remapped to their clones already. Results of the operation are
+    /// mapped to results of the clone afterwards.
+    pub fn clone_with_mapping(&self, mapping: &mut ValueMapping<'a>) -> Operation<'a> {
+        let operation = self.to_owned();
+
+        operation.walk(WalkOrder::PreOrder, |operation| {
+            for (position, operand) in operation.operands().enumerate() {
+                if let Some(value) = mapping.get(operand) {
+                    // Positions of existing operands are always valid.
+                    operation.set_operand(position, value).unwrap();
+                }
+            }
+
+            WalkResult::Advance
+        });
+
+        for (result, clone) in self.results().zip(operation.results()) {
+            mapping.insert(result.into(), clone.into());
+        }
+
+        operation
     }
 
     pub(crate) unsafe fn to_raw(self) -> MlirOperation {
+        #[cfg(debug_assertions)]
+        self.generation.check(self.raw.ptr, "operation");
+
         self.raw
     }
 
     pub(crate) unsafe fn from_raw(raw: MlirOperation) -> Self {
         Self {
             raw,
+            #[cfg(debug_assertions)]
+            generation: generation::Generation::new(raw.ptr),
             _reference: Default::default(),
         }
     }
@@ -197,7 +774,7 @@ impl<'a> Display for OperationRef<'a> {
 
         unsafe {
             mlirOperationPrint(
-                self.raw,
+                self.to_raw(),
                 Some(print_callback),
                 &mut data as *mut _ as *mut c_void,
             );
@@ -215,18 +792,185 @@ impl<'a> Debug for OperationRef<'a> {
     }
 }
 
+struct FlaggedOperation<'a, 'b> {
+    operation: OperationRef<'a>,
+    flags: &'b PrintingFlags,
+}
+
+impl<'a, 'b> FlaggedOperation<'a, 'b> {
+    fn new(operation: OperationRef<'a>, flags: &'b PrintingFlags) -> Self {
+        Self { operation, flags }
+    }
+}
+
+impl<'a, 'b> Display for FlaggedOperation<'a, 'b> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let mut data = (formatter, Ok(()));
+
+        unsafe {
+            mlirOperationPrintWithFlags(
+                self.operation.raw,
+                self.flags.to_raw(),
+                Some(print_callback),
+                &mut data as *mut _ as *mut c_void,
+            );
+        }
+
+        data.1
+    }
+}
+
+fn is_discardable_attribute_name(identifier: &Identifier) -> bool {
+    identifier
+        .as_string_ref()
+        .as_str()
+        .map(|name| split_name(name).is_some())
+        .unwrap_or_default()
+}
+
+unsafe fn elide_large_attributes(operation: MlirOperation, limit: usize) {
+    walk::walk(operation, WalkOrder::PreOrder, &mut |operation| {
+        for index in 0..mlirOperationGetNumAttributes(operation) {
+            let attribute = mlirOperationGetAttribute(operation, index);
+            let size = Attribute::from_raw(attribute.attribute).to_string().len();
+
+            if size > limit {
+                // Attributes are replaced in place and their indices do not change.
+                mlirOperationSetAttributeByName(
+                    operation,
+                    mlirIdentifierStr(attribute.name),
+                    mlirStringAttrGet(
+                        mlirOperationGetContext(operation),
+                        StringRef::from(format!("<elided {} bytes>", size).as_str()).to_raw(),
+                    ),
+                );
+            }
+        }
+
+        WalkResult::Advance
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         context::Context,
-        ir::{Block, Location},
+        ir::{Block, Location, Module, Region, Type, ValueLike},
     };
+    use indoc::indoc;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn split_name() {
+        assert_eq!(super::split_name("scf.for"), Some(("scf", "for")));
+        assert_eq!(
+            super::split_name("llvm.mlir.constant"),
+            Some(("llvm", "mlir.constant"))
+        );
+        assert_eq!(super::split_name("foo"), None);
+    }
+
+    #[test]
+    fn name_str() {
+        let context = Context::new();
+        let operation = Builder::new("foo.bar", Location::unknown(&context))
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.name_str(), "foo.bar");
+        assert_eq!(operation.dialect_name(), Some("foo"));
+        assert!(operation.is("foo.bar"));
+        assert!(!operation.is("foo.baz"));
+    }
+
+    #[test]
+    fn dialect_name_without_dialect() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.dialect_name(), None);
+    }
+
+    #[test]
+    fn matches() {
+        let context = Context::new();
+        let operation = Builder::new("foo.bar", Location::unknown(&context))
+            .build()
+            .unwrap();
+
+        assert!(operation.matches(&matcher::name("foo.bar")));
+        assert!(!operation.matches(&matcher::constant()));
+    }
+
+    #[test]
+    fn parse() {
+        let context = Context::new();
+        let operation =
+            Operation::parse(&context, "%0 = arith.constant 42 : i64", "foo.mlir").unwrap();
+
+        assert_eq!(
+            operation.name(),
+            Identifier::new(&context, "arith.constant")
+        );
+        assert_eq!(operation.block(), None);
+    }
+
+    #[test]
+    fn parse_module() {
+        let context = Context::new();
+        let operation = Operation::parse(&context, "module @foo {}", "foo.mlir").unwrap();
+
+        assert_eq!(
+            operation.name(),
+            Identifier::new(&context, "builtin.module")
+        );
+        assert!(operation.verify());
+    }
+
+    #[test]
+    fn parse_error() {
+        let context = Context::new();
+
+        assert!(matches!(
+            Operation::parse(&context, "%0 = arith.constant", "foo.mlir"),
+            Err(Error::ParseOperation(Some(range), _, _))
+                if range.start().filename() == "foo.mlir" && range.start().line() == 1
+        ));
+    }
+
+    #[test]
+    fn parse_location() {
+        let context = Context::new();
+        let operation = Operation::parse(&context, "\n  %0 = arith.constant 0 : index", "foo.mlir");
+
+        assert_eq!(
+            operation.unwrap().location().to_string(),
+            "loc(\"foo.mlir\":2:3)"
+        );
+    }
+
+    #[test]
+    fn parse_many_operations() {
+        let context = Context::new();
+
+        assert_eq!(
+            Operation::parse(&context, "module {}\nmodule {}", "foo.mlir"),
+            Err(Error::ParseOperation(
+                None,
+                "single operation expected but 2 found".into(),
+                vec![]
+            ))
+        );
+    }
+
     #[test]
     fn new() {
-        Builder::new("foo", Location::unknown(&Context::new())).build();
+        Builder::new("foo", Location::unknown(&Context::new()))
+            .build()
+            .unwrap();
     }
 
     #[test]
@@ -236,6 +980,7 @@ mod tests {
         assert_eq!(
             Builder::new("foo", Location::unknown(&context),)
                 .build()
+                .unwrap()
                 .name(),
             Identifier::new(&context, "foo")
         );
@@ -243,9 +988,13 @@ mod tests {
 
     #[test]
     fn block() {
+        let context = Context::new();
         let block = Block::new(&[]);
-        let operation =
-            block.append_operation(Builder::new("foo", Location::unknown(&Context::new())).build());
+        let operation = block.append_operation(
+            Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
 
         assert_eq!(operation.block(), Some(*block));
     }
@@ -255,6 +1004,7 @@ mod tests {
         assert_eq!(
             Builder::new("foo", Location::unknown(&Context::new()))
                 .build()
+                .unwrap()
                 .block(),
             None
         );
@@ -265,6 +1015,7 @@ mod tests {
         assert_eq!(
             Builder::new("foo", Location::unknown(&Context::new()))
                 .build()
+                .unwrap()
                 .result(0)
                 .unwrap_err(),
             Error::OperationResultPosition("\"foo\"() : () -> ()\n".into(), 0)
@@ -272,21 +1023,612 @@ mod tests {
     }
 
     #[test]
-    fn region_none() {
-        assert!(Builder::new("foo", Location::unknown(&Context::new()),)
+    fn results() {
+        let context = Context::new();
+        let r#type = Type::index(&context);
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_results(&[r#type, r#type])
             .build()
-            .region(0)
-            .is_none());
+            .unwrap();
+
+        assert_eq!(
+            operation
+                .results()
+                .map(|result| result.result_number())
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert!(operation
+            .results()
+            .all(|result| result.owner() == *operation && result.r#type() == r#type));
+    }
+
+    #[test]
+    fn results_empty() {
+        let context = Context::new();
+
+        assert_eq!(
+            Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap()
+                .results()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn operand() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+        let argument = block.argument(0).unwrap().into();
+        let operation = block.append_operation(
+            Builder::new("foo", location)
+                .add_operands(&[argument])
+                .build()
+                .unwrap(),
+        );
+
+        assert_eq!(operation.operand_count(), 1);
+        assert_eq!(operation.operand(0).unwrap(), argument);
+    }
+
+    #[test]
+    fn operand_error() {
+        assert_eq!(
+            Builder::new("foo", Location::unknown(&Context::new()))
+                .build()
+                .unwrap()
+                .operand(0)
+                .unwrap_err(),
+            Error::OperationOperandPosition("\"foo\"() : () -> ()\n".into(), 0)
+        );
+    }
+
+    #[test]
+    fn operands() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::index(&context);
+        let block = Block::new(&[(r#type, location), (r#type, location)]);
+        let arguments = [
+            block.argument(0).unwrap().into(),
+            block.argument(1).unwrap().into(),
+        ];
+        let operation = block.append_operation(
+            Builder::new("foo", location)
+                .add_operands(&arguments)
+                .build()
+                .unwrap(),
+        );
+
+        assert_eq!(operation.operands().collect::<Vec<_>>(), arguments);
+    }
+
+    fn attribute_names<'a>(
+        attributes: impl Iterator<Item = (Identifier<'a>, Attribute<'a>)>,
+    ) -> Vec<String> {
+        attributes
+            .map(|(identifier, _)| identifier.as_string_ref().as_str().unwrap().into())
+            .collect()
+    }
+
+    #[test]
+    fn attribute() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_attributes(&[(
+                Identifier::new(&context, "bar"),
+                Attribute::parse(&context, "0").unwrap(),
+            )])
+            .add_discardable_attributes(&[(
+                Identifier::new(&context, "baz.qux"),
+                Attribute::parse(&context, "unit").unwrap(),
+            )])
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.attribute("bar"), Attribute::parse(&context, "0"));
+        assert_eq!(operation.attribute("foo"), None);
+    }
+
+    #[test]
+    fn attributes() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_attributes(&[(
+                Identifier::new(&context, "bar"),
+                Attribute::parse(&context, "0").unwrap(),
+            )])
+            .add_discardable_attributes(&[(
+                Identifier::new(&context, "baz.qux"),
+                Attribute::parse(&context, "unit").unwrap(),
+            )])
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.attribute_count(), 2);
+        assert_eq!(attribute_names(operation.attributes()), ["bar", "baz.qux"]);
+    }
+
+    #[test]
+    fn attribute_dictionary() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_attributes(&[(
+                Identifier::new(&context, "bar"),
+                Attribute::parse(&context, "0").unwrap(),
+            )])
+            .add_discardable_attributes(&[(
+                Identifier::new(&context, "baz.qux"),
+                Attribute::parse(&context, "unit").unwrap(),
+            )])
+            .build()
+            .unwrap();
+        let dictionary = operation.attribute_dictionary();
+
+        assert_eq!(dictionary.element_count(), 2);
+        assert_eq!(dictionary.get("bar"), Attribute::parse(&context, "0"));
+        assert_eq!(attribute_names(dictionary.elements()), ["bar", "baz.qux"]);
+    }
+
+    #[test]
+    fn discardable_attributes() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_attributes(&[(
+                Identifier::new(&context, "bar"),
+                Attribute::parse(&context, "0").unwrap(),
+            )])
+            .add_discardable_attributes(&[(
+                Identifier::new(&context, "baz.qux"),
+                Attribute::parse(&context, "unit").unwrap(),
+            )])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            attribute_names(operation.discardable_attributes()),
+            ["baz.qux"]
+        );
+        assert_eq!(attribute_names(operation.inherent_attributes()), ["bar"]);
+    }
+
+    #[test]
+    fn set_attribute() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_attributes(&[(
+                Identifier::new(&context, "bar"),
+                Attribute::parse(&context, "0").unwrap(),
+            )])
+            .add_discardable_attributes(&[(
+                Identifier::new(&context, "baz.qux"),
+                Attribute::parse(&context, "unit").unwrap(),
+            )])
+            .build()
+            .unwrap();
+
+        operation.set_attribute("bar", Attribute::parse(&context, "1").unwrap());
+        operation.set_attribute("foo", Attribute::parse(&context, "2").unwrap());
+
+        assert_eq!(operation.attribute("bar"), Attribute::parse(&context, "1"));
+        assert_eq!(operation.attribute("foo"), Attribute::parse(&context, "2"));
+    }
+
+    #[test]
+    fn remove_attribute() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_attributes(&[(
+                Identifier::new(&context, "bar"),
+                Attribute::parse(&context, "0").unwrap(),
+            )])
+            .add_discardable_attributes(&[(
+                Identifier::new(&context, "baz.qux"),
+                Attribute::parse(&context, "unit").unwrap(),
+            )])
+            .build()
+            .unwrap();
+
+        assert!(operation.remove_attribute("bar"));
+        assert!(!operation.remove_attribute("bar"));
+        assert_eq!(operation.attribute("bar"), None);
+    }
+
+    #[test]
+    fn location() {
+        let context = Context::new();
+        let location = Location::new(&context, "foo", 1, 2);
+
+        assert_eq!(
+            Builder::new("foo", location).build().unwrap().location(),
+            location
+        );
+    }
+
+    #[test]
+    fn verify_with_diagnostics() {
+        let context = Context::new();
+
+        let module = Module::new(Location::unknown(&context));
+        let function = module.body().append_operation(
+            Builder::new("func.func", Location::new(&context, "foo", 1, 1))
+                .add_regions(vec![Region::new()])
+                .build()
+                .unwrap(),
+        );
+
+        let errors = module.as_operation().verify_with_diagnostics().unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].operation(), Some(function));
+        assert!(errors[0].to_string().starts_with("loc(\"foo\":1:1): "));
+    }
+
+    #[test]
+    fn verify_with_diagnostics_success() {
+        let context = Context::new();
+        context.set_allow_unregistered_dialects(true);
+
+        assert!(Builder::new("foo.bar", Location::unknown(&context))
+            .build()
+            .unwrap()
+            .verify_with_diagnostics()
+            .is_ok());
+    }
+
+    #[test]
+    fn successor() {
+        let context = Context::new();
+        let block = Block::new(&[]);
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_successors(&[*block])
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.successor(0).unwrap(), *block);
+        assert_eq!(
+            operation.successor(1).unwrap_err(),
+            Error::OperationSuccessorPosition(operation.to_string(), 1)
+        );
+    }
+
+    #[test]
+    fn successors() {
+        let context = Context::new();
+        let blocks = [Block::new(&[]), Block::new(&[])];
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_successors(&[*blocks[0], *blocks[1]])
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.successor_count(), 2);
+        assert_eq!(
+            operation.successors().collect::<Vec<_>>(),
+            [*blocks[0], *blocks[1]]
+        );
+    }
+
+    #[test]
+    fn region_none() {
+        assert!(Builder::new("foo", Location::unknown(&Context::new()),)
+            .build()
+            .unwrap()
+            .region(0)
+            .is_none());
+    }
+
+    #[test]
+    fn regions() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_regions(vec![Region::new(), Region::new()])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            operation.regions().collect::<Vec<_>>(),
+            vec![operation.region(0).unwrap(), operation.region(1).unwrap()]
+        );
+    }
+
+    #[test]
+    fn regions_empty() {
+        assert_eq!(
+            Builder::new("foo", Location::unknown(&Context::new()))
+                .build()
+                .unwrap()
+                .regions()
+                .count(),
+            0
+        );
+    }
+
+    fn walk_names(operation: OperationRef, order: WalkOrder, skipped: &str) -> Vec<String> {
+        let mut names = vec![];
+
+        operation.walk(order, |operation| {
+            let name = operation
+                .name()
+                .as_string_ref()
+                .as_str()
+                .unwrap()
+                .to_owned();
+            let result = if name == skipped {
+                WalkResult::Skip
+            } else {
+                WalkResult::Advance
+            };
+
+            names.push(name);
+
+            result
+        });
+
+        names
+    }
+
+    #[test]
+    fn walk_pre_order() {
+        let context = Context::new();
+        let operation = Operation::parse(
+            &context,
+            indoc!(
+                "
+                \"foo\"() ({
+                  \"bar\"() ({
+                    \"baz\"() : () -> ()
+                  }) : () -> ()
+                  \"qux\"() : () -> ()
+                }) : () -> ()
+                "
+            ),
+            "foo.mlir",
+        )
+        .unwrap();
+
+        assert_eq!(
+            walk_names(*operation, WalkOrder::PreOrder, ""),
+            ["foo", "bar", "baz", "qux"]
+        );
+    }
+
+    #[test]
+    fn walk_post_order() {
+        let context = Context::new();
+        let operation = Operation::parse(
+            &context,
+            indoc!(
+                "
+                \"foo\"() ({
+                  \"bar\"() ({
+                    \"baz\"() : () -> ()
+                  }) : () -> ()
+                  \"qux\"() : () -> ()
+                }) : () -> ()
+                "
+            ),
+            "foo.mlir",
+        )
+        .unwrap();
+
+        assert_eq!(
+            walk_names(*operation, WalkOrder::PostOrder, ""),
+            ["baz", "bar", "qux", "foo"]
+        );
+    }
+
+    #[test]
+    fn walk_skip() {
+        let context = Context::new();
+        let operation = Operation::parse(
+            &context,
+            indoc!(
+                "
+                \"foo\"() ({
+                  \"bar\"() ({
+                    \"baz\"() : () -> ()
+                  }) : () -> ()
+                  \"qux\"() : () -> ()
+                }) : () -> ()
+                "
+            ),
+            "foo.mlir",
+        )
+        .unwrap();
+
+        assert_eq!(
+            walk_names(*operation, WalkOrder::PreOrder, "bar"),
+            ["foo", "bar", "qux"]
+        );
+    }
+
+    #[test]
+    fn walk_skip_post_order() {
+        let context = Context::new();
+        let operation = Operation::parse(
+            &context,
+            indoc!(
+                "
+                \"foo\"() ({
+                  \"bar\"() ({
+                    \"baz\"() : () -> ()
+                  }) : () -> ()
+                  \"qux\"() : () -> ()
+                }) : () -> ()
+                "
+            ),
+            "foo.mlir",
+        )
+        .unwrap();
+
+        assert_eq!(
+            walk_names(*operation, WalkOrder::PostOrder, "bar"),
+            ["baz", "bar", "qux", "foo"]
+        );
+    }
+
+    #[test]
+    fn walk_interrupt() {
+        let context = Context::new();
+        let operation = Operation::parse(
+            &context,
+            indoc!(
+                "
+                \"foo\"() ({
+                  \"bar\"() ({
+                    \"baz\"() : () -> ()
+                  }) : () -> ()
+                  \"qux\"() : () -> ()
+                }) : () -> ()
+                "
+            ),
+            "foo.mlir",
+        )
+        .unwrap();
+        let mut count = 0;
+
+        assert_eq!(
+            operation.walk(WalkOrder::PreOrder, |operation| {
+                count += 1;
+
+                if operation.name() == Identifier::new(&context, "baz") {
+                    WalkResult::Interrupt
+                } else {
+                    WalkResult::Advance
+                }
+            }),
+            WalkResult::Interrupt
+        );
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn walk_without_nested_operations() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .build()
+            .unwrap();
+        let mut count = 0;
+
+        assert_eq!(
+            operation.walk(WalkOrder::PostOrder, |_| {
+                count += 1;
+                WalkResult::Advance
+            }),
+            WalkResult::Advance
+        );
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn statistics() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                cf.br ^bb1
+            ^bb1:
+                return
+            }
+
+            func.func private @bar()
+            "#,
+        )
+        .unwrap();
+
+        let statistics = module.as_operation().statistics();
+
+        assert_eq!(statistics.operation_count(), 5);
+        assert_eq!(statistics.region_count(), 3);
+        assert_eq!(statistics.block_count(), 3);
     }
 
     #[test]
     fn to_owned() {
         let context = Context::new();
-        let operation = Builder::new("foo", Location::unknown(&context)).build();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .build()
+            .unwrap();
 
         operation.to_owned();
     }
 
+    #[test]
+    fn parent_operation() {
+        let context = Context::new();
+        let module = Module::new(Location::unknown(&context));
+        let operation = module.body().append_operation(
+            Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
+
+        assert_eq!(operation.parent_operation(), Some(module.as_operation()));
+        assert_eq!(module.as_operation().parent_operation(), None);
+    }
+
+    #[test]
+    fn clone_without_regions() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let region = Region::new();
+        region.append_block(Block::new(&[]));
+
+        let operation = Builder::new("foo", location)
+            .add_results(&[Type::index(&context)])
+            .add_attributes(&[(
+                Identifier::new(&context, "bar"),
+                Attribute::parse(&context, "42 : i64").unwrap(),
+            )])
+            .add_regions(vec![region])
+            .build()
+            .unwrap();
+
+        let clone = operation.clone_without_regions();
+
+        assert_eq!(clone.region_count(), 1);
+        assert_eq!(clone.region(0).unwrap().first_block(), None);
+        assert_eq!(clone.result_count(), 1);
+        assert_eq!(
+            clone.attribute("bar"),
+            Some(Attribute::parse(&context, "42 : i64").unwrap())
+        );
+    }
+
+    #[test]
+    fn clone_with_mapping() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::index(&context);
+        let block = Block::new(&[(r#type, location), (r#type, location)]);
+        let one = block.argument(0).unwrap().into();
+        let other = block.argument(1).unwrap().into();
+
+        let operation = block.append_operation(
+            Builder::new("foo", location)
+                .add_operands(&[one, one])
+                .add_results(&[r#type])
+                .build()
+                .unwrap(),
+        );
+
+        let mut mapping = ValueMapping::new();
+        mapping.insert(one, other);
+
+        let clone = operation.clone_with_mapping(&mut mapping);
+
+        assert_eq!(clone.operands().collect::<Vec<_>>(), vec![other, other]);
+        assert_eq!(operation.operand(0), Ok(one));
+        assert_eq!(
+            mapping.get(operation.result(0).unwrap().into()),
+            Some(clone.result(0).unwrap().into())
+        );
+    }
+
     #[test]
     fn display() {
         let context = Context::new();
@@ -294,11 +1636,128 @@ mod tests {
         assert_eq!(
             Builder::new("foo", Location::unknown(&context),)
                 .build()
+                .unwrap()
                 .to_string(),
             "\"foo\"() : () -> ()\n"
         );
     }
 
+    #[test]
+    fn to_string_with_flags() {
+        let context = Context::new();
+        let module = Module::parse(&context, "func.func @foo() { return }").unwrap();
+
+        assert_eq!(
+            module.as_operation().to_string_with_flags(
+                &PrintingFlags::new()
+                    .print_generic_operation_form()
+                    .use_local_scope()
+            ),
+            indoc!(
+                "
+                \"builtin.module\"() ({
+                  \"func.func\"() ({
+                    \"func.return\"() : () -> ()
+                  }) {function_type = () -> (), sym_name = \"foo\"} : () -> ()
+                }) : () -> ()
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn to_string_with_debug_info() {
+        let context = Context::new();
+        let module =
+            Module::parse_with_filename(&context, "func.func @foo() { return }", "foo.mlir")
+                .unwrap();
+
+        assert!(module
+            .as_operation()
+            .to_string_with_flags(&PrintingFlags::new().enable_debug_info(true, false))
+            .contains("loc(\"foo.mlir\":1:1)"));
+    }
+
+    #[test]
+    fn print_generic_deterministic() {
+        let context = Context::new();
+        let module =
+            Module::parse_with_filename(&context, "func.func @foo() { return }", "foo.mlir")
+                .unwrap();
+
+        assert_eq!(
+            module.as_operation().print_generic_deterministic(),
+            indoc!(
+                "
+                \"builtin.module\"() ({
+                  \"func.func\"() ({
+                    \"func.return\"() : () -> ()
+                  }) {function_type = () -> (), sym_name = \"foo\"} : () -> ()
+                }) : () -> ()
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn print_generic_deterministic_eliding_large_attributes() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_attributes(&[(
+                Identifier::new(&context, "bar"),
+                Attribute::parse(&context, &format!("\"{}\"", "a".repeat(2000))).unwrap(),
+            )])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            operation.print_generic_deterministic(),
+            "\"foo\"() {bar = \"<elided 2002 bytes>\"} : () -> ()\n"
+        );
+    }
+
+    #[test]
+    fn to_string_with_flags_eliding_large_attributes() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let operation = Builder::new("foo", location)
+            .add_attributes(&[
+                (
+                    Identifier::new(&context, "bar"),
+                    Attribute::parse(&context, &format!("\"{}\"", "a".repeat(100))).unwrap(),
+                ),
+                (
+                    Identifier::new(&context, "baz"),
+                    Attribute::parse(&context, "42 : i64").unwrap(),
+                ),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            operation.to_string_with_flags(&PrintingFlags::new().elide_large_attributes(16)),
+            "\"foo\"() {bar = \"<elided 102 bytes>\", baz = 42 : i64} : () -> ()\n"
+        );
+        assert!(operation.to_string().contains(&"a".repeat(100)));
+    }
+
+    #[test]
+    fn to_string_with_flags_eliding_nested_large_attributes() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            &format!("func.func @{}() {{ return }}", "a".repeat(100)),
+        )
+        .unwrap();
+
+        let source = module
+            .as_operation()
+            .to_string_with_flags(&PrintingFlags::new().elide_large_attributes(64));
+
+        assert!(source.contains("sym_name = \"<elided 102 bytes>\""));
+        assert!(!source.contains(&"a".repeat(100)));
+    }
+
     #[test]
     fn debug() {
         let context = Context::new();
@@ -306,9 +1765,236 @@ mod tests {
         assert_eq!(
             format!(
                 "{:?}",
-                *Builder::new("foo", Location::unknown(&context)).build()
+                *Builder::new("foo", Location::unknown(&context))
+                    .build()
+                    .unwrap()
             ),
             "OperationRef(\n\"foo\"() : () -> ()\n)"
         );
     }
+
+    fn operation_names(block: &Block) -> Vec<String> {
+        block
+            .operations()
+            .map(|operation| {
+                operation
+                    .name()
+                    .as_string_ref()
+                    .as_str()
+                    .unwrap()
+                    .to_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn set_operand() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::index(&context);
+        let block = Block::new(&[(r#type, location), (r#type, location)]);
+        let operation = Builder::new("foo", location)
+            .add_operands(&[block.argument(0).unwrap().into()])
+            .build()
+            .unwrap();
+
+        operation
+            .set_operand(0, block.argument(1).unwrap().into())
+            .unwrap();
+
+        assert_eq!(
+            operation.operand(0).unwrap(),
+            block.argument(1).unwrap().into()
+        );
+        assert!(operation
+            .set_operand(1, block.argument(1).unwrap().into())
+            .is_err());
+    }
+
+    #[test]
+    fn set_operands() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::index(&context);
+        let block = Block::new(&[(r#type, location), (r#type, location)]);
+        let argument = block.argument(0).unwrap().into();
+        let other = block.argument(1).unwrap().into();
+        let operation = Builder::new("foo", location)
+            .add_operands(&[argument, argument, argument])
+            .build()
+            .unwrap();
+
+        operation.set_operands(1, &[other, other]).unwrap();
+
+        assert_eq!(
+            operation.operands().collect::<Vec<_>>(),
+            [argument, other, other]
+        );
+        assert_eq!(
+            operation.set_operands(2, &[other, other]).unwrap_err(),
+            Error::OperationOperandPosition(operation.to_string(), 3)
+        );
+        assert_eq!(
+            operation.set_operands(usize::MAX, &[other]).unwrap_err(),
+            Error::OperationOperandPosition(operation.to_string(), usize::MAX)
+        );
+    }
+
+    #[test]
+    fn move_after() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[]);
+
+        for name in ["foo", "bar", "baz"] {
+            block.append_operation(Builder::new(name, location).build().unwrap());
+        }
+
+        let foo = block.first_operation().unwrap();
+
+        foo.move_after(block.terminator().unwrap());
+
+        assert_eq!(operation_names(&block), ["bar", "baz", "foo"]);
+    }
+
+    #[test]
+    fn move_before() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[]);
+
+        for name in ["foo", "bar", "baz"] {
+            block.append_operation(Builder::new(name, location).build().unwrap());
+        }
+
+        let baz = block.terminator().unwrap();
+
+        baz.move_before(block.first_operation().unwrap());
+
+        assert_eq!(operation_names(&block), ["baz", "foo", "bar"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn move_detached_operation() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[]);
+
+        block.append_operation(Builder::new("foo", location).build().unwrap());
+
+        let operation = Builder::new("bar", location).build().unwrap();
+
+        operation.move_after(block.first_operation().unwrap());
+    }
+
+    #[test]
+    fn replace_uses() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::index(&context);
+        let block = Block::new(&[(r#type, location)]);
+        let argument = block.argument(0).unwrap().into();
+        let foo = block.append_operation(
+            Builder::new("foo", location)
+                .add_results(&[r#type])
+                .build()
+                .unwrap(),
+        );
+        let bar = block.append_operation(
+            Builder::new("bar", location)
+                .add_operands(&[foo.result(0).unwrap().into()])
+                .build()
+                .unwrap(),
+        );
+
+        foo.replace_uses(&[argument]).unwrap();
+
+        assert_eq!(bar.operand(0).unwrap(), argument);
+    }
+
+    #[test]
+    fn replace_uses_in_detached_region() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::index(&context);
+        let region = Region::new();
+        let block = region.append_block(Block::new(&[(r#type, location)]));
+        let argument = block.argument(0).unwrap().into();
+        let foo = block.append_operation(
+            Builder::new("foo", location)
+                .add_results(&[r#type])
+                .build()
+                .unwrap(),
+        );
+        let other = region.append_block(Block::new(&[]));
+        let bar = other.append_operation(
+            Builder::new("bar", location)
+                .add_operands(&[foo.result(0).unwrap().into()])
+                .build()
+                .unwrap(),
+        );
+
+        foo.replace_uses(&[argument]).unwrap();
+
+        assert_eq!(bar.operand(0).unwrap(), argument);
+    }
+
+    #[test]
+    fn replace_uses_with_wrong_count() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+        let operation = block.append_operation(Builder::new("foo", location).build().unwrap());
+
+        assert_eq!(
+            operation.replace_uses(&[block.argument(0).unwrap().into()]),
+            Err(Error::ResultCount(0, 1))
+        );
+    }
+
+    #[test]
+    fn remove_from_parent() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[]);
+
+        for name in ["foo", "bar"] {
+            block.append_operation(Builder::new(name, location).build().unwrap());
+        }
+
+        let operation = unsafe { block.first_operation().unwrap().remove_from_parent() }.unwrap();
+
+        assert_eq!(operation.block(), None);
+        assert_eq!(operation_names(&block), ["bar"]);
+        assert!(unsafe { operation.remove_from_parent() }.is_none());
+    }
+
+    #[test]
+    fn erase() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[]);
+
+        for name in ["foo", "bar"] {
+            block.append_operation(Builder::new(name, location).build().unwrap());
+        }
+
+        unsafe { block.first_operation().unwrap().erase() };
+
+        assert_eq!(operation_names(&block), ["bar"]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn use_erased_operation() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[]);
+        let operation = block.append_operation(Builder::new("foo", location).build().unwrap());
+
+        unsafe { operation.erase() };
+        operation.name();
+    }
 }
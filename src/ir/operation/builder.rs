@@ -1,80 +1,161 @@
 use crate::{
-    context::Context,
+    context::{Context, ContextRef},
     ir::{Attribute, BlockRef, Identifier, Location, Region, Type, TypeLike, Value, ValueLike},
     string_ref::StringRef,
-    utility::into_raw_array,
+    utility::capture_diagnostics,
+    Error,
 };
 use mlir_sys::{
-    mlirNamedAttributeGet, mlirOperationCreate, mlirOperationStateAddAttributes,
-    mlirOperationStateAddOperands, mlirOperationStateAddOwnedRegions, mlirOperationStateAddResults,
+    mlirIdentifierGet, mlirIntegerTypeGet, mlirLocationGetContext, mlirNamedAttributeGet,
+    mlirOperationCreate, mlirOperationStateAddAttributes, mlirOperationStateAddOperands,
+    mlirOperationStateAddOwnedRegions, mlirOperationStateAddResults,
     mlirOperationStateAddSuccessors, mlirOperationStateEnableResultTypeInference,
-    mlirOperationStateGet, MlirOperationState,
+    mlirOperationStateGet, mlirVectorTypeGet, MlirOperationState,
 };
 use std::marker::PhantomData;
 
 use super::Operation;
 
 /// An operation builder.
+///
+/// Attributes with the same name cannot be added more than once. Use
+/// [`Builder::set_attribute`] to overwrite them explicitly.
 pub struct Builder<'c> {
     raw: MlirOperationState,
+    name: String,
+    attributes: Vec<(Identifier<'c>, Attribute<'c>)>,
+    duplicate_attribute: Option<Identifier<'c>>,
     _context: PhantomData<&'c Context>,
 }
 
 impl<'c> Builder<'c> {
     /// Creates an operation builder.
     pub fn new(name: &str, location: Location<'c>) -> Self {
+        let name = String::from(name);
+
         Self {
+            // An operation state keeps a reference to a name until an
+            // operation is created. Buffers of strings do not move even if
+            // the builder does.
             raw: unsafe {
-                mlirOperationStateGet(StringRef::from(name).to_raw(), location.to_raw())
+                mlirOperationStateGet(StringRef::from(name.as_str()).to_raw(), location.to_raw())
             },
+            name,
+            attributes: vec![],
+            duplicate_attribute: None,
             _context: Default::default(),
         }
     }
 
+    /// Adds a result.
+    pub fn add_result(self, result: Type<'c>) -> Self {
+        self.add_results(&[result])
+    }
+
     /// Adds results.
     pub fn add_results(mut self, results: &[Type<'c>]) -> Self {
         unsafe {
             mlirOperationStateAddResults(
                 &mut self.raw,
                 results.len() as isize,
-                into_raw_array(results.iter().map(|r#type| r#type.to_raw()).collect()),
+                results
+                    .iter()
+                    .map(|r#type| r#type.to_raw())
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
             )
         }
 
         self
     }
 
+    /// Adds an operand.
+    pub fn add_operand(self, operand: Value) -> Self {
+        self.add_operands(&[operand])
+    }
+
     /// Adds operands.
     pub fn add_operands(mut self, operands: &[Value]) -> Self {
         unsafe {
             mlirOperationStateAddOperands(
                 &mut self.raw,
                 operands.len() as isize,
-                into_raw_array(operands.iter().map(|value| value.to_raw()).collect()),
+                operands
+                    .iter()
+                    .map(|value| value.to_raw())
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
             )
         }
 
         self
     }
 
+    /// Adds operands in segments with an `operand_segment_sizes` attribute.
+    ///
+    /// Operands of an operation with variadic operands should not be added
+    /// otherwise, or sizes in the attribute do not match them.
+    pub fn add_operand_segments(self, segments: &[&[Value]]) -> Self {
+        let sizes = segments
+            .iter()
+            .map(|operands| operands.len() as i32)
+            .collect::<Vec<_>>();
+
+        segments
+            .iter()
+            .fold(self, |builder, operands| builder.add_operands(operands))
+            .add_segment_sizes("operand_segment_sizes", &sizes)
+    }
+
+    /// Adds an attribute of segment sizes, e.g. `case_operand_segments` of
+    /// `cf.switch` operations.
+    pub fn add_segment_sizes(self, name: &str, sizes: &[i32]) -> Self {
+        let (identifier, attribute) = unsafe {
+            let context = mlirLocationGetContext(self.raw.location);
+
+            (
+                Identifier::from_raw(mlirIdentifierGet(context, StringRef::from(name).to_raw())),
+                Attribute::dense_elements_i32(
+                    Type::from_raw(mlirVectorTypeGet(
+                        1,
+                        &(sizes.len() as i64),
+                        mlirIntegerTypeGet(context, 32),
+                    )),
+                    sizes,
+                ),
+            )
+        };
+
+        self.add_attributes(&[(identifier, attribute)])
+    }
+
+    /// Adds a region.
+    pub fn add_region(self, region: Region) -> Self {
+        self.add_regions(vec![region])
+    }
+
     /// Adds regions.
     pub fn add_regions(mut self, regions: Vec<Region>) -> Self {
         unsafe {
             mlirOperationStateAddOwnedRegions(
                 &mut self.raw,
                 regions.len() as isize,
-                into_raw_array(
-                    regions
-                        .into_iter()
-                        .map(|region| region.into_raw())
-                        .collect(),
-                ),
+                regions
+                    .into_iter()
+                    .map(|region| region.into_raw())
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
             )
         }
 
         self
     }
 
+    /// Adds a successor block.
+    pub fn add_successor(self, successor: BlockRef) -> Self {
+        self.add_successors(&[successor])
+    }
+
     /// Adds successor blocks.
     // TODO Fix this to ensure blocks are alive while they are referenced by the
     // operation.
@@ -83,7 +164,11 @@ impl<'c> Builder<'c> {
             mlirOperationStateAddSuccessors(
                 &mut self.raw,
                 successors.len() as isize,
-                into_raw_array(successors.iter().map(|block| block.to_raw()).collect()),
+                successors
+                    .iter()
+                    .map(|block| block.to_raw())
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
             )
         }
 
@@ -91,20 +176,45 @@ impl<'c> Builder<'c> {
     }
 
     /// Adds attributes.
-    pub fn add_attributes(mut self, attributes: &[(Identifier, Attribute<'c>)]) -> Self {
-        unsafe {
-            mlirOperationStateAddAttributes(
-                &mut self.raw,
-                attributes.len() as isize,
-                into_raw_array(
-                    attributes
-                        .iter()
-                        .map(|(identifier, attribute)| {
-                            mlirNamedAttributeGet(identifier.to_raw(), attribute.to_raw())
-                        })
-                        .collect(),
-                ),
-            )
+    pub fn add_attributes(mut self, attributes: &[(Identifier<'c>, Attribute<'c>)]) -> Self {
+        for &(identifier, attribute) in attributes {
+            if self.duplicate_attribute.is_none()
+                && self
+                    .attributes
+                    .iter()
+                    .any(|(other, _)| *other == identifier)
+            {
+                self.duplicate_attribute = Some(identifier);
+            }
+
+            self.attributes.push((identifier, attribute));
+        }
+
+        self
+    }
+
+    /// Adds discardable attributes.
+    ///
+    /// Names of discardable attributes need to be prefixed with dialect
+    /// namespaces.
+    // TODO Store discardable attributes separately.
+    pub fn add_discardable_attributes(
+        self,
+        attributes: &[(Identifier<'c>, Attribute<'c>)],
+    ) -> Self {
+        self.add_attributes(attributes)
+    }
+
+    /// Sets an attribute overwriting an existing one with the same name.
+    pub fn set_attribute(mut self, identifier: Identifier<'c>, attribute: Attribute<'c>) -> Self {
+        if let Some((_, existing)) = self
+            .attributes
+            .iter_mut()
+            .find(|(other, _)| *other == identifier)
+        {
+            *existing = attribute;
+        } else {
+            self.attributes.push((identifier, attribute));
         }
 
         self
@@ -118,8 +228,55 @@ impl<'c> Builder<'c> {
     }
 
     /// Builds an operation.
-    pub fn build(mut self) -> Operation<'c> {
-        unsafe { Operation::from_raw(mlirOperationCreate(&mut self.raw)) }
+    ///
+    /// It fails if attributes with the same name are added or result types
+    /// cannot be inferred.
+    pub fn build(mut self) -> Result<Operation<'c>, Error> {
+        if let Some(identifier) = self.duplicate_attribute {
+            return Err(Error::DuplicateAttribute(
+                identifier.as_string_ref().as_str().unwrap().into(),
+            ));
+        }
+
+        unsafe {
+            mlirOperationStateAddAttributes(
+                &mut self.raw,
+                self.attributes.len() as isize,
+                self.attributes
+                    .iter()
+                    .map(|(identifier, attribute)| {
+                        mlirNamedAttributeGet(identifier.to_raw(), attribute.to_raw())
+                    })
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
+            );
+
+            Operation::from_option_raw(mlirOperationCreate(&mut self.raw))
+                .ok_or(Error::BuildOperation(self.name))
+        }
+    }
+
+    /// Builds and verifies an operation.
+    ///
+    /// In addition to [`Builder::build`], it fails if the operation is not
+    /// registered in a context or its verifier fails, e.g. on wrong numbers of
+    /// operands, results, or regions. Diagnostics reported by verifiers are
+    /// attached to an error. Operations whose verifiers require parent
+    /// operations should be built with [`Builder::build`] and verified later.
+    // TODO Check numbers of operands, results, and regions before building.
+    pub fn build_checked(self) -> Result<Operation<'c>, Error> {
+        let context = unsafe { ContextRef::from_raw(mlirLocationGetContext(self.raw.location)) };
+
+        context.check_operation_name(&self.name)?;
+
+        let operation = self.build()?;
+        let (verified, diagnostics) = capture_diagnostics(context, || operation.verify());
+
+        if verified {
+            Ok(operation)
+        } else {
+            Err(Error::VerifyOperation(operation.to_string(), diagnostics))
+        }
     }
 }
 
@@ -130,7 +287,9 @@ mod tests {
 
     #[test]
     fn new() {
-        Builder::new("foo", Location::unknown(&Context::new())).build();
+        Builder::new("foo", Location::unknown(&Context::new()))
+            .build()
+            .unwrap();
     }
 
     #[test]
@@ -139,7 +298,118 @@ mod tests {
 
         Builder::new("foo", Location::unknown(&context))
             .add_results(&[Type::parse(&context, "i1").unwrap()])
-            .build();
+            .build()
+            .unwrap();
+    }
+
+    #[test]
+    fn add_result() {
+        let context = Context::new();
+
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_result(Type::index(&context))
+            .add_result(Type::integer(&context, 1))
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.result_count(), 2);
+    }
+
+    #[test]
+    fn add_operand() {
+        let context = Context::new();
+        let block = Block::new(&[
+            (Type::index(&context), Location::unknown(&context)),
+            (Type::index(&context), Location::unknown(&context)),
+        ]);
+        let mut builder = Builder::new("foo", Location::unknown(&context));
+
+        for index in 0..block.argument_count() {
+            builder = builder.add_operand(block.argument(index).unwrap().into());
+        }
+
+        let operation = builder.build().unwrap();
+
+        assert_eq!(operation.operand_count(), 2);
+        assert_eq!(
+            operation.operand(1).unwrap(),
+            block.argument(1).unwrap().into()
+        );
+    }
+
+    #[test]
+    fn add_operand_segments() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[
+            (Type::index(&context), location),
+            (Type::index(&context), location),
+        ]);
+        let argument = |index| block.argument(index).unwrap().into();
+
+        let operation = Builder::new("foo", location)
+            .add_operand_segments(&[&[argument(0)], &[], &[argument(0), argument(1)]])
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.operand_count(), 3);
+        assert_eq!(operation.operand(2).unwrap(), argument(1));
+        assert_eq!(
+            operation.attribute("operand_segment_sizes").unwrap(),
+            Attribute::parse(&context, "dense<[1, 0, 2]> : vector<3xi32>").unwrap()
+        );
+    }
+
+    #[test]
+    fn add_segment_sizes() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+
+        let operation = Builder::new("foo", location)
+            .add_segment_sizes("bar", &[2, 1])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            operation.attribute("bar").unwrap(),
+            Attribute::parse(&context, "dense<[2, 1]> : vector<2xi32>").unwrap()
+        );
+    }
+
+    #[test]
+    fn add_operand_segments_twice() {
+        let context = Context::new();
+
+        assert_eq!(
+            Builder::new("foo", Location::unknown(&context))
+                .add_operand_segments(&[])
+                .add_operand_segments(&[])
+                .build()
+                .unwrap_err(),
+            Error::DuplicateAttribute("operand_segment_sizes".into())
+        );
+    }
+
+    #[test]
+    fn add_region() {
+        let context = Context::new();
+
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_region(Region::new())
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.region_count(), 1);
+    }
+
+    #[test]
+    fn add_successor() {
+        let context = Context::new();
+
+        Builder::new("foo", Location::unknown(&context))
+            .add_successor(*Block::new(&[]))
+            .build()
+            .unwrap();
     }
 
     #[test]
@@ -148,7 +418,8 @@ mod tests {
 
         Builder::new("foo", Location::unknown(&context))
             .add_regions(vec![Region::new()])
-            .build();
+            .build()
+            .unwrap();
     }
 
     #[test]
@@ -157,7 +428,8 @@ mod tests {
 
         Builder::new("foo", Location::unknown(&context))
             .add_successors(&[*Block::new(&[])])
-            .build();
+            .build()
+            .unwrap();
     }
 
     #[test]
@@ -169,7 +441,56 @@ mod tests {
                 Identifier::new(&context, "foo"),
                 Attribute::parse(&context, "unit").unwrap(),
             )])
-            .build();
+            .build()
+            .unwrap();
+    }
+
+    #[test]
+    fn add_duplicate_attributes() {
+        let context = Context::new();
+        let identifier = Identifier::new(&context, "foo");
+        let attribute = Attribute::parse(&context, "unit").unwrap();
+
+        assert_eq!(
+            Builder::new("foo", Location::unknown(&context))
+                .add_attributes(&[(identifier, attribute)])
+                .add_attributes(&[(identifier, attribute)])
+                .build()
+                .unwrap_err(),
+            Error::DuplicateAttribute("foo".into())
+        );
+    }
+
+    #[test]
+    fn add_discardable_attributes() {
+        let context = Context::new();
+
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_discardable_attributes(&[(
+                Identifier::new(&context, "bar.baz"),
+                Attribute::parse(&context, "unit").unwrap(),
+            )])
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.to_string(), "\"foo\"() {bar.baz} : () -> ()\n");
+    }
+
+    #[test]
+    fn set_attribute() {
+        let context = Context::new();
+        let identifier = Identifier::new(&context, "foo");
+
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .add_attributes(&[(identifier, Attribute::parse(&context, "0").unwrap())])
+            .set_attribute(identifier, Attribute::parse(&context, "1").unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            operation.to_string(),
+            "\"foo\"() {foo = 1 : i64} : () -> ()\n"
+        );
     }
 
     #[test]
@@ -191,10 +512,71 @@ mod tests {
                 .add_operands(&[argument, argument])
                 .enable_result_type_inference()
                 .build()
+                .unwrap()
                 .result(0)
                 .unwrap()
                 .r#type(),
             r#type,
         );
     }
+
+    #[test]
+    fn build_checked() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::index(&context);
+        let block = Block::new(&[(r#type, location)]);
+        let argument = block.argument(0).unwrap().into();
+
+        let operation = Builder::new("arith.addi", location)
+            .add_operands(&[argument, argument])
+            .add_results(&[r#type])
+            .build_checked()
+            .unwrap();
+
+        assert_eq!(operation.result(0).unwrap().r#type(), r#type);
+    }
+
+    #[test]
+    fn build_checked_unregistered_operation() {
+        let context = Context::new();
+
+        assert_eq!(
+            Builder::new("arith.foo", Location::unknown(&context))
+                .build_checked()
+                .unwrap_err(),
+            Error::OperationNotRegistered("arith.foo".into())
+        );
+    }
+
+    #[test]
+    fn build_checked_invalid_operation() {
+        let context = Context::new();
+
+        let error = Builder::new("arith.addi", Location::unknown(&context))
+            .add_results(&[Type::index(&context)])
+            .build_checked()
+            .unwrap_err();
+
+        assert!(matches!(error, Error::VerifyOperation(_, _)));
+        assert!(!error.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn fail_result_type_inference() {
+        let registry = dialect::Registry::new();
+        register_all_dialects(&registry);
+
+        let context = Context::new();
+        context.append_dialect_registry(&registry);
+        context.load_all_available_dialects();
+
+        assert_eq!(
+            Builder::new("arith.addi", Location::unknown(&context))
+                .enable_result_type_inference()
+                .build()
+                .unwrap_err(),
+            Error::BuildOperation("arith.addi".into())
+        );
+    }
 }
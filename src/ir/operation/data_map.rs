@@ -0,0 +1,182 @@
+use super::OperationRef;
+use crate::ir::generation::Generation;
+use std::collections::HashMap;
+
+/// A side table of data attached to operations.
+///
+/// Entries of operations destroyed through owned IR objects are invalidated
+/// and not visible anymore even if other operations are allocated at the same
+/// addresses later.
+#[derive(Debug)]
+pub struct DataMap<T> {
+    entries: HashMap<usize, (Generation, T)>,
+}
+
+impl<T> DataMap<T> {
+    /// Creates a data map.
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Gets data of an operation.
+    pub fn get(&self, operation: OperationRef) -> Option<&T> {
+        self.entries
+            .get(&Self::key(operation))
+            .filter(|(generation, _)| Self::is_valid(operation, *generation))
+            .map(|(_, data)| data)
+    }
+
+    /// Gets mutable data of an operation.
+    pub fn get_mut(&mut self, operation: OperationRef) -> Option<&mut T> {
+        self.entries
+            .get_mut(&Self::key(operation))
+            .filter(|(generation, _)| Self::is_valid(operation, *generation))
+            .map(|(_, data)| data)
+    }
+
+    /// Returns `true` if an operation has data.
+    pub fn contains(&self, operation: OperationRef) -> bool {
+        self.get(operation).is_some()
+    }
+
+    /// Inserts data of an operation.
+    ///
+    /// It returns existing data of the operation if any.
+    pub fn insert(&mut self, operation: OperationRef, data: T) -> Option<T> {
+        self.entries
+            .insert(
+                Self::key(operation),
+                (Generation::new(operation.raw.ptr), data),
+            )
+            .filter(|(generation, _)| Self::is_valid(operation, *generation))
+            .map(|(_, data)| data)
+    }
+
+    /// Removes data of an operation.
+    pub fn remove(&mut self, operation: OperationRef) -> Option<T> {
+        let key = Self::key(operation);
+
+        if self.contains(operation) {
+            self.entries.remove(&key).map(|(_, data)| data)
+        } else {
+            None
+        }
+    }
+
+    /// Gets a number of entries of live operations.
+    pub fn len(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|(&key, (generation, _))| generation.is_alive(key as *const _))
+            .count()
+    }
+
+    /// Returns `true` if no live operation has data.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes entries of destroyed operations.
+    pub fn prune(&mut self) {
+        self.entries
+            .retain(|&key, (generation, _)| generation.is_alive(key as *const _));
+    }
+
+    fn key(operation: OperationRef) -> usize {
+        // Check if the operation is alive.
+        unsafe { operation.to_raw() }.ptr as usize
+    }
+
+    fn is_valid(operation: OperationRef, generation: Generation) -> bool {
+        generation.is_alive(operation.raw.ptr)
+    }
+}
+
+impl<T> Default for DataMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{operation::Builder, Block, Location},
+        Context,
+    };
+
+    #[test]
+    fn insert() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .build()
+            .unwrap();
+        let mut map = DataMap::new();
+
+        assert_eq!(map.insert(*operation, 1), None);
+        assert_eq!(map.insert(*operation, 2), Some(1));
+        assert_eq!(map.get(*operation), Some(&2));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn get_mut() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .build()
+            .unwrap();
+        let mut map = DataMap::new();
+
+        map.insert(*operation, 1);
+        *map.get_mut(*operation).unwrap() += 1;
+
+        assert_eq!(map.get(*operation), Some(&2));
+    }
+
+    #[test]
+    fn remove() {
+        let context = Context::new();
+        let operation = Builder::new("foo", Location::unknown(&context))
+            .build()
+            .unwrap();
+        let mut map = DataMap::new();
+
+        map.insert(*operation, 1);
+
+        assert_eq!(map.remove(*operation), Some(1));
+        assert_eq!(map.remove(*operation), None);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn invalidate_erased_operation() {
+        let context = Context::new();
+        let block = Block::new(&[]);
+        let operation = block.append_operation(
+            Builder::new("foo", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
+        let mut map = DataMap::new();
+
+        map.insert(operation, 1);
+        unsafe { operation.erase() };
+
+        assert!(map.is_empty());
+
+        let other = block.append_operation(
+            Builder::new("bar", Location::unknown(&context))
+                .build()
+                .unwrap(),
+        );
+
+        assert!(!map.contains(other));
+
+        map.prune();
+
+        assert!(map.entries.is_empty());
+    }
+}
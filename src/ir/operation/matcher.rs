@@ -0,0 +1,255 @@
+//! Operation matchers.
+
+use super::{OperationRef, ResultValue};
+
+/// An operation matcher.
+pub trait Matcher {
+    /// Returns `true` if an operation matches.
+    fn matches(&self, operation: OperationRef) -> bool;
+
+    /// Creates a matcher which matches operations matched by both matchers.
+    fn and<M: Matcher>(self, other: M) -> And<Self, M>
+    where
+        Self: Sized,
+    {
+        And(self, other)
+    }
+
+    /// Creates a matcher which matches operations matched by either matcher.
+    fn or<M: Matcher>(self, other: M) -> Or<Self, M>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+
+    /// Creates a matcher which matches operations not matched by a matcher.
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self)
+    }
+}
+
+impl<F: Fn(OperationRef) -> bool> Matcher for F {
+    fn matches(&self, operation: OperationRef) -> bool {
+        self(operation)
+    }
+}
+
+/// A matcher created by [`Matcher::and`].
+#[derive(Clone, Copy, Debug)]
+pub struct And<A, B>(A, B);
+
+impl<A: Matcher, B: Matcher> Matcher for And<A, B> {
+    fn matches(&self, operation: OperationRef) -> bool {
+        self.0.matches(operation) && self.1.matches(operation)
+    }
+}
+
+/// A matcher created by [`Matcher::or`].
+#[derive(Clone, Copy, Debug)]
+pub struct Or<A, B>(A, B);
+
+impl<A: Matcher, B: Matcher> Matcher for Or<A, B> {
+    fn matches(&self, operation: OperationRef) -> bool {
+        self.0.matches(operation) || self.1.matches(operation)
+    }
+}
+
+/// A matcher created by [`Matcher::not`].
+#[derive(Clone, Copy, Debug)]
+pub struct Not<A>(A);
+
+impl<A: Matcher> Matcher for Not<A> {
+    fn matches(&self, operation: OperationRef) -> bool {
+        !self.0.matches(operation)
+    }
+}
+
+/// Creates a matcher of any operations.
+pub fn any() -> impl Matcher {
+    |_: OperationRef| true
+}
+
+/// Creates a matcher of operations of a name.
+pub fn name(name: &str) -> impl Matcher + '_ {
+    move |operation: OperationRef| operation.is(name)
+}
+
+/// Creates a matcher of constant operations.
+///
+/// Constant operations are ones with no operand, a single result, and a
+/// `value` attribute, such as `arith.constant` and `llvm.mlir.constant`.
+pub fn constant() -> impl Matcher {
+    |operation: OperationRef| {
+        operation.operand_count() == 0
+            && operation.result_count() == 1
+            && operation.attribute("value").is_some()
+    }
+}
+
+/// Creates a matcher of operations whose operand at a position is defined by
+/// an operation matched by a matcher.
+pub fn operand(position: usize, matcher: impl Matcher) -> impl Matcher {
+    move |operation: OperationRef| matches_operand(operation, position, &matcher)
+}
+
+/// Creates a matcher of operations whose operands are defined by operations
+/// matched by matchers respectively.
+pub fn operands<const N: usize>(matchers: [&dyn Matcher; N]) -> impl Matcher + '_ {
+    move |operation: OperationRef| {
+        operation.operand_count() == N
+            && matchers
+                .iter()
+                .enumerate()
+                .all(|(position, matcher)| matches_operand(operation, position, *matcher))
+    }
+}
+
+fn matches_operand(operation: OperationRef, position: usize, matcher: &dyn Matcher) -> bool {
+    operation
+        .operand(position)
+        .ok()
+        .and_then(|value| ResultValue::try_from(value).ok())
+        .map(|result| matcher.matches(result.owner()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::Context,
+        ir::{
+            walk::{WalkOrder, WalkResult},
+            Module,
+        },
+    };
+
+    fn count(module: &Module, matcher: impl Matcher) -> usize {
+        let mut count = 0;
+
+        module
+            .as_operation()
+            .walk(WalkOrder::PreOrder, |operation| {
+                if matcher.matches(operation) {
+                    count += 1;
+                }
+
+                WalkResult::Advance
+            });
+
+        count
+    }
+
+    #[test]
+    fn name() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0 : i32) -> i32 {
+                %zero = arith.constant 0 : i32
+                %res = arith.addi %arg0, %zero : i32
+                %double = arith.addi %res, %res : i32
+                return %double : i32
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(count(&module, super::name("arith.addi")), 2);
+    }
+
+    #[test]
+    fn constant() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0 : i32) -> i32 {
+                %zero = arith.constant 0 : i32
+                %res = arith.addi %arg0, %zero : i32
+                %double = arith.addi %res, %res : i32
+                return %double : i32
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(count(&module, super::constant()), 1);
+    }
+
+    #[test]
+    fn operand() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0 : i32) -> i32 {
+                %zero = arith.constant 0 : i32
+                %res = arith.addi %arg0, %zero : i32
+                %double = arith.addi %res, %res : i32
+                return %double : i32
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            count(
+                &module,
+                super::name("arith.addi").and(super::operand(1, super::constant()))
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn operands() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0 : i32) -> i32 {
+                %zero = arith.constant 0 : i32
+                %res = arith.addi %arg0, %zero : i32
+                %double = arith.addi %res, %res : i32
+                return %double : i32
+            }
+            "#,
+        )
+        .unwrap();
+        let addition = super::name("arith.addi");
+
+        assert_eq!(count(&module, super::operands([&addition, &addition])), 1);
+    }
+
+    #[test]
+    fn combinators() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0 : i32) -> i32 {
+                %zero = arith.constant 0 : i32
+                %res = arith.addi %arg0, %zero : i32
+                %double = arith.addi %res, %res : i32
+                return %double : i32
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            count(&module, super::name("arith.addi").or(super::constant())),
+            3
+        );
+        assert_eq!(
+            count(&module, super::name("arith.addi").not().and(super::any())),
+            4
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use mlir_sys::{
+    mlirOpPrintingFlagsCreate, mlirOpPrintingFlagsDestroy,
+    mlirOpPrintingFlagsElideLargeElementsAttrs, mlirOpPrintingFlagsEnableDebugInfo,
+    mlirOpPrintingFlagsPrintGenericOpForm, mlirOpPrintingFlagsUseLocalScope, MlirOpPrintingFlags,
+};
+
+/// Operation printing flags.
+#[derive(Debug)]
+pub struct PrintingFlags {
+    raw: MlirOpPrintingFlags,
+    large_attribute_limit: Option<usize>,
+}
+
+impl PrintingFlags {
+    /// Creates operation printing flags.
+    pub fn new() -> Self {
+        Self {
+            raw: unsafe { mlirOpPrintingFlagsCreate() },
+            large_attribute_limit: None,
+        }
+    }
+
+    /// Elides elements attributes with more elements than a limit.
+    pub fn elide_large_elements_attributes(self, limit: usize) -> Self {
+        unsafe { mlirOpPrintingFlagsElideLargeElementsAttrs(self.raw, limit as isize) }
+
+        self
+    }
+
+    /// Elides attributes whose printed forms are larger than a limit in bytes.
+    ///
+    /// Elided attributes are replaced with string attributes of placeholders
+    /// with their sizes. Operations are printed in generic form because their
+    /// custom printers might expect the original attributes.
+    pub fn elide_large_attributes(mut self, limit: usize) -> Self {
+        unsafe { mlirOpPrintingFlagsPrintGenericOpForm(self.raw) }
+
+        self.large_attribute_limit = Some(limit);
+
+        self
+    }
+
+    /// Enables debug information.
+    pub fn enable_debug_info(self, enabled: bool, pretty_form: bool) -> Self {
+        unsafe { mlirOpPrintingFlagsEnableDebugInfo(self.raw, enabled, pretty_form) }
+
+        self
+    }
+
+    /// Prints operations in generic form.
+    pub fn print_generic_operation_form(self) -> Self {
+        unsafe { mlirOpPrintingFlagsPrintGenericOpForm(self.raw) }
+
+        self
+    }
+
+    /// Uses local scope to print operations.
+    pub fn use_local_scope(self) -> Self {
+        unsafe { mlirOpPrintingFlagsUseLocalScope(self.raw) }
+
+        self
+    }
+
+    pub(crate) fn large_attribute_limit(&self) -> Option<usize> {
+        self.large_attribute_limit
+    }
+
+    pub(crate) unsafe fn to_raw(&self) -> MlirOpPrintingFlags {
+        self.raw
+    }
+}
+
+impl Drop for PrintingFlags {
+    fn drop(&mut self) {
+        unsafe { mlirOpPrintingFlagsDestroy(self.raw) }
+    }
+}
+
+impl Default for PrintingFlags {
+    fn default() -> Self {
+        Self::new()
+    }
+}
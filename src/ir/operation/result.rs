@@ -1,6 +1,6 @@
 use super::Value;
 use crate::{
-    ir::{OperationRef, ValueLike},
+    ir::{Location, OperationRef, ValueLike},
     Error,
 };
 use mlir_sys::{mlirOpResultGetOwner, mlirOpResultGetResultNumber, MlirValue};
@@ -13,14 +13,21 @@ pub struct ResultValue<'a> {
 }
 
 impl<'a> ResultValue<'a> {
+    /// Gets a result number.
     pub fn result_number(&self) -> usize {
         unsafe { mlirOpResultGetResultNumber(self.value.to_raw()) as usize }
     }
 
-    pub fn owner(&self) -> OperationRef {
+    /// Gets an owner operation.
+    pub fn owner(&self) -> OperationRef<'a> {
         unsafe { OperationRef::from_raw(mlirOpResultGetOwner(self.value.to_raw())) }
     }
 
+    /// Gets a location, which is the one of an owner operation.
+    pub fn location(&self) -> Location<'a> {
+        self.owner().location()
+    }
+
     pub(crate) unsafe fn from_raw(value: MlirValue) -> Self {
         Self {
             value: Value::from_raw(value),
@@ -28,7 +35,7 @@ impl<'a> ResultValue<'a> {
     }
 }
 
-impl<'a> ValueLike for ResultValue<'a> {
+impl<'a> ValueLike<'a> for ResultValue<'a> {
     fn to_raw(&self) -> MlirValue {
         self.value.to_raw()
     }
@@ -65,7 +72,8 @@ mod tests {
         let r#type = Type::parse(&context, "index").unwrap();
         let operation = operation::Builder::new("foo", Location::unknown(&context))
             .add_results(&[r#type])
-            .build();
+            .build()
+            .unwrap();
 
         assert_eq!(operation.result(0).unwrap().result_number(), 0);
     }
@@ -78,4 +86,27 @@ mod tests {
 
         assert_eq!(block.argument(0).unwrap().owner(), *block);
     }
+
+    #[test]
+    fn result_owner() {
+        let context = Context::new();
+        let operation = operation::Builder::new("foo", Location::unknown(&context))
+            .add_results(&[Type::index(&context)])
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.result(0).unwrap().owner(), *operation);
+    }
+
+    #[test]
+    fn location() {
+        let context = Context::new();
+        let location = Location::new(&context, "foo", 1, 2);
+        let operation = operation::Builder::new("foo", location)
+            .add_results(&[Type::index(&context)])
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.result(0).unwrap().location(), location);
+    }
 }
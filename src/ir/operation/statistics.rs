@@ -0,0 +1,30 @@
+/// Statistics of an operation and its nested IR objects.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Statistics {
+    operation_count: usize,
+    block_count: usize,
+    region_count: usize,
+}
+
+impl Statistics {
+    /// Gets a number of operations including a root operation.
+    pub fn operation_count(&self) -> usize {
+        self.operation_count
+    }
+
+    /// Gets a number of blocks.
+    pub fn block_count(&self) -> usize {
+        self.block_count
+    }
+
+    /// Gets a number of regions.
+    pub fn region_count(&self) -> usize {
+        self.region_count
+    }
+
+    pub(crate) fn add_operation(&mut self, region_count: usize, block_count: usize) {
+        self.operation_count += 1;
+        self.region_count += region_count;
+        self.block_count += block_count;
+    }
+}
@@ -0,0 +1,89 @@
+use crate::ir::{Value, ValueLike};
+use std::collections::HashMap;
+
+/// A mapping of values used to remap operands of cloned operations.
+#[derive(Clone, Debug, Default)]
+pub struct ValueMapping<'a> {
+    values: HashMap<Value<'a>, Value<'a>>,
+}
+
+impl<'a> ValueMapping<'a> {
+    /// Creates a value mapping.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets a value which a value is mapped to.
+    pub fn get(&self, value: Value) -> Option<Value<'a>> {
+        // The lifetime of a key does not matter for lookup.
+        let value = unsafe { Value::from_raw(value.to_raw()) };
+
+        self.values.get(&value).copied()
+    }
+
+    /// Returns `true` if a value is mapped.
+    pub fn contains(&self, value: Value) -> bool {
+        self.get(value).is_some()
+    }
+
+    /// Maps a value to another value.
+    ///
+    /// It returns a value which the value was mapped to before.
+    pub fn insert(&mut self, from: Value<'a>, to: Value<'a>) -> Option<Value<'a>> {
+        self.values.insert(from, to)
+    }
+
+    /// Removes a mapping of a value.
+    pub fn remove(&mut self, value: Value<'a>) -> Option<Value<'a>> {
+        self.values.remove(&value)
+    }
+
+    /// Gets a number of mapped values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if no value is mapped.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{Block, Location, Type},
+        Context,
+    };
+
+    #[test]
+    fn insert() {
+        let context = Context::new();
+        let r#type = Type::index(&context);
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(r#type, location), (r#type, location)]);
+        let one = block.argument(0).unwrap().into();
+        let other = block.argument(1).unwrap().into();
+        let mut mapping = ValueMapping::new();
+
+        assert_eq!(mapping.insert(one, other), None);
+        assert_eq!(mapping.get(one), Some(other));
+        assert!(mapping.contains(one));
+        assert!(!mapping.contains(other));
+        assert_eq!(mapping.len(), 1);
+    }
+
+    #[test]
+    fn remove() {
+        let context = Context::new();
+        let block = Block::new(&[(Type::index(&context), Location::unknown(&context))]);
+        let value = block.argument(0).unwrap().into();
+        let mut mapping = ValueMapping::new();
+
+        mapping.insert(value, value);
+
+        assert_eq!(mapping.remove(value), Some(value));
+        assert!(mapping.is_empty());
+    }
+}
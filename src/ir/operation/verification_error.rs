@@ -0,0 +1,43 @@
+use super::OperationRef;
+use crate::diagnostic::CapturedDiagnostic;
+use std::fmt::{self, Display, Formatter};
+
+/// A verification error of an operation.
+#[derive(Clone, Debug)]
+pub struct VerificationError<'a> {
+    operation: Option<OperationRef<'a>>,
+    diagnostic: CapturedDiagnostic,
+}
+
+impl<'a> VerificationError<'a> {
+    /// Gets an operation which a diagnostic is associated with.
+    ///
+    /// It returns `None` if no operation or more than one operations have the
+    /// location of the diagnostic.
+    pub fn operation(&self) -> Option<OperationRef<'a>> {
+        self.operation
+    }
+
+    /// Gets a diagnostic.
+    pub fn diagnostic(&self) -> &CapturedDiagnostic {
+        &self.diagnostic
+    }
+
+    pub(crate) fn new(operation: Option<OperationRef<'a>>, diagnostic: CapturedDiagnostic) -> Self {
+        Self {
+            operation,
+            diagnostic,
+        }
+    }
+}
+
+impl<'a> Display for VerificationError<'a> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{}: {}",
+            self.diagnostic.location(),
+            self.diagnostic.message()
+        )
+    }
+}
@@ -0,0 +1,144 @@
+use super::Module;
+use crate::context::Context;
+use std::mem::transmute;
+
+/// A module which owns its context.
+///
+/// It can be stored in structs without borrowing a context from elsewhere.
+pub struct OwnedModule {
+    // The module must be dropped before the context.
+    module: Module<'static>,
+    context: Context,
+}
+
+impl OwnedModule {
+    /// Creates an owned module.
+    ///
+    /// A callback builds a module in a given context.
+    pub fn new<E>(
+        context: Context,
+        build: impl for<'c> FnOnce(&'c Context) -> Result<Module<'c>, E>,
+    ) -> Result<Self, E> {
+        let module = build(&context)?;
+
+        Ok(Self {
+            // The module never outlives the context because it is dropped first and
+            // only lent with lifetimes of borrows of `self`.
+            module: unsafe { transmute::<Module, Module<'static>>(module) },
+            context,
+        })
+    }
+
+    /// Gets a context.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Gets a module.
+    ///
+    /// Operations appended to the module from other contexts cause panics.
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
+    /// Modifies a module.
+    ///
+    /// A callback receives a context and a mutable module of the same lifetime.
+    /// Objects from the context can be built inside the callback but not moved
+    /// out of it.
+    pub fn with_module_mut<T>(
+        &mut self,
+        modify: impl for<'c> FnOnce(&'c Context, &mut Module<'c>) -> T,
+    ) -> T {
+        // The callback cannot move the module or objects of the lifetime out of it
+        // because it must accept any lifetime.
+        modify(&self.context, unsafe {
+            transmute::<&mut Module<'static>, &mut Module>(&mut self.module)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dialect::func, ir::Location, pass};
+
+    struct Compiler {
+        module: OwnedModule,
+    }
+
+    #[test]
+    fn new() {
+        let module = OwnedModule::new(Context::new(), |context| {
+            Module::parse(context, "func.func @foo() { return }").ok_or(())
+        })
+        .unwrap();
+
+        assert!(module.module().as_operation().verify());
+    }
+
+    #[test]
+    fn new_with_error() {
+        assert!(OwnedModule::new(Context::new(), |context| {
+            Module::parse(context, "foo").ok_or(())
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn store_in_struct() {
+        let compiler = Compiler {
+            module: OwnedModule::new(Context::new(), |context| {
+                Module::parse(context, "func.func @foo() { return }").ok_or(())
+            })
+            .unwrap(),
+        };
+
+        assert!(compiler.module.module().body().first_operation().is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "operation from another context")]
+    fn append_operation_from_other_context() {
+        let module = OwnedModule::new(Context::new(), |context| {
+            Module::parse(context, "func.func @foo() { return }").ok_or(())
+        })
+        .unwrap();
+        let context = Context::new();
+
+        module.module().append_operation(
+            func::Builder::new(&context, "bar", &[], &[], Location::unknown(&context))
+                .build(|_| Ok(()))
+                .unwrap(),
+        );
+    }
+
+    #[test]
+    fn with_module_mut() {
+        let mut module = OwnedModule::new(Context::new(), |context| {
+            Module::parse(context, "func.func @foo() { return }").ok_or(())
+        })
+        .unwrap();
+
+        module
+            .with_module_mut(|context, module| {
+                let location = Location::unknown(context);
+
+                module.append_operation(
+                    func::Builder::new(context, "bar", &[], &[], location).build(|block| {
+                        block.append_operation(func::r#return(&[], location)?);
+
+                        Ok(())
+                    })?,
+                );
+
+                pass::preset::cpu_jit(context).run(module)
+            })
+            .unwrap();
+
+        let source = module.module().as_operation().to_string();
+
+        assert!(source.contains("llvm.func @foo"));
+        assert!(source.contains("llvm.func @bar"));
+    }
+}
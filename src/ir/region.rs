@@ -1,10 +1,10 @@
-use super::{Block, BlockRef};
+use super::{generation, Block, BlockRef};
 use mlir_sys::{
     mlirRegionAppendOwnedBlock, mlirRegionCreate, mlirRegionDestroy, mlirRegionEqual,
     mlirRegionGetFirstBlock, mlirRegionInsertOwnedBlockAfter, mlirRegionInsertOwnedBlockBefore,
     MlirRegion,
 };
-use std::{marker::PhantomData, mem::forget, ops::Deref};
+use std::{iter::successors, marker::PhantomData, mem::forget, ops::Deref};
 
 /// A region.
 #[derive(Debug)]
@@ -37,7 +37,21 @@ impl Default for Region {
 
 impl Drop for Region {
     fn drop(&mut self) {
-        unsafe { mlirRegionDestroy(self.raw) }
+        #[cfg(feature = "drop-diagnostics")]
+        if self.first_block().is_some() && !std::thread::panicking() {
+            eprintln!(
+                "melior: detached region dropped with {} blocks and {} operations",
+                self.blocks().count(),
+                self.blocks()
+                    .map(|block| block.operation_count())
+                    .sum::<usize>()
+            );
+        }
+
+        unsafe {
+            generation::invalidate_region(self.raw);
+            mlirRegionDestroy(self.raw);
+        }
     }
 }
 
@@ -61,6 +75,8 @@ impl Deref for Region {
 #[derive(Clone, Copy, Debug)]
 pub struct RegionRef<'a> {
     raw: MlirRegion,
+    #[cfg(debug_assertions)]
+    generation: generation::Generation,
     _region: PhantomData<&'a Region>,
 }
 
@@ -68,7 +84,7 @@ impl<'a> RegionRef<'a> {
     /// Gets the first block in a region.
     pub fn first_block(&self) -> Option<BlockRef> {
         unsafe {
-            let block = mlirRegionGetFirstBlock(self.raw);
+            let block = mlirRegionGetFirstBlock(self.to_raw());
 
             if block.ptr.is_null() {
                 None
@@ -78,12 +94,17 @@ impl<'a> RegionRef<'a> {
         }
     }
 
+    /// Gets blocks.
+    pub fn blocks(&self) -> impl Iterator<Item = BlockRef> {
+        successors(self.first_block(), |block| block.next_in_region())
+    }
+
     /// Inserts a block after another block.
     pub fn insert_block_after(&self, one: BlockRef, other: Block) -> BlockRef {
         unsafe {
             let r#ref = BlockRef::from_raw(other.to_raw());
 
-            mlirRegionInsertOwnedBlockAfter(self.raw, one.to_raw(), other.into_raw());
+            mlirRegionInsertOwnedBlockAfter(self.to_raw(), one.to_raw(), other.into_raw());
 
             r#ref
         }
@@ -94,7 +115,7 @@ impl<'a> RegionRef<'a> {
         unsafe {
             let r#ref = BlockRef::from_raw(other.to_raw());
 
-            mlirRegionInsertOwnedBlockBefore(self.raw, one.to_raw(), other.into_raw());
+            mlirRegionInsertOwnedBlockBefore(self.to_raw(), one.to_raw(), other.into_raw());
 
             r#ref
         }
@@ -105,19 +126,52 @@ impl<'a> RegionRef<'a> {
         unsafe {
             let r#ref = BlockRef::from_raw(block.to_raw());
 
-            mlirRegionAppendOwnedBlock(self.raw, block.into_raw());
+            mlirRegionAppendOwnedBlock(self.to_raw(), block.into_raw());
 
             r#ref
         }
     }
 
+    // TODO Use `mlirRegionTakeBody` instead of moving blocks one by one.
+    /// Takes blocks of a region into a new detached region.
+    ///
+    /// Blocks are moved as they are, so references to them, their arguments,
+    /// and their operations stay valid. The region is left empty.
+    pub fn take_body(&self) -> Region {
+        let region = Region::new();
+
+        while let Some(block) = self.first_block() {
+            // Blocks are only moved and never dropped.
+            region.append_block(unsafe { block.detach() }.unwrap());
+        }
+
+        region
+    }
+
+    /// Appends blocks of a detached region.
+    pub fn append_body(&self, region: Region) {
+        while let Some(block) = region.first_block() {
+            // Blocks are only moved and never dropped.
+            self.append_block(unsafe { block.detach() }.unwrap());
+        }
+    }
+
     pub(crate) unsafe fn from_raw(raw: MlirRegion) -> Self {
         Self {
             raw,
+            #[cfg(debug_assertions)]
+            generation: generation::Generation::new(raw.ptr),
             _region: Default::default(),
         }
     }
 
+    pub(crate) unsafe fn to_raw(self) -> MlirRegion {
+        #[cfg(debug_assertions)]
+        self.generation.check(self.raw.ptr, "region");
+
+        self.raw
+    }
+
     pub(crate) unsafe fn from_option_raw(raw: MlirRegion) -> Option<Self> {
         if raw.ptr.is_null() {
             None
@@ -149,6 +203,24 @@ mod tests {
         assert!(Region::new().first_block().is_none());
     }
 
+    #[test]
+    fn blocks() {
+        let region = Region::new();
+
+        let first_block = region.append_block(Block::new(&[]));
+        let second_block = region.append_block(Block::new(&[]));
+
+        assert_eq!(
+            region.blocks().collect::<Vec<_>>(),
+            vec![first_block, second_block]
+        );
+    }
+
+    #[test]
+    fn blocks_empty() {
+        assert_eq!(Region::new().blocks().next(), None);
+    }
+
     #[test]
     fn append_block() {
         let region = Region::new();
@@ -179,6 +251,42 @@ mod tests {
         assert_eq!(region.first_block(), Some(block));
     }
 
+    #[test]
+    fn take_body() {
+        let region = Region::new();
+
+        let first_block = region.append_block(Block::new(&[]));
+        let second_block = region.append_block(Block::new(&[]));
+
+        let body = region.take_body();
+
+        assert_eq!(region.first_block(), None);
+        assert_eq!(
+            body.blocks().collect::<Vec<_>>(),
+            vec![first_block, second_block]
+        );
+        assert_eq!(first_block.parent_region(), Some(*body));
+    }
+
+    #[test]
+    fn take_body_empty() {
+        assert_eq!(Region::new().take_body().first_block(), None);
+    }
+
+    #[test]
+    fn append_body() {
+        let region = Region::new();
+        let first_block = region.append_block(Block::new(&[]));
+
+        let other = Region::new();
+        other.append_block(Block::new(&[]));
+
+        region.append_body(other);
+
+        assert_eq!(region.first_block(), Some(first_block));
+        assert_eq!(region.blocks().count(), 2);
+    }
+
     #[test]
     fn equal() {
         let region = Region::new();
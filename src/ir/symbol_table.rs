@@ -0,0 +1,500 @@
+use super::{
+    generation,
+    walk::{WalkOrder, WalkResult},
+    Operation, OperationRef,
+};
+use crate::{logical_result::LogicalResult, string_ref::StringRef, Error};
+use mlir_sys::{
+    mlirArrayAttrGetElement, mlirArrayAttrGetNumElements, mlirAttributeIsAArray,
+    mlirAttributeIsADictionary, mlirAttributeIsASymbolRef, mlirDictionaryAttrGetElement,
+    mlirDictionaryAttrGetNumElements, mlirOperationGetAttribute, mlirOperationGetNumAttributes,
+    mlirStringAttrGetValue, mlirStringRefEqual, mlirSymbolRefAttrGetRootReference,
+    mlirSymbolTableCreate, mlirSymbolTableDestroy, mlirSymbolTableErase,
+    mlirSymbolTableGetSymbolAttributeName, mlirSymbolTableGetVisibilityAttributeName,
+    mlirSymbolTableInsert, mlirSymbolTableLookup, mlirSymbolTableReplaceAllSymbolUses,
+    mlirSymbolTableWalkSymbolTables, MlirAttribute, MlirOperation, MlirStringRef, MlirSymbolTable,
+};
+use std::{ffi::c_void, marker::PhantomData};
+
+/// A symbol table.
+#[derive(Debug)]
+pub struct SymbolTable<'a> {
+    raw: MlirSymbolTable,
+    _operation: PhantomData<&'a ()>,
+}
+
+impl<'a> SymbolTable<'a> {
+    /// Creates a symbol table of an operation with the `SymbolTable` trait.
+    pub fn new(operation: OperationRef<'a>) -> Result<Self, Error> {
+        let raw = unsafe { mlirSymbolTableCreate(operation.to_raw()) };
+
+        if raw.ptr.is_null() {
+            Err(Error::SymbolTableExpected(operation.to_string()))
+        } else {
+            Ok(Self {
+                raw,
+                _operation: Default::default(),
+            })
+        }
+    }
+
+    /// Gets a name of symbol attributes.
+    pub fn symbol_attribute_name() -> StringRef<'static> {
+        unsafe { StringRef::from_raw(mlirSymbolTableGetSymbolAttributeName()) }
+    }
+
+    /// Gets a name of visibility attributes.
+    pub fn visibility_attribute_name() -> StringRef<'static> {
+        unsafe { StringRef::from_raw(mlirSymbolTableGetVisibilityAttributeName()) }
+    }
+
+    /// Looks up a symbol.
+    pub fn lookup(&self, name: &str) -> Option<OperationRef<'a>> {
+        unsafe {
+            OperationRef::from_option_raw(mlirSymbolTableLookup(
+                self.raw,
+                StringRef::from(name).to_raw(),
+            ))
+        }
+    }
+
+    /// Inserts a symbol operation at the end of a symbol table operation.
+    ///
+    /// A symbol is renamed if its name conflicts with an existing one. It
+    /// returns a name of the inserted symbol.
+    pub fn insert(&self, operation: Operation) -> StringRef<'a> {
+        unsafe {
+            StringRef::from_raw(mlirStringAttrGetValue(mlirSymbolTableInsert(
+                self.raw,
+                operation.into_raw(),
+            )))
+        }
+    }
+
+    /// Erases a symbol operation from a symbol table operation and destroys
+    /// it.
+    ///
+    /// # Safety
+    ///
+    /// Values of the operation must not be used afterwards and its results
+    /// must have no uses. Replace them with [`OperationRef::replace_uses`]
+    /// first.
+    pub unsafe fn erase(&self, operation: OperationRef) {
+        unsafe {
+            let operation = operation.to_raw();
+
+            generation::invalidate_operation(operation);
+            mlirSymbolTableErase(self.raw, operation);
+        }
+    }
+
+    /// Replaces all uses of a symbol with another in nested operations of an
+    /// operation.
+    ///
+    /// Symbol definitions are not renamed.
+    pub fn replace_all_symbol_uses(
+        old_symbol: &str,
+        new_symbol: &str,
+        from: OperationRef,
+    ) -> Result<(), Error> {
+        let result = LogicalResult::from_raw(unsafe {
+            mlirSymbolTableReplaceAllSymbolUses(
+                StringRef::from(old_symbol).to_raw(),
+                StringRef::from(new_symbol).to_raw(),
+                from.to_raw(),
+            )
+        });
+
+        if result.is_success() {
+            Ok(())
+        } else {
+            Err(Error::ReplaceAllSymbolUses(old_symbol.into()))
+        }
+    }
+
+    /// Walks symbol table operations nested in and including an operation.
+    ///
+    /// A callback receives a symbol table operation and whether all uses of
+    /// its symbols are visible. Nested symbol tables are visited before their
+    /// parents.
+    pub fn walk_symbol_tables(
+        operation: OperationRef<'a>,
+        all_symbol_uses_visible: bool,
+        mut callback: impl FnMut(OperationRef<'a>, bool),
+    ) {
+        let mut callback: &mut dyn FnMut(MlirOperation, bool) = &mut |operation, visible| {
+            callback(unsafe { OperationRef::from_raw(operation) }, visible)
+        };
+
+        unsafe {
+            mlirSymbolTableWalkSymbolTables(
+                operation.to_raw(),
+                all_symbol_uses_visible,
+                Some(walk_callback),
+                &mut callback as *mut _ as *mut c_void,
+            )
+        }
+    }
+
+    /// Gets operations nested in a scope operation which use a symbol.
+    ///
+    /// An operation uses a symbol if any of its attributes refers to the
+    /// symbol as a root reference.
+    pub fn symbol_uses(
+        symbol: &str,
+        scope: OperationRef<'a>,
+    ) -> impl Iterator<Item = OperationRef<'a>> {
+        let symbol = StringRef::from(symbol);
+        let mut operations = vec![];
+
+        scope.walk(WalkOrder::PreOrder, |operation| {
+            if operation != scope && unsafe { uses_symbol(operation.to_raw(), symbol.to_raw()) } {
+                operations.push(operation);
+            }
+
+            WalkResult::Advance
+        });
+
+        operations.into_iter()
+    }
+}
+
+unsafe extern "C" fn walk_callback(operation: MlirOperation, visible: bool, data: *mut c_void) {
+    let callback = &mut *(data as *mut &mut dyn FnMut(MlirOperation, bool));
+
+    callback(operation, visible)
+}
+
+unsafe fn uses_symbol(operation: MlirOperation, symbol: MlirStringRef) -> bool {
+    (0..mlirOperationGetNumAttributes(operation)).any(|index| {
+        refers_to_symbol(
+            mlirOperationGetAttribute(operation, index).attribute,
+            symbol,
+        )
+    })
+}
+
+unsafe fn refers_to_symbol(attribute: MlirAttribute, symbol: MlirStringRef) -> bool {
+    if mlirAttributeIsASymbolRef(attribute) {
+        mlirStringRefEqual(mlirSymbolRefAttrGetRootReference(attribute), symbol)
+    } else if mlirAttributeIsAArray(attribute) {
+        (0..mlirArrayAttrGetNumElements(attribute))
+            .any(|index| refers_to_symbol(mlirArrayAttrGetElement(attribute, index), symbol))
+    } else if mlirAttributeIsADictionary(attribute) {
+        (0..mlirDictionaryAttrGetNumElements(attribute)).any(|index| {
+            refers_to_symbol(
+                mlirDictionaryAttrGetElement(attribute, index).attribute,
+                symbol,
+            )
+        })
+    } else {
+        false
+    }
+}
+
+impl<'a> Drop for SymbolTable<'a> {
+    fn drop(&mut self) {
+        unsafe { mlirSymbolTableDestroy(self.raw) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::Context,
+        ir::{operation, Identifier, Location, Module},
+    };
+
+    #[test]
+    fn new() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                return
+            }
+
+            func.func @bar() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+
+        SymbolTable::new(module.as_operation()).unwrap();
+    }
+
+    #[test]
+    fn new_error() {
+        let context = Context::new();
+        let operation = operation::Builder::new("foo", Location::unknown(&context))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            SymbolTable::new(*operation).unwrap_err(),
+            Error::SymbolTableExpected("\"foo\"() : () -> ()\n".into())
+        );
+    }
+
+    #[test]
+    fn symbol_attribute_name() {
+        assert_eq!(
+            SymbolTable::symbol_attribute_name().as_str().unwrap(),
+            "sym_name"
+        );
+    }
+
+    #[test]
+    fn visibility_attribute_name() {
+        assert_eq!(
+            SymbolTable::visibility_attribute_name().as_str().unwrap(),
+            "sym_visibility"
+        );
+    }
+
+    #[test]
+    fn lookup() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                return
+            }
+
+            func.func @bar() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+        let table = SymbolTable::new(module.as_operation()).unwrap();
+
+        assert_eq!(table.lookup("foo"), module.body().first_operation());
+    }
+
+    #[test]
+    fn lookup_none() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                return
+            }
+
+            func.func @bar() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+        let table = SymbolTable::new(module.as_operation()).unwrap();
+
+        assert_eq!(table.lookup("baz"), None);
+    }
+
+    #[test]
+    fn insert() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                return
+            }
+
+            func.func @bar() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+        let table = SymbolTable::new(module.as_operation()).unwrap();
+
+        let name = table.insert(table.lookup("foo").unwrap().to_owned());
+
+        assert_eq!(name.as_str().unwrap(), "foo_0");
+        assert!(table.lookup("foo_0").is_some());
+        assert_eq!(module.body().operation_count(), 3);
+        assert!(module.as_operation().verify());
+    }
+
+    #[test]
+    fn erase() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                return
+            }
+
+            func.func @bar() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+        let table = SymbolTable::new(module.as_operation()).unwrap();
+
+        unsafe { table.erase(table.lookup("bar").unwrap()) };
+
+        assert_eq!(table.lookup("bar"), None);
+        assert_eq!(module.body().operation_count(), 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "reference to destroyed operation")]
+    fn use_erased_operation() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                return
+            }
+
+            func.func @bar() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+        let table = SymbolTable::new(module.as_operation()).unwrap();
+        let operation = table.lookup("bar").unwrap();
+
+        unsafe { table.erase(operation) };
+
+        operation.name();
+    }
+
+    #[test]
+    fn replace_all_symbol_uses() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                return
+            }
+
+            func.func @bar() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+
+        SymbolTable::replace_all_symbol_uses("foo", "baz", module.as_operation()).unwrap();
+
+        let source = module.as_operation().to_string();
+
+        assert!(source.contains("call @baz()"));
+        assert!(source.contains("func.func @foo()"));
+    }
+
+    #[test]
+    fn walk_symbol_tables() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                return
+            }
+
+            func.func @bar() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+        let mut operations = vec![];
+
+        SymbolTable::walk_symbol_tables(module.as_operation(), true, |operation, visible| {
+            operations.push((operation, visible))
+        });
+
+        assert_eq!(operations, [(module.as_operation(), true)]);
+    }
+
+    #[test]
+    fn walk_nested_symbol_tables() {
+        let context = Context::new();
+        let module = Module::parse(&context, "module @foo {}").unwrap();
+        let mut operations = vec![];
+
+        SymbolTable::walk_symbol_tables(module.as_operation(), false, |operation, _| {
+            operations.push(operation)
+        });
+
+        assert_eq!(
+            operations,
+            [
+                module.body().first_operation().unwrap(),
+                module.as_operation()
+            ]
+        );
+    }
+
+    #[test]
+    fn symbol_uses() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                return
+            }
+
+            func.func @bar() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+
+        let operations = SymbolTable::symbol_uses("foo", module.as_operation()).collect::<Vec<_>>();
+
+        assert_eq!(operations.len(), 1);
+        assert_eq!(operations[0].name(), Identifier::new(&context, "func.call"));
+    }
+
+    #[test]
+    fn symbol_uses_none() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                return
+            }
+
+            func.func @bar() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            SymbolTable::symbol_uses("bar", module.as_operation()).count(),
+            0
+        );
+    }
+}
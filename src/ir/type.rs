@@ -2,12 +2,18 @@
 
 mod function;
 pub mod id;
+mod integer;
+mod mem_ref;
+mod shaped;
 mod tuple;
 mod type_like;
 
-pub use self::{function::Function, id::Id, tuple::Tuple, type_like::TypeLike};
+pub use self::{
+    function::Function, id::Id, integer::Integer, mem_ref::MemRef, shaped::Shaped, tuple::Tuple,
+    type_like::TypeLike,
+};
 use super::Location;
-use crate::{context::Context, string_ref::StringRef, utility::print_callback};
+use crate::{context::Context, string_ref::StringRef, utility::print_callback, Error};
 use mlir_sys::{
     mlirBF16TypeGet, mlirF16TypeGet, mlirF32TypeGet, mlirF64TypeGet, mlirIndexTypeGet,
     mlirIntegerTypeGet, mlirIntegerTypeSignedGet, mlirIntegerTypeUnsignedGet, mlirNoneTypeGet,
@@ -15,13 +21,16 @@ use mlir_sys::{
     MlirType,
 };
 use std::{
-    ffi::c_void,
+    ffi::{c_void, CString},
     fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
     marker::PhantomData,
 };
 
 /// A type.
 // Types are always values but their internal storage is owned by contexts.
+//
+// TODO Add size and alignment queries.
 #[derive(Clone, Copy)]
 pub struct Type<'c> {
     raw: MlirType,
@@ -31,10 +40,12 @@ pub struct Type<'c> {
 impl<'c> Type<'c> {
     /// Parses a type.
     pub fn parse(context: &'c Context, source: &str) -> Option<Self> {
+        let source = CString::new(source).ok()?;
+
         unsafe {
             Self::from_option_raw(mlirTypeParseGet(
                 context.to_raw(),
-                StringRef::from(source).to_raw(),
+                StringRef::from(source.as_c_str()).to_raw(),
             ))
         }
     }
@@ -59,6 +70,16 @@ impl<'c> Type<'c> {
         unsafe { Self::from_raw(mlirF64TypeGet(context.to_raw())) }
     }
 
+    /// Creates a float80 type.
+    pub fn float80(context: &'c Context) -> Self {
+        Self::parse(context, "f80").unwrap()
+    }
+
+    /// Creates a float128 type.
+    pub fn float128(context: &'c Context) -> Self {
+        Self::parse(context, "f128").unwrap()
+    }
+
     /// Creates an index type.
     pub fn index(context: &'c Context) -> Self {
         unsafe { Self::from_raw(mlirIndexTypeGet(context.to_raw())) }
@@ -111,6 +132,11 @@ impl<'c> Type<'c> {
         }
     }
 
+    /// Downcasts a type into a specific one, e.g. `Integer`.
+    pub fn try_downcast<T: TryFrom<Self, Error = Error>>(self) -> Result<T, Error> {
+        T::try_from(self)
+    }
+
     pub(crate) unsafe fn from_raw(raw: MlirType) -> Self {
         Self {
             raw,
@@ -141,6 +167,13 @@ impl<'c> PartialEq for Type<'c> {
 
 impl<'c> Eq for Type<'c> {}
 
+// Types are uniqued in contexts and so are their pointers.
+impl<'c> Hash for Type<'c> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.raw.ptr.hash(hasher);
+    }
+}
+
 impl<'c> Display for Type<'c> {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         let mut data = (formatter, Ok(()));
@@ -171,6 +204,24 @@ impl<'c> From<Function<'c>> for Type<'c> {
     }
 }
 
+impl<'c> From<Integer<'c>> for Type<'c> {
+    fn from(integer: Integer<'c>) -> Self {
+        unsafe { Self::from_raw(integer.to_raw()) }
+    }
+}
+
+impl<'c> From<MemRef<'c>> for Type<'c> {
+    fn from(mem_ref: MemRef<'c>) -> Self {
+        unsafe { Self::from_raw(mem_ref.to_raw()) }
+    }
+}
+
+impl<'c> From<Shaped<'c>> for Type<'c> {
+    fn from(shaped: Shaped<'c>) -> Self {
+        unsafe { Self::from_raw(shaped.to_raw()) }
+    }
+}
+
 impl<'c> From<Tuple<'c>> for Type<'c> {
     fn from(tuple: Tuple<'c>) -> Self {
         unsafe { Self::from_raw(tuple.to_raw()) }
@@ -186,6 +237,16 @@ mod tests {
         Type::parse(&Context::new(), "f32");
     }
 
+    #[test]
+    fn float80() {
+        assert_eq!(Type::float80(&Context::new()).to_string(), "f80");
+    }
+
+    #[test]
+    fn float128() {
+        assert_eq!(Type::float128(&Context::new()).to_string(), "f128");
+    }
+
     #[test]
     fn integer() {
         let context = Context::new();
@@ -270,6 +331,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn try_downcast() {
+        let context = Context::new();
+
+        assert_eq!(
+            Type::integer(&context, 42)
+                .try_downcast::<Integer>()
+                .unwrap()
+                .width(),
+            42
+        );
+    }
+
+    #[test]
+    fn try_downcast_fail() {
+        let context = Context::new();
+
+        assert_eq!(
+            Type::index(&context).try_downcast::<Integer>().unwrap_err(),
+            Error::IntegerExpected("index".into())
+        );
+    }
+
     #[test]
     fn equal() {
         let context = Context::new();
@@ -1,5 +1,5 @@
 use super::TypeLike;
-use crate::{ir::Type, utility::into_raw_array, Context, Error};
+use crate::{ir::Type, Context, Error};
 use mlir_sys::{
     mlirFunctionTypeGet, mlirFunctionTypeGetInput, mlirFunctionTypeGetNumInputs,
     mlirFunctionTypeGetNumResults, mlirFunctionTypeGetResult, MlirType,
@@ -20,9 +20,17 @@ impl<'c> Function<'c> {
                 Type::from_raw(mlirFunctionTypeGet(
                     context.to_raw(),
                     inputs.len() as isize,
-                    into_raw_array(inputs.iter().map(|r#type| r#type.to_raw()).collect()),
+                    inputs
+                        .iter()
+                        .map(|r#type| r#type.to_raw())
+                        .collect::<Vec<_>>()
+                        .as_ptr(),
                     results.len() as isize,
-                    into_raw_array(results.iter().map(|r#type| r#type.to_raw()).collect()),
+                    results
+                        .iter()
+                        .map(|r#type| r#type.to_raw())
+                        .collect::<Vec<_>>()
+                        .as_ptr(),
                 ))
             },
         }
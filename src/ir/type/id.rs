@@ -14,6 +14,10 @@ impl Id {
     pub(crate) unsafe fn from_raw(raw: MlirTypeID) -> Self {
         Self { raw }
     }
+
+    pub(crate) unsafe fn to_raw(self) -> MlirTypeID {
+        self.raw
+    }
 }
 
 impl PartialEq for Id {
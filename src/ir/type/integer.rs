@@ -0,0 +1,160 @@
+use super::TypeLike;
+use crate::{ir::Type, Context, Error};
+use mlir_sys::{
+    mlirIntegerTypeGet, mlirIntegerTypeGetWidth, mlirIntegerTypeIsSigned,
+    mlirIntegerTypeIsSignless, mlirIntegerTypeIsUnsigned, mlirIntegerTypeSignedGet,
+    mlirIntegerTypeUnsignedGet, MlirType,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// An integer type.
+#[derive(Clone, Copy, Debug)]
+pub struct Integer<'c> {
+    r#type: Type<'c>,
+}
+
+impl<'c> Integer<'c> {
+    /// Creates a signless integer type.
+    pub fn new(context: &'c Context, bits: u32) -> Self {
+        Self {
+            r#type: unsafe { Type::from_raw(mlirIntegerTypeGet(context.to_raw(), bits)) },
+        }
+    }
+
+    /// Creates a signed integer type.
+    pub fn signed(context: &'c Context, bits: u32) -> Self {
+        Self {
+            r#type: unsafe { Type::from_raw(mlirIntegerTypeSignedGet(context.to_raw(), bits)) },
+        }
+    }
+
+    /// Creates an unsigned integer type.
+    pub fn unsigned(context: &'c Context, bits: u32) -> Self {
+        Self {
+            r#type: unsafe { Type::from_raw(mlirIntegerTypeUnsignedGet(context.to_raw(), bits)) },
+        }
+    }
+
+    /// Gets a bit width.
+    pub fn width(&self) -> u32 {
+        unsafe { mlirIntegerTypeGetWidth(self.r#type.to_raw()) }
+    }
+
+    /// Returns `true` if an integer type is signed.
+    pub fn is_signed(&self) -> bool {
+        unsafe { mlirIntegerTypeIsSigned(self.r#type.to_raw()) }
+    }
+
+    /// Returns `true` if an integer type is signless.
+    pub fn is_signless(&self) -> bool {
+        unsafe { mlirIntegerTypeIsSignless(self.r#type.to_raw()) }
+    }
+
+    /// Returns `true` if an integer type is unsigned.
+    pub fn is_unsigned(&self) -> bool {
+        unsafe { mlirIntegerTypeIsUnsigned(self.r#type.to_raw()) }
+    }
+}
+
+impl<'c> TypeLike<'c> for Integer<'c> {
+    fn to_raw(&self) -> MlirType {
+        self.r#type.to_raw()
+    }
+}
+
+impl<'c> Display for Integer<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        Type::from(*self).fmt(formatter)
+    }
+}
+
+impl<'c> TryFrom<Type<'c>> for Integer<'c> {
+    type Error = Error;
+
+    fn try_from(r#type: Type<'c>) -> Result<Self, Self::Error> {
+        if r#type.is_integer() {
+            Ok(Self { r#type })
+        } else {
+            Err(Error::IntegerExpected(r#type.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let context = Context::new();
+
+        assert_eq!(
+            Type::from(Integer::new(&context, 42)),
+            Type::integer(&context, 42)
+        );
+    }
+
+    #[test]
+    fn signed() {
+        let context = Context::new();
+
+        assert_eq!(
+            Type::from(Integer::signed(&context, 42)),
+            Type::parse(&context, "si42").unwrap()
+        );
+    }
+
+    #[test]
+    fn unsigned() {
+        let context = Context::new();
+
+        assert_eq!(
+            Type::from(Integer::unsigned(&context, 42)),
+            Type::parse(&context, "ui42").unwrap()
+        );
+    }
+
+    #[test]
+    fn width() {
+        assert_eq!(Integer::new(&Context::new(), 42).width(), 42);
+    }
+
+    #[test]
+    fn signedness() {
+        let context = Context::new();
+        let signless = Integer::new(&context, 8);
+        let signed = Integer::signed(&context, 8);
+        let unsigned = Integer::unsigned(&context, 8);
+
+        assert!(signless.is_signless());
+        assert!(!signless.is_signed());
+        assert!(!signless.is_unsigned());
+        assert!(signed.is_signed());
+        assert!(!signed.is_signless());
+        assert!(unsigned.is_unsigned());
+        assert!(!unsigned.is_signless());
+    }
+
+    #[test]
+    fn try_from() {
+        let context = Context::new();
+
+        assert_eq!(
+            Integer::try_from(Type::parse(&context, "si8").unwrap())
+                .unwrap()
+                .to_string(),
+            "si8"
+        );
+    }
+
+    #[test]
+    fn try_from_error() {
+        let context = Context::new();
+        let r#type = Type::index(&context);
+
+        assert_eq!(
+            Integer::try_from(r#type).unwrap_err(),
+            Error::IntegerExpected(r#type.to_string())
+        );
+    }
+}
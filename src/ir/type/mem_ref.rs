@@ -0,0 +1,388 @@
+use super::{Shaped, TypeLike};
+use crate::{
+    ir::{Attribute, Type},
+    Context, Error,
+};
+use mlir_sys::{
+    mlirAffineAddExprGet, mlirAffineBinaryOpExprGetLHS, mlirAffineBinaryOpExprGetRHS,
+    mlirAffineConstantExprGet, mlirAffineConstantExprGetValue, mlirAffineDimExprGet,
+    mlirAffineDimExprGetPosition, mlirAffineExprIsAAdd, mlirAffineExprIsAConstant,
+    mlirAffineExprIsADim, mlirAffineExprIsAMul, mlirAffineExprIsASymbol, mlirAffineMapAttrGet,
+    mlirAffineMapGet, mlirAffineMapGetNumResults, mlirAffineMapGetResult, mlirAffineMapIsIdentity,
+    mlirAffineMulExprGet, mlirAffineSymbolExprGet, mlirMemRefTypeGet, mlirMemRefTypeGetAffineMap,
+    mlirMemRefTypeGetLayout, mlirMemRefTypeGetMemorySpace, mlirShapedTypeGetDimSize,
+    mlirShapedTypeGetRank, MlirAffineExpr, MlirType,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// A memref type.
+#[derive(Clone, Copy, Debug)]
+pub struct MemRef<'c> {
+    r#type: Type<'c>,
+}
+
+impl<'c> MemRef<'c> {
+    /// A marker of dynamic dimension sizes.
+    pub const DYNAMIC_SIZE: i64 = Shaped::DYNAMIC;
+
+    /// A marker of dynamic strides and offsets.
+    pub const DYNAMIC_STRIDE_OR_OFFSET: i64 = i64::MIN;
+
+    /// Creates a memref type.
+    pub fn new(
+        r#type: Type<'c>,
+        dimensions: &[i64],
+        layout: Option<Attribute<'c>>,
+        memory_space: Option<Attribute<'c>>,
+    ) -> Self {
+        unsafe {
+            Self {
+                r#type: Type::from_raw(mlirMemRefTypeGet(
+                    r#type.to_raw(),
+                    dimensions.len() as isize,
+                    dimensions.as_ptr(),
+                    layout.unwrap_or_else(Attribute::null).to_raw(),
+                    memory_space.unwrap_or_else(Attribute::null).to_raw(),
+                )),
+            }
+        }
+    }
+
+    /// Creates a strided layout attribute with an offset and strides.
+    ///
+    /// Dynamic offsets and strides are represented by
+    /// [`DYNAMIC_STRIDE_OR_OFFSET`](Self::DYNAMIC_STRIDE_OR_OFFSET) and mapped
+    /// to symbols of the layout map.
+    pub fn strided_layout(context: &'c Context, offset: i64, strides: &[i64]) -> Attribute<'c> {
+        unsafe {
+            let mut symbol_count = 0;
+            let mut next_symbol = || {
+                let symbol = mlirAffineSymbolExprGet(context.to_raw(), symbol_count);
+                symbol_count += 1;
+                symbol
+            };
+
+            let mut expression = if offset == Self::DYNAMIC_STRIDE_OR_OFFSET {
+                next_symbol()
+            } else {
+                mlirAffineConstantExprGet(context.to_raw(), offset)
+            };
+
+            for (index, &stride) in strides.iter().enumerate() {
+                expression = mlirAffineAddExprGet(
+                    expression,
+                    mlirAffineMulExprGet(
+                        mlirAffineDimExprGet(context.to_raw(), index as isize),
+                        if stride == Self::DYNAMIC_STRIDE_OR_OFFSET {
+                            next_symbol()
+                        } else {
+                            mlirAffineConstantExprGet(context.to_raw(), stride)
+                        },
+                    ),
+                );
+            }
+
+            Attribute::from_raw(mlirAffineMapAttrGet(mlirAffineMapGet(
+                context.to_raw(),
+                strides.len() as isize,
+                symbol_count,
+                1,
+                &mut expression,
+            )))
+        }
+    }
+
+    /// Gets a layout.
+    pub fn layout(&self) -> Attribute<'c> {
+        unsafe { Attribute::from_raw(mlirMemRefTypeGetLayout(self.r#type.to_raw())) }
+    }
+
+    /// Gets a memory space.
+    pub fn memory_space(&self) -> Option<Attribute<'c>> {
+        unsafe { Attribute::from_option_raw(mlirMemRefTypeGetMemorySpace(self.r#type.to_raw())) }
+    }
+
+    /// Gets strides and an offset of a strided layout.
+    ///
+    /// Dynamic offsets and strides are represented by
+    /// [`DYNAMIC_STRIDE_OR_OFFSET`](Self::DYNAMIC_STRIDE_OR_OFFSET).
+    pub fn strides_and_offset(&self) -> Result<(Vec<i64>, i64), Error> {
+        unsafe {
+            let rank = mlirShapedTypeGetRank(self.r#type.to_raw()) as usize;
+            let map = mlirMemRefTypeGetAffineMap(self.r#type.to_raw());
+
+            if mlirAffineMapIsIdentity(map) {
+                let mut strides = vec![1; rank];
+
+                for index in (0..rank.saturating_sub(1)).rev() {
+                    let size = mlirShapedTypeGetDimSize(self.r#type.to_raw(), index as isize + 1);
+
+                    strides[index] = if size == Self::DYNAMIC_SIZE
+                        || strides[index + 1] == Self::DYNAMIC_STRIDE_OR_OFFSET
+                    {
+                        Self::DYNAMIC_STRIDE_OR_OFFSET
+                    } else {
+                        strides[index + 1] * size
+                    };
+                }
+
+                return Ok((strides, 0));
+            }
+
+            let mut strides = vec![0; rank];
+            let mut offset = 0;
+
+            if mlirAffineMapGetNumResults(map) != 1
+                || !collect_strided_terms(mlirAffineMapGetResult(map, 0), &mut strides, &mut offset)
+            {
+                return Err(Error::StridedLayoutExpected(self.to_string()));
+            }
+
+            Ok((strides, offset))
+        }
+    }
+}
+
+unsafe fn collect_strided_terms(
+    expression: MlirAffineExpr,
+    strides: &mut [i64],
+    offset: &mut i64,
+) -> bool {
+    if mlirAffineExprIsAAdd(expression) {
+        collect_strided_terms(mlirAffineBinaryOpExprGetLHS(expression), strides, offset)
+            && collect_strided_terms(mlirAffineBinaryOpExprGetRHS(expression), strides, offset)
+    } else if mlirAffineExprIsAMul(expression) {
+        let lhs = mlirAffineBinaryOpExprGetLHS(expression);
+        let rhs = mlirAffineBinaryOpExprGetRHS(expression);
+        let (dimension, factor) = if mlirAffineExprIsADim(lhs) {
+            (lhs, rhs)
+        } else if mlirAffineExprIsADim(rhs) {
+            (rhs, lhs)
+        } else {
+            return false;
+        };
+        let stride = &mut strides[mlirAffineDimExprGetPosition(dimension) as usize];
+
+        if mlirAffineExprIsAConstant(factor) {
+            add_stride_or_offset(stride, mlirAffineConstantExprGetValue(factor));
+        } else if mlirAffineExprIsASymbol(factor) {
+            *stride = MemRef::DYNAMIC_STRIDE_OR_OFFSET;
+        } else {
+            return false;
+        }
+
+        true
+    } else if mlirAffineExprIsADim(expression) {
+        add_stride_or_offset(
+            &mut strides[mlirAffineDimExprGetPosition(expression) as usize],
+            1,
+        );
+
+        true
+    } else if mlirAffineExprIsAConstant(expression) {
+        add_stride_or_offset(offset, mlirAffineConstantExprGetValue(expression));
+
+        true
+    } else if mlirAffineExprIsASymbol(expression) {
+        *offset = MemRef::DYNAMIC_STRIDE_OR_OFFSET;
+
+        true
+    } else {
+        false
+    }
+}
+
+fn add_stride_or_offset(value: &mut i64, other: i64) {
+    if *value != MemRef::DYNAMIC_STRIDE_OR_OFFSET {
+        *value += other;
+    }
+}
+
+impl<'c> TypeLike<'c> for MemRef<'c> {
+    fn to_raw(&self) -> MlirType {
+        self.r#type.to_raw()
+    }
+}
+
+impl<'c> Display for MemRef<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        Type::from(*self).fmt(formatter)
+    }
+}
+
+impl<'c> TryFrom<Type<'c>> for MemRef<'c> {
+    type Error = Error;
+
+    fn try_from(r#type: Type<'c>) -> Result<Self, Self::Error> {
+        if r#type.is_mem_ref() {
+            Ok(Self { r#type })
+        } else {
+            Err(Error::MemRefExpected(r#type.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Context;
+
+    #[test]
+    fn new() {
+        let context = Context::new();
+
+        assert_eq!(
+            Type::from(MemRef::new(Type::float32(&context), &[42], None, None)),
+            Type::parse(&context, "memref<42xf32>").unwrap()
+        );
+    }
+
+    #[test]
+    fn new_with_dynamic_size() {
+        let context = Context::new();
+
+        assert_eq!(
+            Type::from(MemRef::new(
+                Type::float32(&context),
+                &[MemRef::DYNAMIC_SIZE, 42],
+                None,
+                None
+            )),
+            Type::parse(&context, "memref<?x42xf32>").unwrap()
+        );
+    }
+
+    #[test]
+    fn new_with_layout() {
+        let context = Context::new();
+
+        assert_eq!(
+            Type::from(MemRef::new(
+                Type::float32(&context),
+                &[4, 16],
+                Some(MemRef::strided_layout(&context, 8, &[32, 1])),
+                None
+            )),
+            Type::parse(
+                &context,
+                "memref<4x16xf32, affine_map<(d0, d1) -> (d0 * 32 + d1 + 8)>>"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn strided_layout() {
+        let context = Context::new();
+
+        assert_eq!(
+            MemRef::strided_layout(&context, 0, &[16, 1]),
+            Attribute::parse(&context, "affine_map<(d0, d1) -> (d0 * 16 + d1)>").unwrap()
+        );
+    }
+
+    #[test]
+    fn strided_layout_with_dynamic_offset_and_stride() {
+        let context = Context::new();
+
+        assert_eq!(
+            MemRef::strided_layout(
+                &context,
+                MemRef::DYNAMIC_STRIDE_OR_OFFSET,
+                &[MemRef::DYNAMIC_STRIDE_OR_OFFSET, 1]
+            ),
+            Attribute::parse(
+                &context,
+                "affine_map<(d0, d1)[s0, s1] -> (s0 + d0 * s1 + d1)>"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn memory_space() {
+        let context = Context::new();
+
+        assert_eq!(
+            MemRef::new(Type::float32(&context), &[42], None, None).memory_space(),
+            None
+        );
+    }
+
+    #[test]
+    fn strides_and_offset_of_identity_layout() {
+        let context = Context::new();
+
+        assert_eq!(
+            MemRef::new(Type::float32(&context), &[2, 3, 4], None, None).strides_and_offset(),
+            Ok((vec![12, 4, 1], 0))
+        );
+    }
+
+    #[test]
+    fn strides_and_offset_of_identity_layout_with_dynamic_size() {
+        let context = Context::new();
+
+        assert_eq!(
+            MemRef::new(
+                Type::float32(&context),
+                &[2, MemRef::DYNAMIC_SIZE, 4],
+                None,
+                None
+            )
+            .strides_and_offset(),
+            Ok((vec![MemRef::DYNAMIC_STRIDE_OR_OFFSET, 4, 1], 0))
+        );
+    }
+
+    #[test]
+    fn strides_and_offset_of_strided_layout() {
+        let context = Context::new();
+
+        for (offset, strides) in [
+            (8, vec![32, 1]),
+            (MemRef::DYNAMIC_STRIDE_OR_OFFSET, vec![32, 1]),
+            (0, vec![MemRef::DYNAMIC_STRIDE_OR_OFFSET, 1]),
+        ] {
+            assert_eq!(
+                MemRef::new(
+                    Type::float32(&context),
+                    &[4, 16],
+                    Some(MemRef::strided_layout(&context, offset, &strides)),
+                    None
+                )
+                .strides_and_offset(),
+                Ok((strides, offset))
+            );
+        }
+    }
+
+    #[test]
+    fn strides_and_offset_error() {
+        let context = Context::new();
+        let r#type = MemRef::try_from(
+            Type::parse(
+                &context,
+                "memref<4x16xf32, affine_map<(d0, d1) -> (d1, d0)>>",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            r#type.strides_and_offset(),
+            Err(Error::StridedLayoutExpected(r#type.to_string()))
+        );
+    }
+
+    #[test]
+    fn try_from_error() {
+        let context = Context::new();
+        let r#type = Type::index(&context);
+
+        assert_eq!(
+            MemRef::try_from(r#type).unwrap_err(),
+            Error::MemRefExpected(r#type.to_string())
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use super::{MemRef, TypeLike};
+use crate::{ir::Type, Error};
+use mlir_sys::{
+    mlirShapedTypeGetDimSize, mlirShapedTypeGetElementType, mlirShapedTypeGetRank,
+    mlirShapedTypeHasRank, mlirShapedTypeHasStaticShape, mlirShapedTypeIsDynamicDim, MlirType,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// A shaped type, which is a tensor, memref, or vector type.
+#[derive(Clone, Copy, Debug)]
+pub struct Shaped<'c> {
+    r#type: Type<'c>,
+}
+
+impl<'c> Shaped<'c> {
+    /// A marker of dynamic dimension sizes.
+    pub const DYNAMIC: i64 = -1;
+
+    /// Gets an element type.
+    pub fn element_type(&self) -> Type<'c> {
+        unsafe { Type::from_raw(mlirShapedTypeGetElementType(self.r#type.to_raw())) }
+    }
+
+    /// Gets a rank.
+    ///
+    /// It returns `None` if a type is unranked.
+    pub fn rank(&self) -> Option<usize> {
+        if self.has_rank() {
+            Some(unsafe { mlirShapedTypeGetRank(self.r#type.to_raw()) } as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Gets a size of a dimension at a position.
+    ///
+    /// Dynamic sizes are represented by [`DYNAMIC`](Self::DYNAMIC).
+    pub fn dimension_size(&self, position: usize) -> Result<i64, Error> {
+        self.check_dimension_position(position)?;
+
+        Ok(unsafe { mlirShapedTypeGetDimSize(self.r#type.to_raw(), position as isize) })
+    }
+
+    /// Gets sizes of dimensions.
+    ///
+    /// It returns `None` if a type is unranked.
+    pub fn dimension_sizes(&self) -> Option<Vec<i64>> {
+        Some(
+            (0..self.rank()?)
+                .map(|position| unsafe {
+                    mlirShapedTypeGetDimSize(self.r#type.to_raw(), position as isize)
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns `true` if a type is ranked.
+    pub fn has_rank(&self) -> bool {
+        unsafe { mlirShapedTypeHasRank(self.r#type.to_raw()) }
+    }
+
+    /// Returns `true` if a type is ranked and all of its dimensions are static.
+    pub fn has_static_shape(&self) -> bool {
+        unsafe { mlirShapedTypeHasStaticShape(self.r#type.to_raw()) }
+    }
+
+    /// Returns `true` if a dimension at a position is dynamic.
+    pub fn is_dynamic_dimension(&self, position: usize) -> Result<bool, Error> {
+        self.check_dimension_position(position)?;
+
+        Ok(unsafe { mlirShapedTypeIsDynamicDim(self.r#type.to_raw(), position as isize) })
+    }
+
+    fn check_dimension_position(&self, position: usize) -> Result<(), Error> {
+        if position < self.rank().unwrap_or_default() {
+            Ok(())
+        } else {
+            Err(Error::ShapedDimensionPosition(
+                self.r#type.to_string(),
+                position,
+            ))
+        }
+    }
+}
+
+impl<'c> TypeLike<'c> for Shaped<'c> {
+    fn to_raw(&self) -> MlirType {
+        self.r#type.to_raw()
+    }
+}
+
+impl<'c> Display for Shaped<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        Type::from(*self).fmt(formatter)
+    }
+}
+
+impl<'c> From<MemRef<'c>> for Shaped<'c> {
+    fn from(mem_ref: MemRef<'c>) -> Self {
+        Self {
+            r#type: mem_ref.into(),
+        }
+    }
+}
+
+impl<'c> TryFrom<Type<'c>> for Shaped<'c> {
+    type Error = Error;
+
+    fn try_from(r#type: Type<'c>) -> Result<Self, Self::Error> {
+        if r#type.is_shaped() {
+            Ok(Self { r#type })
+        } else {
+            Err(Error::ShapedExpected(r#type.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Context;
+
+    fn parse_shaped<'c>(context: &'c Context, source: &str) -> Shaped<'c> {
+        Shaped::try_from(Type::parse(context, source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn element_type() {
+        let context = Context::new();
+
+        assert_eq!(
+            parse_shaped(&context, "tensor<4xf32>").element_type(),
+            Type::float32(&context)
+        );
+    }
+
+    #[test]
+    fn rank() {
+        let context = Context::new();
+
+        assert_eq!(parse_shaped(&context, "tensor<2x?xf32>").rank(), Some(2));
+        assert_eq!(parse_shaped(&context, "vector<4xf32>").rank(), Some(1));
+        assert_eq!(parse_shaped(&context, "tensor<*xf32>").rank(), None);
+        assert_eq!(parse_shaped(&context, "memref<*xf32>").rank(), None);
+    }
+
+    #[test]
+    fn dimension_size() {
+        let context = Context::new();
+        let r#type = parse_shaped(&context, "memref<2x?xf32>");
+
+        assert_eq!(r#type.dimension_size(0), Ok(2));
+        assert_eq!(r#type.dimension_size(1), Ok(Shaped::DYNAMIC));
+        assert_eq!(
+            r#type.dimension_size(2),
+            Err(Error::ShapedDimensionPosition(r#type.to_string(), 2))
+        );
+    }
+
+    #[test]
+    fn dimension_size_of_unranked_type() {
+        let context = Context::new();
+        let r#type = parse_shaped(&context, "tensor<*xf32>");
+
+        assert_eq!(
+            r#type.dimension_size(0),
+            Err(Error::ShapedDimensionPosition(r#type.to_string(), 0))
+        );
+    }
+
+    #[test]
+    fn dimension_sizes() {
+        let context = Context::new();
+
+        assert_eq!(
+            parse_shaped(&context, "tensor<2x?x4xf32>").dimension_sizes(),
+            Some(vec![2, Shaped::DYNAMIC, 4])
+        );
+        assert_eq!(
+            parse_shaped(&context, "tensor<*xf32>").dimension_sizes(),
+            None
+        );
+    }
+
+    #[test]
+    fn is_dynamic_dimension() {
+        let context = Context::new();
+        let r#type = parse_shaped(&context, "tensor<2x?xf32>");
+
+        assert_eq!(r#type.is_dynamic_dimension(0), Ok(false));
+        assert_eq!(r#type.is_dynamic_dimension(1), Ok(true));
+        assert!(r#type.is_dynamic_dimension(2).is_err());
+    }
+
+    #[test]
+    fn has_static_shape() {
+        let context = Context::new();
+
+        assert!(parse_shaped(&context, "tensor<2x4xf32>").has_static_shape());
+        assert!(!parse_shaped(&context, "tensor<2x?xf32>").has_static_shape());
+        assert!(!parse_shaped(&context, "tensor<*xf32>").has_static_shape());
+    }
+
+    #[test]
+    fn from_mem_ref() {
+        let context = Context::new();
+
+        assert_eq!(
+            Shaped::from(MemRef::new(Type::float32(&context), &[4], None, None)).rank(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn try_from_error() {
+        let context = Context::new();
+        let r#type = Type::index(&context);
+
+        assert_eq!(
+            Shaped::try_from(r#type).unwrap_err(),
+            Error::ShapedExpected(r#type.to_string())
+        );
+    }
+}
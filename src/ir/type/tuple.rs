@@ -1,5 +1,5 @@
 use super::TypeLike;
-use crate::{ir::Type, utility::into_raw_array, Context, Error};
+use crate::{ir::Type, Context, Error};
 use mlir_sys::{mlirTupleTypeGet, mlirTupleTypeGetNumTypes, mlirTupleTypeGetType, MlirType};
 use std::fmt::{self, Display, Formatter};
 
@@ -17,7 +17,11 @@ impl<'c> Tuple<'c> {
                 Type::from_raw(mlirTupleTypeGet(
                     context.to_raw(),
                     types.len() as isize,
-                    into_raw_array(types.iter().map(|r#type| r#type.to_raw()).collect()),
+                    types
+                        .iter()
+                        .map(|r#type| r#type.to_raw())
+                        .collect::<Vec<_>>()
+                        .as_ptr(),
                 ))
             },
         }
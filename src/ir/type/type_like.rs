@@ -1,8 +1,11 @@
 use super::Id;
-use crate::context::ContextRef;
+use crate::{context::ContextRef, string_ref::StringRef};
 use mlir_sys::{
-    mlirTypeDump, mlirTypeGetContext, mlirTypeGetTypeID, mlirTypeIsABF16, mlirTypeIsAF16,
-    mlirTypeIsAF32, mlirTypeIsAF64, mlirTypeIsAFunction, mlirTypeIsATuple, mlirTypeIsAVector,
+    mlirTypeDump, mlirTypeEqual, mlirTypeGetContext, mlirTypeGetTypeID, mlirTypeIsABF16,
+    mlirTypeIsAComplex, mlirTypeIsAF16, mlirTypeIsAF32, mlirTypeIsAF64, mlirTypeIsAFunction,
+    mlirTypeIsAIndex, mlirTypeIsAInteger, mlirTypeIsAMemRef, mlirTypeIsANone, mlirTypeIsAOpaque,
+    mlirTypeIsARankedTensor, mlirTypeIsAShaped, mlirTypeIsATensor, mlirTypeIsATuple,
+    mlirTypeIsAUnrankedMemRef, mlirTypeIsAUnrankedTensor, mlirTypeIsAVector, mlirTypeParseGet,
     MlirType,
 };
 
@@ -25,6 +28,21 @@ pub trait TypeLike<'c> {
         unsafe { mlirTypeIsABF16(self.to_raw()) }
     }
 
+    /// Returns `true` if a type is a complex number.
+    fn is_complex(&self) -> bool {
+        unsafe { mlirTypeIsAComplex(self.to_raw()) }
+    }
+
+    /// Returns `true` if a type is a floating point number of any width.
+    fn is_float(&self) -> bool {
+        self.is_bfloat16()
+            || self.is_float16()
+            || self.is_float32()
+            || self.is_float64()
+            || self.is_float80()
+            || self.is_float128()
+    }
+
     /// Returns `true` if a type is float16.
     fn is_float16(&self) -> bool {
         unsafe { mlirTypeIsAF16(self.to_raw()) }
@@ -40,16 +58,76 @@ pub trait TypeLike<'c> {
         unsafe { mlirTypeIsAF64(self.to_raw()) }
     }
 
+    /// Returns `true` if a type is float80.
+    fn is_float80(&self) -> bool {
+        is_parsed(self.to_raw(), "f80")
+    }
+
+    /// Returns `true` if a type is float128.
+    fn is_float128(&self) -> bool {
+        is_parsed(self.to_raw(), "f128")
+    }
+
     /// Returns `true` if a type is a function.
     fn is_function(&self) -> bool {
         unsafe { mlirTypeIsAFunction(self.to_raw()) }
     }
 
+    /// Returns `true` if a type is an index.
+    fn is_index(&self) -> bool {
+        unsafe { mlirTypeIsAIndex(self.to_raw()) }
+    }
+
+    /// Returns `true` if a type is an integer.
+    fn is_integer(&self) -> bool {
+        unsafe { mlirTypeIsAInteger(self.to_raw()) }
+    }
+
+    /// Returns `true` if a type is a memref.
+    fn is_mem_ref(&self) -> bool {
+        unsafe { mlirTypeIsAMemRef(self.to_raw()) }
+    }
+
+    /// Returns `true` if a type is none.
+    fn is_none(&self) -> bool {
+        unsafe { mlirTypeIsANone(self.to_raw()) }
+    }
+
+    /// Returns `true` if a type is opaque.
+    fn is_opaque(&self) -> bool {
+        unsafe { mlirTypeIsAOpaque(self.to_raw()) }
+    }
+
+    /// Returns `true` if a type is a ranked tensor.
+    fn is_ranked_tensor(&self) -> bool {
+        unsafe { mlirTypeIsARankedTensor(self.to_raw()) }
+    }
+
+    /// Returns `true` if a type is shaped, e.g. a tensor, memref, or vector.
+    fn is_shaped(&self) -> bool {
+        unsafe { mlirTypeIsAShaped(self.to_raw()) }
+    }
+
+    /// Returns `true` if a type is a tensor.
+    fn is_tensor(&self) -> bool {
+        unsafe { mlirTypeIsATensor(self.to_raw()) }
+    }
+
     /// Returns `true` if a type is a tuple.
     fn is_tuple(&self) -> bool {
         unsafe { mlirTypeIsATuple(self.to_raw()) }
     }
 
+    /// Returns `true` if a type is an unranked memref.
+    fn is_unranked_mem_ref(&self) -> bool {
+        unsafe { mlirTypeIsAUnrankedMemRef(self.to_raw()) }
+    }
+
+    /// Returns `true` if a type is an unranked tensor.
+    fn is_unranked_tensor(&self) -> bool {
+        unsafe { mlirTypeIsAUnrankedTensor(self.to_raw()) }
+    }
+
     /// Returns `true` if a type is a vector.
     fn is_vector(&self) -> bool {
         unsafe { mlirTypeIsAVector(self.to_raw()) }
@@ -61,6 +139,16 @@ pub trait TypeLike<'c> {
     }
 }
 
+// Compares a type with a parsed one.
+fn is_parsed(r#type: MlirType, source: &str) -> bool {
+    unsafe {
+        mlirTypeEqual(
+            r#type,
+            mlirTypeParseGet(mlirTypeGetContext(r#type), StringRef::from(source).to_raw()),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,7 +173,25 @@ mod tests {
     fn is_bfloat16() {
         let context = Context::new();
 
-        assert!(Function::new(&context, &[], &[]).is_function());
+        assert!(Type::bfloat16(&context).is_bfloat16());
+    }
+
+    #[test]
+    fn is_complex() {
+        let context = Context::new();
+
+        assert!(Type::parse(&context, "complex<f32>").unwrap().is_complex());
+    }
+
+    #[test]
+    fn is_float() {
+        let context = Context::new();
+
+        assert!(Type::bfloat16(&context).is_float());
+        assert!(Type::float64(&context).is_float());
+        assert!(Type::float80(&context).is_float());
+        assert!(Type::float128(&context).is_float());
+        assert!(!Type::integer(&context, 64).is_float());
     }
 
     #[test]
@@ -95,6 +201,76 @@ mod tests {
         assert!(Function::new(&context, &[], &[]).is_function());
     }
 
+    #[test]
+    fn is_index() {
+        let context = Context::new();
+
+        assert!(Type::index(&context).is_index());
+        assert!(!Type::index(&context).is_integer());
+    }
+
+    #[test]
+    fn is_integer() {
+        let context = Context::new();
+
+        assert!(Type::integer(&context, 64).is_integer());
+        assert!(Type::signed_integer(&context, 64).is_integer());
+    }
+
+    #[test]
+    fn is_mem_ref() {
+        let context = Context::new();
+
+        assert!(Type::parse(&context, "memref<42xf32>")
+            .unwrap()
+            .is_mem_ref());
+    }
+
+    #[test]
+    fn is_none() {
+        assert!(Type::none(&Context::new()).is_none());
+    }
+
+    #[test]
+    fn is_opaque() {
+        let context = Context::new();
+        context.set_allow_unregistered_dialects(true);
+
+        assert!(Type::parse(&context, "!foo.bar").unwrap().is_opaque());
+    }
+
+    #[test]
+    fn is_shaped() {
+        let context = Context::new();
+
+        assert!(Type::parse(&context, "tensor<42xf32>").unwrap().is_shaped());
+        assert!(Type::parse(&context, "memref<42xf32>").unwrap().is_shaped());
+        assert!(Type::vector(&[42], Type::float32(&context)).is_shaped());
+        assert!(!Type::float32(&context).is_shaped());
+    }
+
+    #[test]
+    fn is_tensor() {
+        let context = Context::new();
+        let ranked = Type::parse(&context, "tensor<42xf32>").unwrap();
+        let unranked = Type::parse(&context, "tensor<*xf32>").unwrap();
+
+        assert!(ranked.is_tensor());
+        assert!(ranked.is_ranked_tensor());
+        assert!(!ranked.is_unranked_tensor());
+        assert!(unranked.is_tensor());
+        assert!(unranked.is_unranked_tensor());
+    }
+
+    #[test]
+    fn is_unranked_mem_ref() {
+        let context = Context::new();
+
+        assert!(Type::parse(&context, "memref<*xf32>")
+            .unwrap()
+            .is_unranked_mem_ref());
+    }
+
     #[test]
     fn is_vector() {
         let context = Context::new();
@@ -1,12 +1,13 @@
 mod value_like;
 
 pub use self::value_like::ValueLike;
-use super::{block, operation, Type};
+use super::{block, operation, Location, Type};
 use crate::utility::print_callback;
 use mlir_sys::{mlirValueEqual, mlirValuePrint, MlirValue};
 use std::{
     ffi::c_void,
     fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
     marker::PhantomData,
 };
 
@@ -20,6 +21,16 @@ pub struct Value<'a> {
 }
 
 impl<'a> Value<'a> {
+    /// Gets a location of an operation result.
+    ///
+    /// It returns `None` for block arguments.
+    // TODO Get locations of block arguments.
+    pub fn location(&self) -> Option<Location<'a>> {
+        operation::ResultValue::try_from(*self)
+            .ok()
+            .map(|result| result.location())
+    }
+
     pub(crate) unsafe fn from_raw(value: MlirValue) -> Self {
         Self {
             raw: value,
@@ -28,7 +39,7 @@ impl<'a> Value<'a> {
     }
 }
 
-impl<'a> ValueLike for Value<'a> {
+impl<'a> ValueLike<'a> for Value<'a> {
     fn to_raw(&self) -> MlirValue {
         self.raw
     }
@@ -42,6 +53,12 @@ impl<'a> PartialEq for Value<'a> {
 
 impl<'a> Eq for Value<'a> {}
 
+impl<'a> Hash for Value<'a> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.raw.ptr.hash(hasher);
+    }
+}
+
 impl<'a> Display for Value<'a> {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         let mut data = (formatter, Ok(()));
@@ -100,11 +117,35 @@ mod tests {
                 Identifier::new(&context, "value"),
                 Attribute::parse(&context, "0 : index").unwrap(),
             )])
-            .build();
+            .build()
+            .unwrap();
 
         assert_eq!(operation.result(0).unwrap().r#type(), index_type);
     }
 
+    #[test]
+    fn location() {
+        let context = Context::new();
+        let location = Location::new(&context, "foo", 1, 2);
+        let operation = operation::Builder::new("foo", location)
+            .add_results(&[Type::index(&context)])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            Value::from(operation.result(0).unwrap()).location(),
+            Some(location)
+        );
+    }
+
+    #[test]
+    fn location_of_block_argument() {
+        let context = Context::new();
+        let block = Block::new(&[(Type::index(&context), Location::unknown(&context))]);
+
+        assert_eq!(Value::from(block.argument(0).unwrap()).location(), None);
+    }
+
     #[test]
     fn is_operation_result() {
         let context = Context::new();
@@ -117,7 +158,8 @@ mod tests {
                 Identifier::new(&context, "value"),
                 Attribute::parse(&context, "0 : index").unwrap(),
             )])
-            .build();
+            .build()
+            .unwrap();
 
         assert!(operation.result(0).unwrap().is_operation_result());
     }
@@ -143,7 +185,8 @@ mod tests {
                 Identifier::new(&context, "value"),
                 Attribute::parse(&context, "0 : index").unwrap(),
             )])
-            .build();
+            .build()
+            .unwrap();
 
         value.result(0).unwrap().dump();
     }
@@ -160,7 +203,8 @@ mod tests {
                 Identifier::new(&context, "value"),
                 Attribute::parse(&context, "0 : index").unwrap(),
             )])
-            .build();
+            .build()
+            .unwrap();
         let result = Value::from(operation.result(0).unwrap());
 
         assert_eq!(result, result);
@@ -180,6 +224,7 @@ mod tests {
                     Attribute::parse(&context, "0 : index").unwrap(),
                 )])
                 .build()
+                .unwrap()
         };
 
         assert_ne!(
@@ -201,7 +246,8 @@ mod tests {
                 Identifier::new(&context, "value"),
                 Attribute::parse(&context, "0 : index").unwrap(),
             )])
-            .build();
+            .build()
+            .unwrap();
 
         assert_eq!(
             operation.result(0).unwrap().to_string(),
@@ -227,7 +273,8 @@ mod tests {
                 Identifier::new(&context, "value"),
                 Attribute::parse(&context, "0 : index").unwrap(),
             )])
-            .build();
+            .build()
+            .unwrap();
 
         assert_eq!(
             operation.result(0).unwrap().to_string(),
@@ -248,7 +295,8 @@ mod tests {
                 Identifier::new(&context, "value"),
                 Attribute::parse(&context, "0 : index").unwrap(),
             )])
-            .build();
+            .build()
+            .unwrap();
 
         assert_eq!(
             format!("{:?}", Value::from(operation.result(0).unwrap())),
@@ -4,12 +4,13 @@ use mlir_sys::{
 };
 
 /// Trait for value-like types.
-pub trait ValueLike {
+// TODO Iterate uses of values.
+pub trait ValueLike<'c> {
     /// Converts a value into a raw value.
     fn to_raw(&self) -> MlirValue;
 
     /// Gets a type.
-    fn r#type(&self) -> Type {
+    fn r#type(&self) -> Type<'c> {
         unsafe { Type::from_raw(mlirValueGetType(self.to_raw())) }
     }
 
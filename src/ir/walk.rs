@@ -0,0 +1,70 @@
+//! IR walkers.
+
+use mlir_sys::{
+    mlirBlockGetFirstOperation, mlirBlockGetNextInRegion, mlirOperationGetNextInBlock,
+    mlirOperationGetNumRegions, mlirOperationGetRegion, mlirRegionGetFirstBlock, MlirOperation,
+};
+
+/// An order of operations in a walk.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WalkOrder {
+    /// Visits operations before their nested operations.
+    PreOrder,
+    /// Visits operations after their nested operations.
+    PostOrder,
+}
+
+/// A result of a visit of an operation in a walk.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WalkResult {
+    /// Continues a walk.
+    Advance,
+    /// Skips operations nested in a visited operation. This is the same as
+    /// `Advance` in post-order walks.
+    Skip,
+    /// Stops a walk.
+    Interrupt,
+}
+
+// Operations are walked through raw handles so that callbacks can receive
+// references with the lifetime of a root operation. Next operations are
+// fetched before visits so that visited operations can be detached.
+pub(crate) unsafe fn walk(
+    operation: MlirOperation,
+    order: WalkOrder,
+    callback: &mut dyn FnMut(MlirOperation) -> WalkResult,
+) -> WalkResult {
+    if order == WalkOrder::PreOrder {
+        match callback(operation) {
+            WalkResult::Advance => {}
+            WalkResult::Skip => return WalkResult::Advance,
+            WalkResult::Interrupt => return WalkResult::Interrupt,
+        }
+    }
+
+    for index in 0..mlirOperationGetNumRegions(operation) {
+        let mut block = mlirRegionGetFirstBlock(mlirOperationGetRegion(operation, index));
+
+        while !block.ptr.is_null() {
+            let mut nested_operation = mlirBlockGetFirstOperation(block);
+
+            block = mlirBlockGetNextInRegion(block);
+
+            while !nested_operation.ptr.is_null() {
+                let next_operation = mlirOperationGetNextInBlock(nested_operation);
+
+                if walk(nested_operation, order, callback) == WalkResult::Interrupt {
+                    return WalkResult::Interrupt;
+                }
+
+                nested_operation = next_operation;
+            }
+        }
+    }
+
+    if order == WalkOrder::PostOrder && callback(operation) == WalkResult::Interrupt {
+        WalkResult::Interrupt
+    } else {
+        WalkResult::Advance
+    }
+}
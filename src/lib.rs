@@ -12,51 +12,97 @@
 //! brew install llvm@15
 //! ```
 //!
+//! # Limitations
+//!
+//! Melior is built on the C API of MLIR 15, which does not expose every part
+//! of MLIR, e.g. uses of values, source buffers, and operation definitions.
+//! Some functions work around it by printing and parsing IR in its textual
+//! form, and `TODO` comments in the source mark features blocked by it.
+//!
 //! # Safety
 //!
 //! Although Melior aims to be completely safe, some part of the current API is
 //! not.
 //!
 //! - Access to operations, types, or attributes that belong to dialects not
-//!   loaded in contexts can lead to runtime errors or segmentation faults in
-//!   the worst case.
-//!   - Fix plan: Load all dialects by default on creation of contexts, and
-//!     provide unsafe constructors of contexts for advanced users.
+//!   loaded in contexts created by `Context::new_unchecked()` can lead to
+//!   runtime errors or segmentation faults in the worst case.
+//!   - `Context::new()` loads all dialects to prevent this.
 //! - IR object references returned from functions that move ownership of
 //!   arguments might get invalidated later.
 //!   - This is because we need to borrow `&self` rather than `&mut self` to
 //!     return such references.
 //!   - e.g. `Region::append_block()`
-//!   - Fix plan: Use dynamic check, such as `RefCell`, for the objects.
+//!   - References to operations, blocks, and regions are checked dynamically
+//!     in debug builds and panic if their objects are destroyed through owned
+//!     objects, such as `Block` and `Module`, or symbol tables.
+//!   - Objects destroyed by MLIR itself, such as ones erased by passes, are not
+//!     tracked yet.
+//!
+//! # Thread safety
+//!
+//! No context or IR object implements `Send` or `Sync`.
+//!
+//! - IR in MLIR is not thread-safe for mutation.
+//! - References to IR objects and modules are tracked in thread-local storage.
+//! - Diagnostic handlers attached to contexts are required to be `Send` as
+//!   MLIR might call them on its worker threads.
+//!
+//! ```compile_fail
+//! fn send<T: Send>() {}
+//!
+//! send::<melior::Context>();
+//! ```
+//!
+//! ```compile_fail
+//! fn sync<T: Sync>() {}
+//!
+//! sync::<melior::ir::Module>();
+//! ```
+//!
+//! To share IR across threads, create a context for each thread and transfer
+//! IR in its textual form.
+//!
+//! ```rust
+//! use melior::{ir::Module, Context};
+//! use std::thread;
+//!
+//! let context = Context::new();
+//! let module = Module::parse(&context, "func.func @foo() { return }").unwrap();
+//! let source = module.as_operation().to_string();
+//!
+//! thread::spawn(move || {
+//!     let context = Context::new();
+//!     let module = Module::parse(&context, &source).unwrap();
+//!
+//!     assert!(module.as_operation().verify());
+//! })
+//! .join()
+//! .unwrap();
+//! ```
 //!
 //! # Examples
 //!
 //! ## Building a function to add integers
 //!
 //! ```rust
-//! use melior::{
-//!     Context,
-//!     dialect,
-//!     ir::*,
-//!     utility::register_all_dialects,
-//! };
-//!
-//! let registry = dialect::Registry::new();
-//! register_all_dialects(&registry);
+//! use melior::{Context, dialect::func, ir::*};
 //!
 //! let context = Context::new();
-//! context.append_dialect_registry(&registry);
-//! context.get_or_load_dialect("func");
 //!
 //! let location = Location::unknown(&context);
 //! let module = Module::new(location);
 //!
 //! let integer_type = Type::integer(&context, 64);
 //!
-//! let function = {
-//!     let region = Region::new();
-//!     let block = Block::new(&[(integer_type, location), (integer_type, location)]);
-//!
+//! let function = func::Builder::new(
+//!     &context,
+//!     "add",
+//!     &[integer_type, integer_type],
+//!     &[integer_type],
+//!     location,
+//! )
+//! .build(|block| {
 //!     let sum = block.append_operation(
 //!         operation::Builder::new("arith.addi", location)
 //!             .add_operands(&[
@@ -64,51 +110,39 @@
 //!                 block.argument(1).unwrap().into(),
 //!             ])
 //!             .add_results(&[integer_type])
-//!             .build(),
+//!             .build()?,
 //!     );
 //!
-//!     block.append_operation(
-//!         operation::Builder::new("func.return", Location::unknown(&context))
-//!             .add_operands(&[sum.result(0).unwrap().into()])
-//!             .build(),
-//!     );
+//!     block.append_operation(func::r#return(&[sum.result(0).unwrap().into()], location)?);
+//!
+//!     Ok(())
+//! })
+//! .unwrap();
 //!
-//!     region.append_block(block);
-//!
-//!     operation::Builder::new("func.func", Location::unknown(&context))
-//!         .add_attributes(&[
-//!             (
-//!                 Identifier::new(&context, "function_type"),
-//!                 Attribute::parse(&context, "(i64, i64) -> i64").unwrap(),
-//!             ),
-//!             (
-//!                 Identifier::new(&context, "sym_name"),
-//!                 Attribute::parse(&context, "\"add\"").unwrap(),
-//!             ),
-//!         ])
-//!         .add_regions(vec![region])
-//!         .build()
-//! };
-//!
-//! module.body().append_operation(function);
+//! module.append_operation(function);
 //!
 //! assert!(module.as_operation().verify());
 //! ```
 
 mod context;
+pub mod diagnostic;
 pub mod dialect;
 mod error;
 mod execution_engine;
+#[cfg(feature = "generate")]
+pub mod generate;
 pub mod ir;
 mod logical_result;
 pub mod pass;
+pub mod plugin;
 mod string_ref;
+pub mod transform;
 pub mod utility;
 
 pub use self::{
     context::{Context, ContextRef},
     error::Error,
-    execution_engine::ExecutionEngine,
+    execution_engine::{AsyncInvocation, ExecutionEngine, JitFunction},
     string_ref::StringRef,
 };
 
@@ -166,13 +200,15 @@ mod tests {
                         block.argument(1).unwrap().into(),
                     ])
                     .add_results(&[integer_type])
-                    .build(),
+                    .build()
+                    .unwrap(),
             );
 
             block.append_operation(
                 operation::Builder::new("func.return", Location::unknown(&context))
                     .add_operands(&[sum.result(0).unwrap().into()])
-                    .build(),
+                    .build()
+                    .unwrap(),
             );
 
             region.append_block(block);
@@ -190,6 +226,7 @@ mod tests {
                 ])
                 .add_regions(vec![region])
                 .build()
+                .unwrap()
         };
 
         module.body().append_operation(function);
@@ -226,7 +263,8 @@ mod tests {
                         Identifier::new(&context, "value"),
                         Attribute::parse(&context, "0 : index").unwrap(),
                     )])
-                    .build(),
+                    .build()
+                    .unwrap(),
             );
 
             let dim = function_block.append_operation(
@@ -236,7 +274,8 @@ mod tests {
                         zero.result(0).unwrap().into(),
                     ])
                     .add_results(&[index_type])
-                    .build(),
+                    .build()
+                    .unwrap(),
             );
 
             let loop_block = Block::new(&[]);
@@ -249,7 +288,8 @@ mod tests {
                         Identifier::new(&context, "value"),
                         Attribute::parse(&context, "1 : index").unwrap(),
                     )])
-                    .build(),
+                    .build()
+                    .unwrap(),
             );
 
             {
@@ -262,7 +302,8 @@ mod tests {
                             loop_block.argument(0).unwrap().into(),
                         ])
                         .add_results(&[f32_type])
-                        .build(),
+                        .build()
+                        .unwrap(),
                 );
 
                 let rhs = loop_block.append_operation(
@@ -272,7 +313,8 @@ mod tests {
                             loop_block.argument(0).unwrap().into(),
                         ])
                         .add_results(&[f32_type])
-                        .build(),
+                        .build()
+                        .unwrap(),
                 );
 
                 let add = loop_block.append_operation(
@@ -282,7 +324,8 @@ mod tests {
                             rhs.result(0).unwrap().into(),
                         ])
                         .add_results(&[f32_type])
-                        .build(),
+                        .build()
+                        .unwrap(),
                 );
 
                 loop_block.append_operation(
@@ -292,10 +335,15 @@ mod tests {
                             function_block.argument(0).unwrap().into(),
                             loop_block.argument(0).unwrap().into(),
                         ])
-                        .build(),
+                        .build()
+                        .unwrap(),
                 );
 
-                loop_block.append_operation(operation::Builder::new("scf.yield", location).build());
+                loop_block.append_operation(
+                    operation::Builder::new("scf.yield", location)
+                        .build()
+                        .unwrap(),
+                );
             }
 
             function_block.append_operation(
@@ -312,11 +360,14 @@ mod tests {
                         ])
                         .add_regions(vec![loop_region])
                 }
-                .build(),
+                .build()
+                .unwrap(),
             );
 
             function_block.append_operation(
-                operation::Builder::new("func.return", Location::unknown(&context)).build(),
+                operation::Builder::new("func.return", Location::unknown(&context))
+                    .build()
+                    .unwrap(),
             );
 
             function_region.append_block(function_block);
@@ -334,6 +385,7 @@ mod tests {
                 ])
                 .add_regions(vec![function_region])
                 .build()
+                .unwrap()
         };
 
         module.body().append_operation(function);
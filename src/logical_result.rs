@@ -35,6 +35,10 @@ impl LogicalResult {
     pub(crate) fn from_raw(result: MlirLogicalResult) -> Self {
         Self { raw: result }
     }
+
+    pub(crate) fn to_raw(&self) -> MlirLogicalResult {
+        self.raw
+    }
 }
 
 #[cfg(test)]
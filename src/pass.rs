@@ -1,11 +1,26 @@
 //! Passes and pass managers.
 
+pub mod r#async;
 pub mod conversion;
+mod external;
 mod manager;
 mod operation_manager;
+mod pipeline;
+pub mod preset;
+#[cfg(feature = "profile")]
+pub mod profile;
+mod timed_manager;
+mod timing;
 pub mod transform;
 
-pub use self::{manager::Manager, operation_manager::OperationManager};
+pub use self::{
+    external::{create_external, RunnablePass},
+    manager::Manager,
+    operation_manager::OperationManager,
+    pipeline::{FuncOp, GpuModuleOp, ModuleOp, OperationName, Pipeline, PipelinePass},
+    timed_manager::TimedManager,
+    timing::{Timing, TimingReport},
+};
 use mlir_sys::MlirPass;
 
 /// A pass.
@@ -20,6 +35,10 @@ impl Pass {
         }
     }
 
+    pub(crate) unsafe fn from_raw(raw: MlirPass) -> Self {
+        Self { raw }
+    }
+
     pub(crate) unsafe fn to_raw(&self) -> MlirPass {
         self.raw
     }
@@ -0,0 +1,57 @@
+//! Passes of the `async` dialect.
+
+use super::Pass;
+use mlir_sys::{
+    mlirCreateAsyncAsyncParallelFor, mlirCreateAsyncAsyncRuntimeRefCounting,
+    mlirCreateAsyncAsyncRuntimeRefCountingOpt, mlirCreateAsyncAsyncToAsyncRuntime,
+    mlirRegisterAsyncAsyncParallelFor, mlirRegisterAsyncAsyncRuntimeRefCounting,
+    mlirRegisterAsyncAsyncRuntimeRefCountingOpt, mlirRegisterAsyncAsyncToAsyncRuntime,
+};
+
+/// Creates a pass to convert `scf.parallel` operations into concurrent
+/// `async` operations.
+pub fn async_parallel_for() -> Pass {
+    Pass::from_raw_fn(mlirCreateAsyncAsyncParallelFor)
+}
+
+/// Registers a pass to convert `scf.parallel` operations into concurrent
+/// `async` operations.
+pub fn register_async_parallel_for() {
+    unsafe { mlirRegisterAsyncAsyncParallelFor() }
+}
+
+/// Creates a pass to lower high-level `async` operations to async runtime
+/// operations.
+pub fn async_to_async_runtime() -> Pass {
+    Pass::from_raw_fn(mlirCreateAsyncAsyncToAsyncRuntime)
+}
+
+/// Registers a pass to lower high-level `async` operations to async runtime
+/// operations.
+pub fn register_async_to_async_runtime() {
+    unsafe { mlirRegisterAsyncAsyncToAsyncRuntime() }
+}
+
+/// Creates a pass to add reference counting operations of async runtime
+/// values.
+pub fn async_runtime_ref_counting() -> Pass {
+    Pass::from_raw_fn(mlirCreateAsyncAsyncRuntimeRefCounting)
+}
+
+/// Registers a pass to add reference counting operations of async runtime
+/// values.
+pub fn register_async_runtime_ref_counting() {
+    unsafe { mlirRegisterAsyncAsyncRuntimeRefCounting() }
+}
+
+/// Creates a pass to optimize reference counting operations of async runtime
+/// values.
+pub fn async_runtime_ref_counting_opt() -> Pass {
+    Pass::from_raw_fn(mlirCreateAsyncAsyncRuntimeRefCountingOpt)
+}
+
+/// Registers a pass to optimize reference counting operations of async
+/// runtime values.
+pub fn register_async_runtime_ref_counting_opt() {
+    unsafe { mlirRegisterAsyncAsyncRuntimeRefCountingOpt() }
+}
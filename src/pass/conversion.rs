@@ -1,11 +1,31 @@
 //! Dialect conversion passes.
 
+mod llvm_options;
+
+pub use self::llvm_options::{add_llvm_conversion_passes, LlvmOptions};
 use super::Pass;
 use mlir_sys::{
-    mlirCreateConversionConvertArithmeticToLLVM, mlirCreateConversionConvertControlFlowToLLVM,
-    mlirCreateConversionConvertControlFlowToSPIRV, mlirCreateConversionConvertFuncToLLVM,
-    mlirCreateConversionConvertMathToLLVM, mlirCreateConversionConvertMathToLibm,
-    mlirCreateConversionConvertMathToSPIRV,
+    mlirCreateConversionConvertAffineToStandard, mlirCreateConversionConvertArithmeticToLLVM,
+    mlirCreateConversionConvertArithmeticToSPIRV, mlirCreateConversionConvertAsyncToLLVM,
+    mlirCreateConversionConvertBufferizationToMemRef, mlirCreateConversionConvertComplexToLLVM,
+    mlirCreateConversionConvertComplexToLibm, mlirCreateConversionConvertComplexToStandard,
+    mlirCreateConversionConvertControlFlowToLLVM, mlirCreateConversionConvertControlFlowToSPIRV,
+    mlirCreateConversionConvertFuncToLLVM, mlirCreateConversionConvertFuncToSPIRV,
+    mlirCreateConversionConvertGPUToSPIRV, mlirCreateConversionConvertGpuOpsToNVVMOps,
+    mlirCreateConversionConvertGpuOpsToROCDLOps, mlirCreateConversionConvertLinalgToLLVM,
+    mlirCreateConversionConvertLinalgToStandard, mlirCreateConversionConvertMathToLLVM,
+    mlirCreateConversionConvertMathToLibm, mlirCreateConversionConvertMathToSPIRV,
+    mlirCreateConversionConvertMemRefToLLVM, mlirCreateConversionConvertMemRefToSPIRV,
+    mlirCreateConversionConvertOpenMPToLLVM, mlirCreateConversionConvertPDLToPDLInterp,
+    mlirCreateConversionConvertParallelLoopToGpu, mlirCreateConversionConvertSCFToOpenMP,
+    mlirCreateConversionConvertSPIRVToLLVM, mlirCreateConversionConvertShapeConstraints,
+    mlirCreateConversionConvertShapeToStandard, mlirCreateConversionConvertTensorToLinalg,
+    mlirCreateConversionConvertTensorToSPIRV, mlirCreateConversionConvertVectorToGPU,
+    mlirCreateConversionConvertVectorToLLVM, mlirCreateConversionConvertVectorToSCF,
+    mlirCreateConversionConvertVectorToSPIRV, mlirCreateConversionGpuToLLVMConversionPass,
+    mlirCreateConversionReconcileUnrealizedCasts, mlirCreateConversionSCFToControlFlow,
+    mlirCreateConversionSCFToSPIRV, mlirCreateConversionTosaToArith,
+    mlirCreateConversionTosaToLinalg, mlirCreateConversionTosaToSCF,
 };
 
 /// Creates a pass to convert the `arith` dialect to the `llvm` dialect.
@@ -14,10 +34,16 @@ pub fn convert_arithmetic_to_llvm() -> Pass {
 }
 
 /// Creates a pass to convert the `cf` dialect to the `llvm` dialect.
-pub fn convert_scf_to_llvm() -> Pass {
+pub fn convert_cf_to_llvm() -> Pass {
     Pass::from_raw_fn(mlirCreateConversionConvertControlFlowToLLVM)
 }
 
+/// Creates a pass to convert the `cf` dialect to the `llvm` dialect.
+#[deprecated(note = "use `convert_cf_to_llvm` instead")]
+pub fn convert_scf_to_llvm() -> Pass {
+    convert_cf_to_llvm()
+}
+
 /// Creates a pass to convert the `func` dialect to the `llvm` dialect.
 pub fn convert_func_to_llvm() -> Pass {
     Pass::from_raw_fn(mlirCreateConversionConvertFuncToLLVM)
@@ -29,16 +55,198 @@ pub fn convert_math_to_llvm() -> Pass {
 }
 
 /// Creates a pass to convert the `cf` dialect to the `spirv` dialect.
-pub fn convert_scf_to_spirv() -> Pass {
+pub fn convert_cf_to_spirv() -> Pass {
     Pass::from_raw_fn(mlirCreateConversionConvertControlFlowToSPIRV)
 }
 
+/// Creates a pass to convert the `cf` dialect to the `spirv` dialect.
+#[deprecated(note = "use `convert_cf_to_spirv` instead")]
+pub fn convert_scf_to_spirv() -> Pass {
+    convert_cf_to_spirv()
+}
+
 /// Creates a pass to convert the `math` dialect to the `spirv` dialect.
 pub fn convert_math_to_spirv() -> Pass {
     Pass::from_raw_fn(mlirCreateConversionConvertMathToSPIRV)
 }
 
-/// Creates a pass to convert the `math` dialect to the `libm` dialect.
+/// Creates a pass to convert the `math` dialect to `libm` calls.
 pub fn convert_math_to_libm() -> Pass {
     Pass::from_raw_fn(mlirCreateConversionConvertMathToLibm)
 }
+
+/// Creates a pass to convert the `affine` dialect to the `scf`, `memref`, and `arith` dialects.
+pub fn convert_affine_to_standard() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertAffineToStandard)
+}
+
+/// Creates a pass to convert the `arith` dialect to the `spirv` dialect.
+pub fn convert_arithmetic_to_spirv() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertArithmeticToSPIRV)
+}
+
+/// Creates a pass to convert the `async` dialect to the `llvm` dialect.
+pub fn convert_async_to_llvm() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertAsyncToLLVM)
+}
+
+/// Creates a pass to convert the `bufferization` dialect to the `memref` dialect.
+pub fn convert_bufferization_to_mem_ref() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertBufferizationToMemRef)
+}
+
+/// Creates a pass to convert the `complex` dialect to the `llvm` dialect.
+pub fn convert_complex_to_llvm() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertComplexToLLVM)
+}
+
+/// Creates a pass to convert the `complex` dialect to `libm` calls.
+pub fn convert_complex_to_libm() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertComplexToLibm)
+}
+
+/// Creates a pass to convert the `complex` dialect to standard dialects.
+pub fn convert_complex_to_standard() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertComplexToStandard)
+}
+
+/// Creates a pass to convert the `func` dialect to the `spirv` dialect.
+pub fn convert_func_to_spirv() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertFuncToSPIRV)
+}
+
+/// Creates a pass to convert the `gpu` dialect to the `spirv` dialect.
+pub fn convert_gpu_to_spirv() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertGPUToSPIRV)
+}
+
+/// Creates a pass to convert the `gpu` dialect to the `nvvm` dialect.
+pub fn convert_gpu_to_nvvm() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertGpuOpsToNVVMOps)
+}
+
+/// Creates a pass to convert the `gpu` dialect to the `rocdl` dialect.
+pub fn convert_gpu_to_rocdl() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertGpuOpsToROCDLOps)
+}
+
+/// Creates a pass to convert `gpu` dialect host code to the `llvm` dialect.
+pub fn convert_gpu_to_llvm() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionGpuToLLVMConversionPass)
+}
+
+/// Creates a pass to convert the `linalg` dialect to the `llvm` dialect.
+pub fn convert_linalg_to_llvm() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertLinalgToLLVM)
+}
+
+/// Creates a pass to convert the `linalg` dialect to standard dialects.
+pub fn convert_linalg_to_standard() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertLinalgToStandard)
+}
+
+/// Creates a pass to convert the `memref` dialect to the `llvm` dialect.
+pub fn convert_mem_ref_to_llvm() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertMemRefToLLVM)
+}
+
+/// Creates a pass to convert the `memref` dialect to the `spirv` dialect.
+pub fn convert_mem_ref_to_spirv() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertMemRefToSPIRV)
+}
+
+/// Creates a pass to convert the `omp` dialect to the `llvm` dialect.
+pub fn convert_open_mp_to_llvm() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertOpenMPToLLVM)
+}
+
+/// Creates a pass to convert parallel loops to the `gpu` dialect.
+pub fn convert_parallel_loop_to_gpu() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertParallelLoopToGpu)
+}
+
+/// Creates a pass to convert the `pdl` dialect to the `pdl_interp` dialect.
+pub fn convert_pdl_to_pdl_interp() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertPDLToPDLInterp)
+}
+
+/// Creates a pass to convert the `scf` dialect to the `cf` dialect.
+pub fn convert_scf_to_cf() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionSCFToControlFlow)
+}
+
+/// Creates a pass to convert the `scf` dialect to the `omp` dialect.
+pub fn convert_scf_to_open_mp() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertSCFToOpenMP)
+}
+
+/// Creates a pass to convert the `scf` dialect to the `spirv` dialect.
+// `convert_scf_to_spirv` is taken by a misnamed alias of `convert_cf_to_spirv`.
+pub fn convert_structured_control_flow_to_spirv() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionSCFToSPIRV)
+}
+
+/// Creates a pass to convert shape constraints of the `shape` dialect to the `cf` dialect.
+pub fn convert_shape_constraints() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertShapeConstraints)
+}
+
+/// Creates a pass to convert the `shape` dialect to standard dialects.
+pub fn convert_shape_to_standard() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertShapeToStandard)
+}
+
+/// Creates a pass to convert the `spirv` dialect to the `llvm` dialect.
+pub fn convert_spirv_to_llvm() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertSPIRVToLLVM)
+}
+
+/// Creates a pass to convert the `tensor` dialect to the `linalg` dialect.
+pub fn convert_tensor_to_linalg() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertTensorToLinalg)
+}
+
+/// Creates a pass to convert the `tensor` dialect to the `spirv` dialect.
+pub fn convert_tensor_to_spirv() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertTensorToSPIRV)
+}
+
+/// Creates a pass to convert the `tosa` dialect to the `arith` dialect.
+pub fn convert_tosa_to_arithmetic() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionTosaToArith)
+}
+
+/// Creates a pass to convert the `tosa` dialect to the `linalg` dialect.
+pub fn convert_tosa_to_linalg() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionTosaToLinalg)
+}
+
+/// Creates a pass to convert the `tosa` dialect to the `scf` dialect.
+pub fn convert_tosa_to_scf() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionTosaToSCF)
+}
+
+/// Creates a pass to convert the `vector` dialect to the `gpu` dialect.
+pub fn convert_vector_to_gpu() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertVectorToGPU)
+}
+
+/// Creates a pass to convert the `vector` dialect to the `llvm` dialect.
+pub fn convert_vector_to_llvm() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertVectorToLLVM)
+}
+
+/// Creates a pass to convert the `vector` dialect to the `scf` dialect.
+pub fn convert_vector_to_scf() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertVectorToSCF)
+}
+
+/// Creates a pass to convert the `vector` dialect to the `spirv` dialect.
+pub fn convert_vector_to_spirv() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionConvertVectorToSPIRV)
+}
+
+/// Creates a pass to reconcile unrealized cast operations.
+pub fn reconcile_unrealized_casts() -> Pass {
+    Pass::from_raw_fn(mlirCreateConversionReconcileUnrealizedCasts)
+}
@@ -0,0 +1,177 @@
+use crate::{
+    pass::{FuncOp, Manager, Pipeline, PipelinePass},
+    Error,
+};
+
+// TODO Populate conversion patterns and type converters directly.
+/// Options of conversion from the `func`, `arith`, `math`, `cf`, `scf`, and
+/// `memref` dialects to the `llvm` dialect.
+///
+/// Its default options form the lowering pipeline of the presets in
+/// [`preset`](crate::pass::preset).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LlvmOptions {
+    index_bitwidth: Option<u32>,
+    bare_pointer_memref_call_convention: bool,
+    aligned_allocation: bool,
+    data_layout: Option<String>,
+}
+
+impl LlvmOptions {
+    /// Creates options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a bit width of the `index` type.
+    ///
+    /// The bit width is derived from a data layout by default.
+    pub fn index_bitwidth(mut self, bitwidth: u32) -> Self {
+        self.index_bitwidth = Some(bitwidth);
+        self
+    }
+
+    /// Enables a calling convention passing memrefs as bare pointers.
+    pub fn bare_pointer_memref_call_convention(mut self, enabled: bool) -> Self {
+        self.bare_pointer_memref_call_convention = enabled;
+        self
+    }
+
+    /// Enables aligned allocation of memrefs.
+    pub fn aligned_allocation(mut self, enabled: bool) -> Self {
+        self.aligned_allocation = enabled;
+        self
+    }
+
+    /// Sets a data layout in the LLVM format.
+    pub fn data_layout(mut self, layout: &str) -> Self {
+        self.data_layout = Some(layout.into());
+        self
+    }
+
+    /// Gets a pass pipeline.
+    pub fn pipeline(&self) -> Pipeline {
+        let with_index_bitwidth = |pass: PipelinePass| match self.index_bitwidth {
+            Some(bitwidth) => pass.with("index-bitwidth", bitwidth),
+            None => pass,
+        };
+        let mut memref_pass = with_index_bitwidth(PipelinePass::convert_memref_to_llvm());
+        let mut func_pass = with_index_bitwidth(PipelinePass::convert_func_to_llvm());
+
+        if self.aligned_allocation {
+            memref_pass = memref_pass.with("use-aligned-alloc", true);
+        }
+
+        if self.bare_pointer_memref_call_convention {
+            func_pass = func_pass.with("use-bare-ptr-memref-call-conv", true);
+        }
+
+        if let Some(layout) = &self.data_layout {
+            func_pass = func_pass.with("data-layout", layout);
+        }
+
+        Pipeline::new()
+            .add_pass(PipelinePass::convert_scf_to_cf())
+            .nested::<FuncOp>(
+                Pipeline::from(with_index_bitwidth(PipelinePass::convert_arith_to_llvm()))
+                    .add_pass(PipelinePass::convert_math_to_llvm()),
+            )
+            .add_pass(memref_pass)
+            .add_pass(with_index_bitwidth(PipelinePass::convert_cf_to_llvm()))
+            .add_pass(func_pass)
+            .add_pass(PipelinePass::reconcile_unrealized_casts())
+    }
+}
+
+/// Adds passes to convert modules to the `llvm` dialect with options.
+pub fn add_llvm_conversion_passes(manager: &Manager, options: &LlvmOptions) -> Result<(), Error> {
+    options.pipeline().add_to(manager)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ir::Module, Context};
+
+    fn convert<'c>(context: &'c Context, source: &str, options: &LlvmOptions) -> Module<'c> {
+        let mut module = Module::parse(context, source).unwrap();
+        let manager = Manager::new(context);
+
+        add_llvm_conversion_passes(&manager, options).unwrap();
+
+        assert_eq!(manager.run(&mut module), Ok(()));
+        assert!(module.as_operation().verify());
+
+        module
+    }
+
+    #[test]
+    fn pipeline() {
+        assert_eq!(
+            LlvmOptions::new().pipeline().to_string(),
+            "convert-scf-to-cf,func.func(convert-arith-to-llvm,convert-math-to-llvm),\
+            convert-memref-to-llvm,convert-cf-to-llvm,convert-func-to-llvm,reconcile-unrealized-casts"
+        );
+    }
+
+    #[test]
+    fn pipeline_with_options() {
+        assert_eq!(
+            LlvmOptions::new()
+                .index_bitwidth(32)
+                .bare_pointer_memref_call_convention(true)
+                .pipeline()
+                .to_string(),
+            "convert-scf-to-cf,\
+            func.func(convert-arith-to-llvm{index-bitwidth=32},convert-math-to-llvm),\
+            convert-memref-to-llvm{index-bitwidth=32},convert-cf-to-llvm{index-bitwidth=32},\
+            convert-func-to-llvm{index-bitwidth=32 use-bare-ptr-memref-call-conv=true},\
+            reconcile-unrealized-casts"
+        );
+    }
+
+    #[test]
+    fn convert_with_index_bitwidth() {
+        let context = Context::new();
+        let module = convert(
+            &context,
+            "func.func @foo(%arg0 : index) -> index { return %arg0 : index }",
+            &LlvmOptions::new().index_bitwidth(32),
+        );
+
+        assert!(module
+            .as_operation()
+            .to_string()
+            .contains("llvm.func @foo(%arg0: i32) -> i32"));
+    }
+
+    #[test]
+    fn convert_with_bare_pointer_memref_call_convention() {
+        let context = Context::new();
+        let module = convert(
+            &context,
+            "func.func @foo(%arg0 : memref<4xf32>) { return }",
+            &LlvmOptions::new().bare_pointer_memref_call_convention(true),
+        );
+
+        assert!(module
+            .as_operation()
+            .to_string()
+            .contains("llvm.func @foo(%arg0: !llvm.ptr<f32>)"));
+    }
+
+    #[test]
+    fn convert_with_data_layout() {
+        let context = Context::new();
+        let module = convert(
+            &context,
+            "func.func @foo() { return }",
+            &LlvmOptions::new().data_layout("e-p:32:32"),
+        );
+
+        assert!(module
+            .as_operation()
+            .to_string()
+            .contains("llvm.data_layout = \"e-p:32:32\""));
+    }
+}
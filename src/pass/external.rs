@@ -0,0 +1,318 @@
+use super::Pass;
+use crate::{
+    context::ContextRef,
+    dialect,
+    ir::{r#type, OperationRef},
+    logical_result::LogicalResult,
+    string_ref::StringRef,
+};
+use mlir_sys::{
+    mlirCreateExternalPass, mlirExternalPassSignalFailure, MlirContext, MlirExternalPass,
+    MlirExternalPassCallbacks, MlirLogicalResult, MlirOperation,
+};
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashSet,
+    ffi::c_void,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::RwLock,
+};
+
+// MLIR keeps references to operation names in external passes, so the names
+// are leaked for the lifetime of a process. Each distinct name is leaked once.
+static OPERATION_NAMES: Lazy<RwLock<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+/// A pass implemented in Rust.
+///
+/// Passes are cloned when pass managers run them on multiple operations in
+/// parallel on worker threads. A panic in a pass is reported as a failure of
+/// the pass.
+pub trait RunnablePass: Clone + Send + 'static {
+    /// Initializes a pass before it is run.
+    #[allow(clippy::result_unit_err)]
+    fn initialize(&mut self, _context: ContextRef) -> Result<(), ()> {
+        Ok(())
+    }
+
+    /// Runs a pass on an operation.
+    #[allow(clippy::result_unit_err)]
+    fn run(&mut self, operation: OperationRef, context: ContextRef) -> Result<(), ()>;
+}
+
+/// Creates a pass from a pass implemented in Rust.
+///
+/// A type ID must be unique for each kind of passes. An operation name is
+/// leaked for the lifetime of a process because MLIR keeps a reference to it.
+pub fn create_external<T: RunnablePass>(
+    pass: T,
+    id: r#type::Id,
+    name: &str,
+    argument: &str,
+    description: &str,
+    operation_name: &str,
+    dependent_dialects: &[dialect::Handle],
+) -> Pass {
+    let mut dependent_dialects = dependent_dialects
+        .iter()
+        .map(|handle| unsafe { handle.to_raw() })
+        .collect::<Vec<_>>();
+
+    unsafe {
+        Pass::from_raw(mlirCreateExternalPass(
+            id.to_raw(),
+            StringRef::from(name).to_raw(),
+            StringRef::from(argument).to_raw(),
+            StringRef::from(description).to_raw(),
+            StringRef::from(intern_operation_name(operation_name)).to_raw(),
+            dependent_dialects.len() as isize,
+            dependent_dialects.as_mut_ptr(),
+            MlirExternalPassCallbacks {
+                construct: Some(construct),
+                destruct: Some(destruct::<T>),
+                initialize: Some(initialize::<T>),
+                clone: Some(clone::<T>),
+                run: Some(run::<T>),
+            },
+            Box::into_raw(Box::new(pass)) as *mut c_void,
+        ))
+    }
+}
+
+fn intern_operation_name(name: &str) -> &'static str {
+    let interned = OPERATION_NAMES.read().unwrap().get(name).copied();
+
+    interned.unwrap_or_else(|| {
+        let mut names = OPERATION_NAMES.write().unwrap();
+
+        if let Some(&interned) = names.get(name) {
+            interned
+        } else {
+            let interned: &'static str = Box::leak(name.into());
+            names.insert(interned);
+            interned
+        }
+    })
+}
+
+unsafe extern "C" fn construct(_pass: *mut c_void) {}
+
+unsafe extern "C" fn destruct<T: RunnablePass>(pass: *mut c_void) {
+    drop(Box::from_raw(pass as *mut T));
+}
+
+unsafe extern "C" fn initialize<T: RunnablePass>(
+    context: MlirContext,
+    pass: *mut c_void,
+) -> MlirLogicalResult {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        (*(pass as *mut T)).initialize(ContextRef::from_raw(context))
+    }));
+
+    if matches!(result, Ok(Ok(()))) {
+        LogicalResult::success()
+    } else {
+        LogicalResult::failure()
+    }
+    .to_raw()
+}
+
+unsafe extern "C" fn clone<T: RunnablePass>(pass: *mut c_void) -> *mut c_void {
+    Box::into_raw(Box::new((*(pass as *mut T)).clone())) as *mut c_void
+}
+
+unsafe extern "C" fn run<T: RunnablePass>(
+    operation: MlirOperation,
+    external_pass: MlirExternalPass,
+    pass: *mut c_void,
+) {
+    let operation = OperationRef::from_raw(operation);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        (*(pass as *mut T)).run(operation, operation.context())
+    }));
+
+    if !matches!(result, Ok(Ok(()))) {
+        mlirExternalPassSignalFailure(external_pass);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::Context,
+        ir::{
+            walk::{WalkOrder, WalkResult},
+            Module,
+        },
+        pass::Manager,
+        Error,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Clone, Default)]
+    struct CountPass {
+        initialization_count: Arc<AtomicUsize>,
+        operation_count: Arc<AtomicUsize>,
+    }
+
+    impl RunnablePass for CountPass {
+        fn initialize(&mut self, _context: ContextRef) -> Result<(), ()> {
+            self.initialization_count.fetch_add(1, Ordering::SeqCst);
+
+            Ok(())
+        }
+
+        fn run(&mut self, operation: OperationRef, _context: ContextRef) -> Result<(), ()> {
+            operation.walk(WalkOrder::PreOrder, |_| {
+                self.operation_count.fetch_add(1, Ordering::SeqCst);
+                WalkResult::Advance
+            });
+
+            Ok(())
+        }
+    }
+
+    #[derive(Clone)]
+    struct FailPass;
+
+    impl RunnablePass for FailPass {
+        fn run(&mut self, _operation: OperationRef, _context: ContextRef) -> Result<(), ()> {
+            Err(())
+        }
+    }
+
+    #[derive(Clone)]
+    struct PanicPass;
+
+    impl RunnablePass for PanicPass {
+        fn run(&mut self, _operation: OperationRef, _context: ContextRef) -> Result<(), ()> {
+            panic!("foo")
+        }
+    }
+
+    #[test]
+    fn run() {
+        let context = Context::new();
+        let mut module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0 : i32) -> i32 {
+                %res = arith.addi %arg0, %arg0 : i32
+                return %res : i32
+            }
+            "#,
+        )
+        .unwrap();
+        let pass = CountPass::default();
+        let mut allocator = r#type::id::Allocator::new();
+
+        let manager = Manager::new(&context);
+        manager.nested_under("func.func").add_pass(create_external(
+            pass.clone(),
+            allocator.allocate_type_id(),
+            "count",
+            "count",
+            "Counts operations",
+            "func.func",
+            &[dialect::Handle::func()],
+        ));
+
+        assert_eq!(manager.run(&mut module), Ok(()));
+        assert_eq!(pass.initialization_count.load(Ordering::SeqCst), 1);
+        assert_eq!(pass.operation_count.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn display() {
+        let context = Context::new();
+        let mut allocator = r#type::id::Allocator::new();
+
+        let manager = Manager::new(&context);
+        manager.add_pass(create_external(
+            CountPass::default(),
+            allocator.allocate_type_id(),
+            "count",
+            "count",
+            "Counts operations",
+            "",
+            &[],
+        ));
+
+        assert_eq!(manager.to_string(), "builtin.module(count)");
+    }
+
+    #[test]
+    fn run_failure() {
+        let context = Context::new();
+        let mut module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0 : i32) -> i32 {
+                %res = arith.addi %arg0, %arg0 : i32
+                return %res : i32
+            }
+            "#,
+        )
+        .unwrap();
+        let mut allocator = r#type::id::Allocator::new();
+
+        let manager = Manager::new(&context);
+        manager.add_pass(create_external(
+            FailPass,
+            allocator.allocate_type_id(),
+            "fail",
+            "fail",
+            "Fails",
+            "",
+            &[],
+        ));
+
+        assert!(matches!(manager.run(&mut module), Err(Error::RunPass(_))));
+    }
+
+    #[test]
+    fn run_panic() {
+        let context = Context::new();
+        let mut module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0 : i32) -> i32 {
+                %res = arith.addi %arg0, %arg0 : i32
+                return %res : i32
+            }
+            "#,
+        )
+        .unwrap();
+        let mut allocator = r#type::id::Allocator::new();
+
+        let manager = Manager::new(&context);
+        manager.add_pass(create_external(
+            PanicPass,
+            allocator.allocate_type_id(),
+            "panic",
+            "panic",
+            "Panics",
+            "",
+            &[],
+        ));
+
+        assert!(matches!(manager.run(&mut module), Err(Error::RunPass(_))));
+    }
+
+    #[test]
+    fn intern_operation_name() {
+        let name = String::from("foo");
+        let interned = super::intern_operation_name(&name);
+
+        drop(name);
+
+        assert_eq!(interned, "foo");
+        assert_eq!(
+            super::intern_operation_name("foo").as_ptr(),
+            interned.as_ptr()
+        );
+    }
+}
@@ -1,6 +1,11 @@
 use super::OperationManager;
 use crate::{
-    context::Context, ir::Module, logical_result::LogicalResult, pass::Pass, string_ref::StringRef,
+    context::Context,
+    ir::{generation, Module},
+    logical_result::LogicalResult,
+    pass::Pass,
+    string_ref::StringRef,
+    utility::capture_diagnostics,
     Error,
 };
 use mlir_sys::{
@@ -9,7 +14,10 @@ use mlir_sys::{
     mlirPassManagerGetAsOpPassManager, mlirPassManagerGetNestedUnder, mlirPassManagerRun,
     MlirPassManager,
 };
-use std::marker::PhantomData;
+use std::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+};
 
 /// A pass manager.
 pub struct Manager<'c> {
@@ -19,7 +27,7 @@ pub struct Manager<'c> {
 
 impl<'c> Manager<'c> {
     /// Creates a pass manager.
-    pub fn new(context: &Context) -> Self {
+    pub fn new(context: &'c Context) -> Self {
         Self {
             raw: unsafe { mlirPassManagerCreate(context.to_raw()) },
             _context: Default::default(),
@@ -53,14 +61,26 @@ impl<'c> Manager<'c> {
     }
 
     /// Runs passes added to a pass manager against a module.
+    ///
+    /// A pass manager keeps no state of modules between runs. Reuse a pass
+    /// manager to run the same pipeline on many modules rather than building
+    /// the pipeline for each of them.
+    ///
+    /// Diagnostics reported by failed passes are attached to an error.
+    /// References to operations, blocks, and regions in the module are
+    /// invalidated because passes might destroy them.
     pub fn run(&self, module: &mut Module) -> Result<(), Error> {
-        let result =
-            LogicalResult::from_raw(unsafe { mlirPassManagerRun(self.raw, module.to_raw()) });
+        // Passes might destroy any operations, blocks, or regions in a module.
+        unsafe { generation::invalidate_nested(module.as_operation().to_raw()) }
+
+        let (result, diagnostics) = capture_diagnostics(module.context(), || {
+            LogicalResult::from_raw(unsafe { mlirPassManagerRun(self.raw, module.to_raw()) })
+        });
 
         if result.is_success() {
             Ok(())
         } else {
-            Err(Error::RunPass)
+            Err(Error::RunPass(diagnostics))
         }
     }
 
@@ -70,6 +90,12 @@ impl<'c> Manager<'c> {
     }
 }
 
+impl<'c> Display for Manager<'c> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.as_operation_pass_manager().fmt(formatter)
+    }
+}
+
 impl<'c> Drop for Manager<'c> {
     fn drop(&mut self) {
         unsafe { mlirPassManagerDestroy(self.raw) }
@@ -135,6 +161,25 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn run_on_many_modules() {
+        let context = Context::new();
+        let manager = Manager::new(&context);
+
+        manager.add_pass(pass::conversion::convert_func_to_llvm());
+
+        for name in ["foo", "bar", "baz"] {
+            let mut module =
+                Module::parse(&context, &format!("func.func @{}() {{ return }}", name)).unwrap();
+
+            assert_eq!(manager.run(&mut module), Ok(()));
+            assert!(module
+                .as_operation()
+                .to_string()
+                .contains(&format!("llvm.func @{}()", name)));
+        }
+    }
+
     #[test]
     fn run_on_function() {
         let context = Context::new();
@@ -220,6 +265,26 @@ mod tests {
         assert_eq!(function_manager.to_string(), "print-op-stats{json=false}");
     }
 
+    #[test]
+    fn display() {
+        let context = Context::new();
+        let manager = Manager::new(&context);
+        let function_manager = manager.nested_under("func.func");
+
+        function_manager.add_pass(pass::transform::canonicalizer());
+        function_manager.add_pass(pass::transform::cse());
+        manager.add_pass(pass::transform::symbol_dce());
+
+        assert_eq!(
+            manager.to_string(),
+            manager.as_operation_pass_manager().to_string()
+        );
+        assert!(manager
+            .to_string()
+            .starts_with("builtin.module(func.func(canonicalize"));
+        assert!(manager.to_string().ends_with("symbol-dce)"));
+    }
+
     #[test]
     fn parse_pass_pipeline_() {
         let context = Context::new();
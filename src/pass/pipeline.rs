@@ -0,0 +1,358 @@
+use super::Manager;
+use crate::{
+    context::Context,
+    ir::Module,
+    utility::{parse_pass_pipeline, register_all_passes},
+    Error,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// A pass with options in a pass pipeline.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PipelinePass {
+    name: String,
+    options: Vec<(String, String)>,
+}
+
+impl PipelinePass {
+    /// Creates a pass of a name, e.g. `canonicalize`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            options: vec![],
+        }
+    }
+
+    /// Creates a `canonicalize` pass.
+    pub fn canonicalize() -> Self {
+        Self::new("canonicalize")
+    }
+
+    /// Creates a `cse` pass.
+    pub fn cse() -> Self {
+        Self::new("cse")
+    }
+
+    /// Creates an `inline` pass.
+    pub fn inline() -> Self {
+        Self::new("inline")
+    }
+
+    /// Creates a `loop-invariant-code-motion` pass.
+    pub fn loop_invariant_code_motion() -> Self {
+        Self::new("loop-invariant-code-motion")
+    }
+
+    /// Creates a `sccp` pass.
+    pub fn sccp() -> Self {
+        Self::new("sccp")
+    }
+
+    /// Creates a `strip-debuginfo` pass.
+    pub fn strip_debug_info() -> Self {
+        Self::new("strip-debuginfo")
+    }
+
+    /// Creates a `symbol-dce` pass.
+    pub fn symbol_dce() -> Self {
+        Self::new("symbol-dce")
+    }
+
+    /// Creates a `convert-arith-to-llvm` pass.
+    pub fn convert_arith_to_llvm() -> Self {
+        Self::new("convert-arith-to-llvm")
+    }
+
+    /// Creates a `convert-cf-to-llvm` pass.
+    pub fn convert_cf_to_llvm() -> Self {
+        Self::new("convert-cf-to-llvm")
+    }
+
+    /// Creates a `convert-func-to-llvm` pass.
+    pub fn convert_func_to_llvm() -> Self {
+        Self::new("convert-func-to-llvm")
+    }
+
+    /// Creates a `convert-math-to-llvm` pass.
+    pub fn convert_math_to_llvm() -> Self {
+        Self::new("convert-math-to-llvm")
+    }
+
+    /// Creates a `convert-memref-to-llvm` pass.
+    pub fn convert_memref_to_llvm() -> Self {
+        Self::new("convert-memref-to-llvm")
+    }
+
+    /// Creates a `convert-scf-to-cf` pass.
+    pub fn convert_scf_to_cf() -> Self {
+        Self::new("convert-scf-to-cf")
+    }
+
+    /// Creates a `reconcile-unrealized-casts` pass.
+    pub fn reconcile_unrealized_casts() -> Self {
+        Self::new("reconcile-unrealized-casts")
+    }
+
+    /// Sets an option.
+    ///
+    /// Values are quoted if they contain spaces or punctuation of pass
+    /// pipelines. The parser of MLIR 15 has no escape sequences, so values
+    /// with unbalanced braces or both kinds of quotes fail to be parsed.
+    pub fn with(mut self, name: &str, value: impl Display) -> Self {
+        self.options.push((name.into(), value.to_string()));
+        self
+    }
+}
+
+impl Display for PipelinePass {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.name)?;
+
+        if self.options.is_empty() {
+            return Ok(());
+        }
+
+        write!(formatter, "{{")?;
+
+        for (index, (name, value)) in self.options.iter().enumerate() {
+            if index > 0 {
+                write!(formatter, " ")?;
+            }
+
+            if !value.contains(|character: char| {
+                character.is_whitespace() || matches!(character, '"' | '\'' | ',' | '{' | '}')
+            }) {
+                write!(formatter, "{}={}", name, value)?;
+            } else if value.contains('"') {
+                write!(formatter, "{}='{}'", name, value)?;
+            } else {
+                write!(formatter, "{}=\"{}\"", name, value)?;
+            }
+        }
+
+        write!(formatter, "}}")
+    }
+}
+
+/// An operation which pass pipelines can be nested under.
+pub trait OperationName {
+    /// An operation name, e.g. `func.func`.
+    const NAME: &'static str;
+}
+
+/// A `builtin.module` operation.
+#[derive(Clone, Copy, Debug)]
+pub struct ModuleOp;
+
+impl OperationName for ModuleOp {
+    const NAME: &'static str = "builtin.module";
+}
+
+/// A `func.func` operation.
+#[derive(Clone, Copy, Debug)]
+pub struct FuncOp;
+
+impl OperationName for FuncOp {
+    const NAME: &'static str = "func.func";
+}
+
+/// A `gpu.module` operation.
+#[derive(Clone, Copy, Debug)]
+pub struct GpuModuleOp;
+
+impl OperationName for GpuModuleOp {
+    const NAME: &'static str = "gpu.module";
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Element {
+    Pass(PipelinePass),
+    Nested(String, Pipeline),
+}
+
+/// A pass pipeline builder.
+///
+/// It builds a textual pass pipeline which is validated when it is converted
+/// into a pass manager.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Pipeline {
+    elements: Vec<Element>,
+}
+
+impl Pipeline {
+    /// Creates a pass pipeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pass.
+    pub fn add_pass(mut self, pass: PipelinePass) -> Self {
+        self.elements.push(Element::Pass(pass));
+        self
+    }
+
+    /// Adds a pass pipeline for nested operations of a type.
+    pub fn nested<T: OperationName>(self, pipeline: impl Into<Pipeline>) -> Self {
+        self.nested_under(T::NAME, pipeline.into())
+    }
+
+    /// Adds a pass pipeline for nested operations of a name, e.g.
+    /// `func.func`.
+    pub fn nested_under(mut self, name: &str, pipeline: Pipeline) -> Self {
+        self.elements.push(Element::Nested(name.into(), pipeline));
+        self
+    }
+
+    /// Creates a pass manager of a pass pipeline.
+    ///
+    /// All passes are registered before the pipeline is parsed.
+    pub fn to_manager<'c>(&self, context: &'c Context) -> Result<Manager<'c>, Error> {
+        let manager = Manager::new(context);
+
+        self.add_to(&manager)?;
+
+        Ok(manager)
+    }
+
+    /// Adds passes of a pass pipeline to a pass manager of modules.
+    ///
+    /// All passes are registered before the pipeline is parsed.
+    pub fn add_to(&self, manager: &Manager) -> Result<(), Error> {
+        register_all_passes();
+        parse_pass_pipeline(
+            manager.as_operation_pass_manager(),
+            &format!("builtin.module({})", self),
+        )
+    }
+
+    /// Runs a pass pipeline against a module.
+    pub fn run(&self, module: &mut Module) -> Result<(), Error> {
+        module
+            .context()
+            .with_context(|context| self.to_manager(context)?.run(module))
+    }
+}
+
+impl From<PipelinePass> for Pipeline {
+    fn from(pass: PipelinePass) -> Self {
+        Self::new().add_pass(pass)
+    }
+}
+
+impl Display for Pipeline {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        for (index, element) in self.elements.iter().enumerate() {
+            if index > 0 {
+                write!(formatter, ",")?;
+            }
+
+            match element {
+                Element::Pass(pass) => write!(formatter, "{}", pass)?,
+                Element::Nested(name, pipeline) => write!(formatter, "{}({})", name, pipeline)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Pipeline::new()
+                .nested::<FuncOp>(PipelinePass::canonicalize().with("top-down", true))
+                .add_pass(PipelinePass::cse())
+                .to_string(),
+            "func.func(canonicalize{top-down=true}),cse"
+        );
+    }
+
+    #[test]
+    fn display_options() {
+        assert_eq!(
+            PipelinePass::new("foo")
+                .with("bar", 42)
+                .with("baz", "a b")
+                .to_string(),
+            "foo{bar=42 baz=\"a b\"}"
+        );
+    }
+
+    #[test]
+    fn display_punctuated_options() {
+        assert_eq!(
+            PipelinePass::new("foo")
+                .with("bar", "a,b")
+                .with("baz", "{}")
+                .with("qux", "\"a\"")
+                .to_string(),
+            "foo{bar=\"a,b\" baz=\"{}\" qux='\"a\"'}"
+        );
+    }
+
+    #[test]
+    fn display_nested() {
+        assert_eq!(
+            Pipeline::new()
+                .nested::<GpuModuleOp>(
+                    Pipeline::new().nested_under("gpu.func", PipelinePass::cse().into())
+                )
+                .to_string(),
+            "gpu.module(gpu.func(cse))"
+        );
+    }
+
+    #[test]
+    fn to_manager() {
+        let context = Context::new();
+
+        assert!(Pipeline::new()
+            .nested::<FuncOp>(PipelinePass::canonicalize().with("top-down", true))
+            .add_pass(PipelinePass::cse())
+            .to_manager(&context)
+            .unwrap()
+            .to_string()
+            .starts_with("builtin.module(func.func(canonicalize{"));
+    }
+
+    #[test]
+    fn to_manager_with_unknown_pass() {
+        let context = Context::new();
+
+        assert!(matches!(
+            Pipeline::new()
+                .add_pass(PipelinePass::new("foo"))
+                .to_manager(&context),
+            Err(Error::ParsePassPipeline)
+        ));
+    }
+
+    #[test]
+    fn run() {
+        let context = Context::new();
+        let mut module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0 : i32) -> i32 {
+                %zero = arith.constant 0 : i32
+                %res = arith.addi %arg0, %zero : i32
+                return %res : i32
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Pipeline::new()
+                .nested::<FuncOp>(PipelinePass::canonicalize().with("top-down", true))
+                .add_pass(PipelinePass::cse())
+                .run(&mut module),
+            Ok(())
+        );
+        assert!(!module.as_operation().to_string().contains("arith.addi"));
+    }
+}
@@ -0,0 +1,267 @@
+//! Preset pass pipelines.
+//!
+//! Each preset configures a pass manager for a common target. Their pass
+//! lists follow the lowering pipelines of MLIR 15 and might change as new
+//! versions of MLIR are supported.
+//!
+//! No preset strips debug information, so file locations of operations are
+//! preserved through lowering into the LLVM dialect.
+
+use super::{conversion, r#async, transform, Manager};
+use crate::context::Context;
+
+/// Creates a pass manager to lower modules for the execution engine on CPUs.
+///
+/// It runs the following passes in order.
+///
+/// - `func.func(canonicalize, cse)`
+/// - `convert-scf-to-cf`
+/// - `func.func(convert-arith-to-llvm, convert-math-to-llvm)`
+/// - `convert-memref-to-llvm`
+/// - `convert-cf-to-llvm`
+/// - `convert-func-to-llvm`
+/// - `reconcile-unrealized-casts`
+pub fn cpu_jit(context: &Context) -> Manager {
+    let manager = Manager::new(context);
+    let function_manager = manager.nested_under("func.func");
+
+    function_manager.add_pass(transform::canonicalizer());
+    function_manager.add_pass(transform::cse());
+    add_llvm_conversion_passes(&manager);
+
+    manager
+}
+
+/// Creates a pass manager to lower modules for the execution engine on CPUs
+/// with parallel loops run by OpenMP.
+///
+/// It runs the following passes in order. Shared libraries of an OpenMP
+/// runtime need to be passed to the execution engine.
+///
+/// - `func.func(canonicalize, cse)`
+/// - `convert-scf-to-openmp`
+/// - `convert-scf-to-cf`
+/// - `func.func(convert-arith-to-llvm, convert-math-to-llvm)`
+/// - `convert-memref-to-llvm`
+/// - `convert-cf-to-llvm`
+/// - `convert-func-to-llvm`
+/// - `convert-openmp-to-llvm`
+/// - `reconcile-unrealized-casts`
+pub fn cpu_openmp(context: &Context) -> Manager {
+    let manager = Manager::new(context);
+    let function_manager = manager.nested_under("func.func");
+
+    function_manager.add_pass(transform::canonicalizer());
+    function_manager.add_pass(transform::cse());
+    manager.add_pass(conversion::convert_scf_to_open_mp());
+    add_llvm_conversion_passes(&manager);
+    manager.add_pass(conversion::convert_open_mp_to_llvm());
+    // Casts introduced by the OpenMP conversion need to be reconciled again.
+    manager.add_pass(conversion::reconcile_unrealized_casts());
+
+    manager
+}
+
+/// Creates a pass manager to lower modules to the `llvm` dialect.
+///
+/// It runs the following passes in order.
+///
+/// - `convert-scf-to-cf`
+/// - `func.func(convert-arith-to-llvm, convert-math-to-llvm)`
+/// - `convert-memref-to-llvm`
+/// - `convert-cf-to-llvm`
+/// - `convert-func-to-llvm`
+/// - `reconcile-unrealized-casts`
+pub fn lower_to_llvm(context: &Context) -> Manager {
+    let manager = Manager::new(context);
+
+    add_llvm_conversion_passes(&manager);
+
+    manager
+}
+
+/// Creates a pass manager to lower modules with `async` operations to the
+/// `llvm` dialect.
+///
+/// It runs the following passes in order. Modules lowered by it call
+/// functions of the async runtime of MLIR, whose shared library needs to be
+/// passed to the execution engine.
+///
+/// - `async-to-async-runtime`
+/// - `async-runtime-ref-counting`
+/// - `async-runtime-ref-counting-opt`
+/// - `convert-async-to-llvm`
+/// - passes of [`lower_to_llvm`]
+pub fn async_lowering(context: &Context) -> Manager {
+    let manager = Manager::new(context);
+
+    manager.add_pass(r#async::async_to_async_runtime());
+    manager.add_pass(r#async::async_runtime_ref_counting());
+    manager.add_pass(r#async::async_runtime_ref_counting_opt());
+    manager.add_pass(conversion::convert_async_to_llvm());
+    add_llvm_conversion_passes(&manager);
+
+    manager
+}
+
+/// Creates a pass manager to lower modules for the execution engine on CPUs
+/// while keeping them close to their original forms.
+///
+/// It runs the same conversion passes as [`cpu_jit`] without any
+/// optimizations. Modules are verified after each pass as pass managers do by
+/// default, and [`Manager::enable_ir_printing`] can be called to print them as
+/// well.
+///
+/// The context is not changed. Disable its multi-threading with
+/// [`Context::enable_multi_threading`] so that passes run one by one and
+/// report diagnostics in a deterministic order.
+pub fn debug_friendly(context: &Context) -> Manager {
+    let manager = Manager::new(context);
+
+    add_llvm_conversion_passes(&manager);
+
+    manager
+}
+
+fn add_llvm_conversion_passes(manager: &Manager) {
+    // Default options always form a valid pipeline.
+    conversion::add_llvm_conversion_passes(manager, &conversion::LlvmOptions::default()).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{operation::PrintingFlags, Module},
+        utility::register_all_llvm_translations,
+        ExecutionEngine,
+    };
+
+    const SOURCE: &str = r#"
+        func.func @sum(%arg0 : index) -> index attributes { llvm.emit_c_interface } {
+            %zero = arith.constant 0 : index
+            %one = arith.constant 1 : index
+            %res = scf.for %i = %zero to %arg0 step %one iter_args(%acc = %zero) -> index {
+                %next = arith.addi %acc, %i : index
+                scf.yield %next : index
+            }
+            return %res : index
+        }
+    "#;
+
+    fn run<'c>(manager: Manager, context: &'c Context) -> Module<'c> {
+        let mut module = Module::parse_with_filename(context, SOURCE, "sum.mlir").unwrap();
+
+        assert_eq!(manager.run(&mut module), Ok(()));
+        assert!(module.as_operation().verify());
+        assert!(module.as_operation().to_string().contains("llvm.func @sum"));
+
+        module
+    }
+
+    #[test]
+    fn cpu_jit() {
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+
+        let module = run(super::cpu_jit(&context), &context);
+        let engine = ExecutionEngine::new(&module, 2, &[]);
+        let mut argument = 5_isize;
+        let mut result = -1_isize;
+
+        assert_eq!(
+            unsafe {
+                engine.invoke_packed(
+                    "sum",
+                    &mut [
+                        &mut argument as *mut isize as *mut (),
+                        &mut result as *mut isize as *mut (),
+                    ],
+                )
+            },
+            Ok(())
+        );
+        assert_eq!(result, 10);
+    }
+
+    #[test]
+    fn preserve_locations() {
+        let context = Context::new();
+        let module = run(super::cpu_jit(&context), &context);
+
+        assert!(module
+            .as_operation()
+            .to_string_with_flags(&PrintingFlags::new().enable_debug_info(true, false))
+            .contains("loc(\"sum.mlir\":2:9)"));
+    }
+
+    #[test]
+    fn cpu_openmp() {
+        let context = Context::new();
+        let mut module = Module::parse(
+            &context,
+            r#"
+            func.func @fill(%arg0 : memref<4xf32>, %arg1 : f32) {
+                %zero = arith.constant 0 : index
+                %one = arith.constant 1 : index
+                %four = arith.constant 4 : index
+                scf.parallel (%i) = (%zero) to (%four) step (%one) {
+                    memref.store %arg1, %arg0[%i] : memref<4xf32>
+                    scf.yield
+                }
+                return
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(super::cpu_openmp(&context).run(&mut module), Ok(()));
+        assert!(module.as_operation().verify());
+
+        let source = module.as_operation().to_string();
+
+        assert!(source.contains("llvm.func @fill"));
+        assert!(source.contains("omp.parallel"));
+        assert!(!source.contains("scf.parallel"));
+    }
+
+    #[test]
+    fn lower_to_llvm() {
+        let context = Context::new();
+
+        run(super::lower_to_llvm(&context), &context);
+    }
+
+    #[test]
+    fn async_lowering() {
+        let context = Context::new();
+        let mut module = Module::parse(
+            &context,
+            r#"
+            func.func @main() {
+                %token = async.execute {
+                    async.yield
+                }
+                async.await %token : !async.token
+                return
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(super::async_lowering(&context).run(&mut module), Ok(()));
+        assert!(module.as_operation().verify());
+        assert!(module
+            .as_operation()
+            .to_string()
+            .contains("llvm.call @mlirAsyncRuntimeAwaitToken"));
+    }
+
+    #[test]
+    fn debug_friendly() {
+        let context = Context::new();
+        context.enable_multi_threading(false);
+
+        run(super::debug_friendly(&context), &context);
+    }
+}
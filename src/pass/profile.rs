@@ -0,0 +1,454 @@
+//! Profiling of JIT-compiled functions.
+//!
+//! [`Profiler::instrument_functions`] creates a pass which inserts calls to
+//! host callbacks at entries and exits of `func.func` operations. After the
+//! callbacks are registered to an execution engine with [`register`], wall
+//! times of function calls are collected into a table of the profiler.
+
+use super::{create_external, Pass, RunnablePass};
+use crate::{
+    context::{Context, ContextRef},
+    dialect,
+    ir::{
+        operation, r#type::Function, Attribute, Identifier, Location, Operation, OperationRef,
+        Region, Type, TypeLike,
+    },
+    string_ref::StringRef,
+    ExecutionEngine,
+};
+use mlir_sys::{mlirStringAttrGetValue, mlirTypeAttrGet, mlirTypeIDCreate};
+use once_cell::sync::Lazy;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::c_void,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// A symbol name of a callback called at entries of functions.
+pub const ENTER_FUNCTION: &str = "melior_profile_enter";
+/// A symbol name of a callback called at exits of functions.
+pub const EXIT_FUNCTION: &str = "melior_profile_exit";
+
+static PASS_ID: u64 = 0;
+static PROFILER_COUNT: AtomicI64 = AtomicI64::new(0);
+static PROFILES: Lazy<Mutex<HashMap<i64, Vec<FunctionProfile>>>> = Lazy::new(Default::default);
+
+thread_local! {
+    static START_TIMES: RefCell<Vec<Instant>> = Default::default();
+}
+
+/// A profile of a function.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FunctionProfile {
+    name: String,
+    call_count: usize,
+    duration: Duration,
+}
+
+impl FunctionProfile {
+    /// Gets a function name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets a number of calls.
+    pub fn call_count(&self) -> usize {
+        self.call_count
+    }
+
+    /// Gets a total wall time of calls.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+/// A profiler of functions.
+///
+/// Each profiler collects profiles of functions instrumented by its own passes.
+/// The profiles are discarded when it is dropped.
+#[derive(Debug)]
+pub struct Profiler {
+    id: i64,
+}
+
+impl Profiler {
+    /// Creates a profiler.
+    pub fn new() -> Self {
+        let id = PROFILER_COUNT.fetch_add(1, Ordering::Relaxed);
+
+        PROFILES.lock().unwrap().insert(id, vec![]);
+
+        Self { id }
+    }
+
+    /// Creates a pass to instrument functions in a module with calls to
+    /// profiling callbacks.
+    ///
+    /// Functions of the same name share a profile even if they are in
+    /// different modules. The pass fails after the profiler is dropped.
+    ///
+    /// The `arith` dialect needs to be loaded in a context.
+    pub fn instrument_functions(&self) -> Pass {
+        create_external(
+            InstrumentPass { profiler: self.id },
+            unsafe {
+                crate::ir::r#type::Id::from_raw(mlirTypeIDCreate(
+                    &PASS_ID as *const _ as *const c_void,
+                ))
+            },
+            "instrument-functions",
+            "melior-instrument-functions",
+            "Instruments functions with calls to profiling callbacks",
+            "builtin.module",
+            &[dialect::Handle::func()],
+        )
+    }
+
+    /// Gets profiles of instrumented functions.
+    pub fn profiles(&self) -> Vec<FunctionProfile> {
+        PROFILES
+            .lock()
+            .unwrap()
+            .get(&self.id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Profiler {
+    fn drop(&mut self) {
+        PROFILES.lock().unwrap().remove(&self.id);
+    }
+}
+
+#[derive(Clone)]
+struct InstrumentPass {
+    profiler: i64,
+}
+
+impl RunnablePass for InstrumentPass {
+    fn run(&mut self, operation: OperationRef, context: ContextRef) -> Result<(), ()> {
+        context.with_context(|context| instrument_module(context, operation, self.profiler))
+    }
+}
+
+/// Registers profiling callbacks to an execution engine.
+pub fn register(engine: &ExecutionEngine) {
+    // The callbacks are static and match their declarations inserted by the
+    // pass.
+    unsafe {
+        engine.register_symbol(ENTER_FUNCTION, enter_function as *mut ());
+        engine.register_symbol(EXIT_FUNCTION, exit_function as *mut ());
+    }
+}
+
+fn instrument_module(context: &Context, module: OperationRef, profiler: i64) -> Result<(), ()> {
+    let region = module.region(0).ok_or(())?;
+    let body = region.first_block().ok_or(())?;
+    let location = Location::unknown(context);
+    let integer_type = Type::integer(context, 64);
+    let mut declared_functions = vec![];
+
+    for function in body.operations() {
+        if function.name().as_string_ref().as_str() != Ok("func.func") {
+            continue;
+        }
+
+        let name = symbol_name(function)?;
+
+        if name == ENTER_FUNCTION || name == EXIT_FUNCTION {
+            declared_functions.push(name);
+            continue;
+        }
+
+        let region = function.region(0).ok_or(())?;
+        let entry_block = match region.first_block() {
+            Some(block) => block,
+            None => continue,
+        };
+
+        let id = {
+            let mut profiles = PROFILES.lock().unwrap();
+            let profiles = profiles.get_mut(&profiler).ok_or(())?;
+
+            match profiles.iter().position(|profile| profile.name == name) {
+                Some(index) => index,
+                None => {
+                    profiles.push(FunctionProfile {
+                        name,
+                        call_count: 0,
+                        duration: Duration::default(),
+                    });
+
+                    profiles.len() - 1
+                }
+            }
+        };
+        let build_constant = |value: i64| -> Result<Operation, ()> {
+            operation::Builder::new("arith.constant", location)
+                .add_attributes(&[(
+                    Identifier::new(context, "value"),
+                    Attribute::parse(context, &format!("{} : i64", value)).ok_or(())?,
+                )])
+                .add_results(&[integer_type])
+                .build()
+                .map_err(|_| ())
+        };
+        let build_call = |callee: &str| -> Result<Vec<Operation>, ()> {
+            let profiler = build_constant(profiler)?;
+            let function = build_constant(id as i64)?;
+            let call = operation::Builder::new("func.call", location)
+                .add_attributes(&[(
+                    Identifier::new(context, "callee"),
+                    Attribute::parse(context, &format!("@{}", callee)).ok_or(())?,
+                )])
+                .add_operands(&[
+                    profiler.result(0).map_err(|_| ())?.into(),
+                    function.result(0).map_err(|_| ())?.into(),
+                ])
+                .build()
+                .map_err(|_| ())?;
+
+            Ok(vec![profiler, function, call])
+        };
+
+        // Entry blocks of functions always have terminators.
+        let first_operation = entry_block.first_operation().ok_or(())?;
+
+        for operation in build_call(ENTER_FUNCTION)? {
+            entry_block.insert_operation_before(first_operation, operation);
+        }
+
+        for block in region.blocks() {
+            if let Some(terminator) = block.terminator() {
+                if terminator.name().as_string_ref().as_str() == Ok("func.return") {
+                    for operation in build_call(EXIT_FUNCTION)? {
+                        block.insert_operation_before(terminator, operation);
+                    }
+                }
+            }
+        }
+    }
+
+    for name in [ENTER_FUNCTION, EXIT_FUNCTION] {
+        if declared_functions.iter().any(|declared| declared == name) {
+            continue;
+        }
+
+        body.append_operation(
+            operation::Builder::new("func.func", location)
+                .add_attributes(&[
+                    (Identifier::new(context, "function_type"), unsafe {
+                        Attribute::from_raw(mlirTypeAttrGet(
+                            Function::new(context, &[integer_type, integer_type], &[]).to_raw(),
+                        ))
+                    }),
+                    (
+                        Identifier::new(context, "sym_name"),
+                        Attribute::string(context, name),
+                    ),
+                    (
+                        Identifier::new(context, "sym_visibility"),
+                        Attribute::string(context, "private"),
+                    ),
+                ])
+                .add_regions(vec![Region::new()])
+                .build()
+                .map_err(|_| ())?,
+        );
+    }
+
+    Ok(())
+}
+
+fn symbol_name(function: OperationRef) -> Result<String, ()> {
+    let attribute = function.attribute("sym_name").ok_or(())?;
+
+    if !attribute.is_string() {
+        return Err(());
+    }
+
+    unsafe { StringRef::from_raw(mlirStringAttrGetValue(attribute.to_raw())) }
+        .as_str()
+        .map(String::from)
+        .map_err(|_| ())
+}
+
+extern "C" fn enter_function(_profiler: i64, _function: i64) {
+    START_TIMES.with(|times| times.borrow_mut().push(Instant::now()));
+}
+
+extern "C" fn exit_function(profiler: i64, function: i64) {
+    let time = match START_TIMES.with(|times| times.borrow_mut().pop()) {
+        Some(time) => time,
+        None => return,
+    };
+
+    if let Some(profile) = PROFILES
+        .lock()
+        .unwrap()
+        .get_mut(&profiler)
+        .and_then(|profiles| profiles.get_mut(function as usize))
+    {
+        profile.call_count += 1;
+        profile.duration += time.elapsed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ir::Module, pass, utility::register_all_llvm_translations};
+
+    #[test]
+    fn instrument() {
+        let context = Context::new();
+        let mut module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() -> i32 {
+                %res = arith.constant 42 : i32
+                return %res : i32
+            }
+            "#,
+        )
+        .unwrap();
+
+        let profiler = Profiler::new();
+        let manager = pass::Manager::new(&context);
+        manager.add_pass(profiler.instrument_functions());
+
+        assert_eq!(manager.run(&mut module), Ok(()));
+        assert!(module.as_operation().verify());
+
+        let source = module.as_operation().to_string();
+
+        assert!(source.contains("call @melior_profile_enter"));
+        assert!(source.contains("call @melior_profile_exit"));
+        assert!(source.contains("func.func private @melior_profile_enter(i64, i64)"));
+        assert!(source.contains("func.func private @melior_profile_exit(i64, i64)"));
+    }
+
+    #[test]
+    fn instrument_with_declaration() {
+        let context = Context::new();
+        let mut module = Module::parse(
+            &context,
+            r#"
+            func.func private @melior_profile_enter(i64, i64)
+            func.func @foo() {
+                return
+            }
+            "#,
+        )
+        .unwrap();
+
+        let profiler = Profiler::new();
+        let manager = pass::Manager::new(&context);
+        manager.add_pass(profiler.instrument_functions());
+
+        assert_eq!(manager.run(&mut module), Ok(()));
+        assert!(module.as_operation().verify());
+        assert!(module
+            .as_operation()
+            .to_string()
+            .contains("func.func private @melior_profile_exit(i64, i64)"));
+    }
+
+    #[test]
+    fn instrument_after_drop() {
+        let context = Context::new();
+        let mut module = Module::parse(&context, "func.func @foo() { return }").unwrap();
+        let manager = pass::Manager::new(&context);
+
+        manager.add_pass(Profiler::new().instrument_functions());
+
+        assert!(manager.run(&mut module).is_err());
+    }
+
+    #[test]
+    fn deduplicate() {
+        let context = Context::new();
+        let profiler = Profiler::new();
+
+        for _ in 0..2 {
+            let mut module = Module::parse(&context, "func.func @foo() { return }").unwrap();
+            let manager = pass::Manager::new(&context);
+            manager.add_pass(profiler.instrument_functions());
+
+            assert_eq!(manager.run(&mut module), Ok(()));
+        }
+
+        assert_eq!(profiler.profiles().len(), 1);
+    }
+
+    #[test]
+    fn profile() {
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+
+        let mut module = Module::parse(
+            &context,
+            r#"
+            func.func @melior_profile_test(%arg0 : i32) -> i32 attributes { llvm.emit_c_interface } {
+                %res = arith.addi %arg0, %arg0 : i32
+                return %res : i32
+            }
+            "#,
+        )
+        .unwrap();
+
+        let profiler = Profiler::new();
+        let manager = pass::Manager::new(&context);
+        manager.add_pass(profiler.instrument_functions());
+        manager
+            .nested_under("func.func")
+            .add_pass(pass::conversion::convert_arithmetic_to_llvm());
+        manager.add_pass(pass::conversion::convert_func_to_llvm());
+        manager.add_pass(pass::conversion::reconcile_unrealized_casts());
+
+        assert_eq!(manager.run(&mut module), Ok(()));
+
+        let engine = ExecutionEngine::new(&module, 2, &[]);
+        register(&engine);
+
+        let mut argument = 21;
+        let mut result = -1;
+
+        for _ in 0..2 {
+            assert_eq!(
+                unsafe {
+                    engine.invoke_packed(
+                        "melior_profile_test",
+                        &mut [
+                            &mut argument as *mut i32 as *mut (),
+                            &mut result as *mut i32 as *mut (),
+                        ],
+                    )
+                },
+                Ok(())
+            );
+        }
+
+        assert_eq!(result, 42);
+        assert_eq!(
+            profiler
+                .profiles()
+                .iter()
+                .find(|profile| profile.name() == "melior_profile_test")
+                .map(FunctionProfile::call_count),
+            Some(2)
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use super::{Manager, Timing, TimingReport};
+use crate::{context::Context, ir::Module, pass::Pass, Error};
+use std::time::Instant;
+
+// TODO Use pass timing and statistics of MLIR.
+/// A pass manager which measures wall times of its passes.
+///
+/// Each pass is run by its own pass manager so that it can be timed
+/// separately.
+pub struct TimedManager<'c> {
+    context: &'c Context,
+    managers: Vec<(String, Manager<'c>)>,
+}
+
+impl<'c> TimedManager<'c> {
+    /// Creates a timed pass manager.
+    pub fn new(context: &'c Context) -> Self {
+        Self {
+            context,
+            managers: vec![],
+        }
+    }
+
+    /// Adds a pass.
+    pub fn add_pass(&mut self, pass: Pass) {
+        let manager = Manager::new(self.context);
+
+        manager.add_pass(pass);
+
+        self.push_manager(manager);
+    }
+
+    /// Adds a pass for nested operations corresponding to a given name.
+    pub fn add_nested_pass(&mut self, name: &str, pass: Pass) {
+        let manager = Manager::new(self.context);
+
+        manager.nested_under(name).add_pass(pass);
+
+        self.push_manager(manager);
+    }
+
+    /// Runs passes against a module and reports their wall times.
+    pub fn run(&self, module: &mut Module) -> Result<TimingReport, Error> {
+        let mut timings = vec![];
+
+        for (pipeline, manager) in &self.managers {
+            let time = Instant::now();
+
+            manager.run(module)?;
+
+            timings.push(Timing::new(
+                pipeline.clone(),
+                time.elapsed(),
+                module.as_operation().statistics(),
+            ));
+        }
+
+        Ok(TimingReport::new(timings))
+    }
+
+    fn push_manager(&mut self, manager: Manager<'c>) {
+        let pipeline = manager.to_string();
+
+        self.managers.push((
+            pipeline
+                .strip_prefix("builtin.module(")
+                .and_then(|pipeline| pipeline.strip_suffix(')'))
+                .unwrap_or(&pipeline)
+                .into(),
+            manager,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pass;
+
+    #[test]
+    fn run() {
+        let context = Context::new();
+        let mut manager = TimedManager::new(&context);
+
+        manager.add_nested_pass("func.func", pass::transform::canonicalizer());
+        manager.add_pass(pass::conversion::convert_func_to_llvm());
+
+        let mut module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0 : i32) -> i32 {
+                %zero = arith.constant 0 : i32
+                %res = arith.addi %arg0, %zero : i32
+                return %res : i32
+            }
+            "#,
+        )
+        .unwrap();
+        let report = manager.run(&mut module).unwrap();
+
+        assert_eq!(report.timings().len(), 2);
+        assert!(report.timings()[0]
+            .pipeline()
+            .starts_with("func.func(canonicalize"));
+        assert!(report.timings()[1]
+            .pipeline()
+            .starts_with("convert-func-to-llvm"));
+        assert_eq!(report.timings()[0].statistics().operation_count(), 3);
+    }
+}
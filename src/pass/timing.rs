@@ -0,0 +1,57 @@
+use crate::ir::operation::Statistics;
+use std::time::Duration;
+
+/// A wall time and statistics of a pass.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Timing {
+    pipeline: String,
+    duration: Duration,
+    statistics: Statistics,
+}
+
+impl Timing {
+    pub(crate) fn new(pipeline: String, duration: Duration, statistics: Statistics) -> Self {
+        Self {
+            pipeline,
+            duration,
+            statistics,
+        }
+    }
+
+    /// Gets a textual pipeline of a pass.
+    pub fn pipeline(&self) -> &str {
+        &self.pipeline
+    }
+
+    /// Gets a wall time.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Gets statistics of a module after a pass is run.
+    pub fn statistics(&self) -> Statistics {
+        self.statistics
+    }
+}
+
+/// A timing report of a pass pipeline.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TimingReport {
+    timings: Vec<Timing>,
+}
+
+impl TimingReport {
+    pub(crate) fn new(timings: Vec<Timing>) -> Self {
+        Self { timings }
+    }
+
+    /// Gets timings of passes in order.
+    pub fn timings(&self) -> &[Timing] {
+        &self.timings
+    }
+
+    /// Gets a total wall time.
+    pub fn duration(&self) -> Duration {
+        self.timings.iter().map(Timing::duration).sum()
+    }
+}
@@ -2,13 +2,19 @@
 
 use super::Pass;
 use mlir_sys::{
-    mlirCreateTransformsCSE, mlirCreateTransformsCanonicalizer, mlirCreateTransformsInliner,
+    mlirCreateTransformsCSE, mlirCreateTransformsCanonicalizer,
+    mlirCreateTransformsControlFlowSink, mlirCreateTransformsInliner,
+    mlirCreateTransformsLocationSnapshot, mlirCreateTransformsLoopInvariantCodeMotion,
     mlirCreateTransformsPrintOpStats, mlirCreateTransformsSCCP, mlirCreateTransformsStripDebugInfo,
-    mlirCreateTransformsSymbolDCE, mlirCreateTransformsSymbolPrivatize, mlirRegisterTransformsCSE,
-    mlirRegisterTransformsCanonicalizer, mlirRegisterTransformsInliner,
+    mlirCreateTransformsSymbolDCE, mlirCreateTransformsSymbolPrivatize,
+    mlirCreateTransformsTopologicalSort, mlirCreateTransformsViewOpGraph,
+    mlirRegisterTransformsCSE, mlirRegisterTransformsCanonicalizer,
+    mlirRegisterTransformsControlFlowSink, mlirRegisterTransformsInliner,
+    mlirRegisterTransformsLocationSnapshot, mlirRegisterTransformsLoopInvariantCodeMotion,
     mlirRegisterTransformsPrintOpStats, mlirRegisterTransformsSCCP,
     mlirRegisterTransformsStripDebugInfo, mlirRegisterTransformsSymbolDCE,
-    mlirRegisterTransformsSymbolPrivatize,
+    mlirRegisterTransformsSymbolPrivatize, mlirRegisterTransformsTopologicalSort,
+    mlirRegisterTransformsViewOpGraph,
 };
 
 /// Creates a pass to canonicalize IR.
@@ -90,3 +96,53 @@ pub fn print_operation_stats() -> Pass {
 pub fn register_print_operation_stats() {
     unsafe { mlirRegisterTransformsPrintOpStats() }
 }
+
+/// Creates a pass to sink operations into conditional blocks.
+pub fn control_flow_sink() -> Pass {
+    Pass::from_raw_fn(mlirCreateTransformsControlFlowSink)
+}
+
+/// Registers a pass to sink operations into conditional blocks.
+pub fn register_control_flow_sink() {
+    unsafe { mlirRegisterTransformsControlFlowSink() }
+}
+
+/// Creates a pass to replace locations with ones of a printed IR snapshot.
+pub fn location_snapshot() -> Pass {
+    Pass::from_raw_fn(mlirCreateTransformsLocationSnapshot)
+}
+
+/// Registers a pass to replace locations with ones of a printed IR snapshot.
+pub fn register_location_snapshot() {
+    unsafe { mlirRegisterTransformsLocationSnapshot() }
+}
+
+/// Creates a pass to hoist loop invariant operations out of loops.
+pub fn loop_invariant_code_motion() -> Pass {
+    Pass::from_raw_fn(mlirCreateTransformsLoopInvariantCodeMotion)
+}
+
+/// Registers a pass to hoist loop invariant operations out of loops.
+pub fn register_loop_invariant_code_motion() {
+    unsafe { mlirRegisterTransformsLoopInvariantCodeMotion() }
+}
+
+/// Creates a pass to sort operations in graph regions topologically.
+pub fn topological_sort() -> Pass {
+    Pass::from_raw_fn(mlirCreateTransformsTopologicalSort)
+}
+
+/// Registers a pass to sort operations in graph regions topologically.
+pub fn register_topological_sort() {
+    unsafe { mlirRegisterTransformsTopologicalSort() }
+}
+
+/// Creates a pass to print an operation graph in the Graphviz format.
+pub fn view_operation_graph() -> Pass {
+    Pass::from_raw_fn(mlirCreateTransformsViewOpGraph)
+}
+
+/// Registers a pass to print an operation graph in the Graphviz format.
+pub fn register_view_operation_graph() {
+    unsafe { mlirRegisterTransformsViewOpGraph() }
+}
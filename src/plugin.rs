@@ -0,0 +1,162 @@
+//! Plugins providing dialects, passes, and preset pass pipelines.
+
+use crate::{context::Context, dialect, pass};
+
+/// A plugin.
+///
+/// Crates providing custom dialects or passes can implement this trait so
+/// that applications compose them with a [`Loader`].
+pub trait Plugin {
+    /// Gets a name.
+    fn name(&self) -> &str;
+
+    /// Registers dialects into a dialect registry.
+    fn register_dialects(&self, _registry: &dialect::Registry) {}
+
+    /// Registers passes globally.
+    fn register_passes(&self) {}
+
+    /// Adds passes of a preset pipeline of a name to a pass manager.
+    ///
+    /// It returns `true` if a plugin provides the preset.
+    fn configure_preset(&self, _name: &str, _manager: &pass::Manager) -> bool {
+        false
+    }
+}
+
+/// A plugin loader.
+#[derive(Default)]
+pub struct Loader {
+    plugins: Vec<Box<dyn Plugin>>,
+}
+
+impl Loader {
+    /// Creates a plugin loader.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a plugin.
+    pub fn add_plugin(mut self, plugin: impl Plugin + 'static) -> Self {
+        self.plugins.push(Box::new(plugin));
+        self
+    }
+
+    /// Gets names of plugins.
+    pub fn plugin_names(&self) -> impl Iterator<Item = &str> {
+        self.plugins.iter().map(|plugin| plugin.name())
+    }
+
+    /// Loads plugins into a context.
+    ///
+    /// It registers and loads dialects of the plugins into the context and
+    /// registers their passes.
+    pub fn load(&self, context: &Context) {
+        let registry = dialect::Registry::new();
+
+        for plugin in &self.plugins {
+            plugin.register_dialects(&registry);
+        }
+
+        context.append_dialect_registry(&registry);
+        context.load_all_available_dialects();
+
+        for plugin in &self.plugins {
+            plugin.register_passes();
+        }
+    }
+
+    /// Creates a pass manager of a preset pipeline of a name.
+    ///
+    /// The first plugin providing the preset configures the pass manager.
+    pub fn preset<'c>(&self, context: &'c Context, name: &str) -> Option<pass::Manager<'c>> {
+        let manager = pass::Manager::new(context);
+
+        self.plugins
+            .iter()
+            .any(|plugin| plugin.configure_preset(name, &manager))
+            .then_some(manager)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utility::parse_pass_pipeline;
+
+    struct FuncPlugin;
+
+    impl Plugin for FuncPlugin {
+        fn name(&self) -> &str {
+            "func"
+        }
+
+        fn register_dialects(&self, registry: &dialect::Registry) {
+            dialect::Handle::func().insert_dialect(registry);
+        }
+
+        fn register_passes(&self) {
+            pass::transform::register_symbol_dce();
+        }
+
+        fn configure_preset(&self, name: &str, manager: &pass::Manager) -> bool {
+            if name == "cleanup" {
+                manager.add_pass(pass::transform::symbol_dce());
+                true
+            } else {
+                false
+            }
+        }
+    }
+
+    struct EmptyPlugin;
+
+    impl Plugin for EmptyPlugin {
+        fn name(&self) -> &str {
+            "empty"
+        }
+    }
+
+    #[test]
+    fn plugin_names() {
+        assert_eq!(
+            Loader::new()
+                .add_plugin(EmptyPlugin)
+                .add_plugin(FuncPlugin)
+                .plugin_names()
+                .collect::<Vec<_>>(),
+            ["empty", "func"]
+        );
+    }
+
+    #[test]
+    fn load() {
+        let context = unsafe { Context::new_unchecked() };
+
+        Loader::new()
+            .add_plugin(EmptyPlugin)
+            .add_plugin(FuncPlugin)
+            .load(&context);
+
+        assert!(context.loaded_dialect_count() > 1);
+        assert_eq!(
+            parse_pass_pipeline(
+                pass::Manager::new(&context).as_operation_pass_manager(),
+                "builtin.module(symbol-dce)"
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn preset() {
+        let context = Context::new();
+        let loader = Loader::new().add_plugin(EmptyPlugin).add_plugin(FuncPlugin);
+
+        assert_eq!(
+            loader.preset(&context, "cleanup").unwrap().to_string(),
+            "builtin.module(symbol-dce)"
+        );
+        assert!(loader.preset(&context, "foo").is_none());
+    }
+}
@@ -1,23 +1,17 @@
-use mlir_sys::{mlirStringRefCreateFromCString, mlirStringRefEqual, MlirStringRef};
-use once_cell::sync::Lazy;
+use mlir_sys::{mlirStringRefEqual, MlirStringRef};
 use std::{
-    collections::HashMap,
-    ffi::CString,
+    ffi::CStr,
     marker::PhantomData,
     slice,
     str::{self, Utf8Error},
-    sync::RwLock,
 };
 
-// We need to pass null-terminated strings to functions in the MLIR API although
-// Rust's strings are not.
-static STRING_CACHE: Lazy<RwLock<HashMap<String, CString>>> = Lazy::new(Default::default);
-
 /// A string reference.
 // https://mlir.llvm.org/docs/CAPI/#stringref
 //
-// TODO The documentation says string refs do not have to be null-terminated.
-// But it looks like some functions do not handle strings not null-terminated?
+// String references borrow their sources and are not null-terminated in
+// general. Parsers in MLIR read past the end of their inputs looking for null
+// terminators, so sources passed to them are converted from `CStr`s.
 #[derive(Clone, Copy, Debug)]
 pub struct StringRef<'a> {
     raw: MlirStringRef,
@@ -26,15 +20,16 @@ pub struct StringRef<'a> {
 
 impl<'a> StringRef<'a> {
     /// Converts a string reference into a `str`.
-    pub fn as_str(&self) -> Result<&str, Utf8Error> {
+    pub fn as_str(&self) -> Result<&'a str, Utf8Error> {
+        // Empty string references from MLIR can have null pointers.
+        if self.raw.length == 0 {
+            return Ok("");
+        }
+
         unsafe {
             let bytes = slice::from_raw_parts(self.raw.data as *mut u8, self.raw.length as usize);
 
-            str::from_utf8(if bytes[bytes.len() - 1] == 0 {
-                &bytes[..bytes.len() - 1]
-            } else {
-                bytes
-            })
+            str::from_utf8(bytes)
         }
     }
 
@@ -58,25 +53,32 @@ impl<'a> PartialEq for StringRef<'a> {
 
 impl<'a> Eq for StringRef<'a> {}
 
-impl From<&str> for StringRef<'static> {
-    fn from(string: &str) -> Self {
-        if !STRING_CACHE.read().unwrap().contains_key(string) {
-            STRING_CACHE
-                .write()
-                .unwrap()
-                .insert(string.to_owned(), CString::new(string).unwrap());
+impl<'a> From<&'a str> for StringRef<'a> {
+    fn from(string: &'a str) -> Self {
+        unsafe {
+            Self::from_raw(MlirStringRef {
+                data: string.as_ptr() as *const _,
+                length: string.len() as _,
+            })
         }
+    }
+}
 
-        let lock = STRING_CACHE.read().unwrap();
-        let string = lock.get(string).unwrap();
-
-        unsafe { Self::from_raw(mlirStringRefCreateFromCString(string.as_ptr())) }
+impl<'a> From<&'a CStr> for StringRef<'a> {
+    fn from(string: &'a CStr) -> Self {
+        unsafe {
+            Self::from_raw(MlirStringRef {
+                data: string.as_ptr(),
+                length: string.to_bytes().len() as _,
+            })
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::ffi::CString;
 
     #[test]
     fn equal() {
@@ -87,4 +89,26 @@ mod tests {
     fn not_equal() {
         assert_ne!(StringRef::from("foo"), StringRef::from("bar"));
     }
+
+    #[test]
+    fn as_str() {
+        assert_eq!(StringRef::from("foo").as_str(), Ok("foo"));
+    }
+
+    #[test]
+    fn as_str_empty() {
+        assert_eq!(StringRef::from("").as_str(), Ok(""));
+    }
+
+    #[test]
+    fn from_c_str() {
+        let string = CString::new("foo").unwrap();
+
+        assert_eq!(StringRef::from(string.as_c_str()), StringRef::from("foo"));
+    }
+
+    #[test]
+    fn borrow_substring() {
+        assert_eq!(StringRef::from(&"foobar"[..3]).as_str(), Ok("foo"));
+    }
 }
@@ -0,0 +1,336 @@
+//! IR transformations.
+
+use crate::{
+    dialect::cf,
+    ir::{
+        operation::ValueMapping,
+        walk::{WalkOrder, WalkResult},
+        OperationRef, SymbolTable, Value, ValueLike,
+    },
+    string_ref::StringRef,
+    Error,
+};
+use mlir_sys::mlirFlatSymbolRefAttrGetValue;
+
+/// Inlines a `func.call` operation.
+///
+/// Operations of a callee are cloned before the call with their arguments
+/// remapped to operands of the call, uses of results of the call are replaced
+/// with operands of the callee's `func.return` operations, and the call is
+/// erased.
+///
+/// If a callee has multiple blocks, the block of the call is split after the
+/// call into a block whose arguments replace results of the call, and the
+/// callee's `func.return` operations are replaced with `cf.br` operations to
+/// it. The `cf` dialect needs to be loaded then.
+///
+/// Looking up a callee and replacing uses of results walk ancestors of the
+/// call, which takes time linear to the size of a whole module. A call in its
+/// own callee is not inlined.
+///
+/// # Safety
+///
+/// The call is erased, so references to it and its results must not be used
+/// afterwards.
+pub unsafe fn inline_call(call: OperationRef) -> Result<(), Error> {
+    let error = || Error::InlineCall(call.to_string());
+
+    if !call.is("func.call") {
+        return Err(error());
+    }
+
+    let function = lookup_callee(call).ok_or_else(error)?;
+
+    if ancestors(call).any(|operation| operation == function) {
+        return Err(error());
+    }
+
+    let region = function.region(0).ok_or_else(error)?;
+    let body = region.first_block().ok_or_else(error)?;
+
+    if body.argument_count() != call.operand_count()
+        || region.blocks().any(|block| match block.terminator() {
+            Some(terminator) => {
+                terminator.is("func.return") && terminator.operand_count() != call.result_count()
+            }
+            None => true,
+        })
+    {
+        return Err(error());
+    }
+
+    if body.next_in_region().is_some() {
+        return inline_blocks(call, function).ok_or_else(error);
+    }
+
+    let block = call.block().ok_or_else(error)?;
+    let terminator = body
+        .terminator()
+        .filter(|terminator| terminator.is("func.return"))
+        .ok_or_else(error)?;
+    let mut mapping = ValueMapping::new();
+
+    for (index, operand) in call.operands().enumerate() {
+        mapping.insert(body.argument(index)?.into(), operand);
+    }
+
+    for operation in body
+        .operations()
+        .take_while(|&operation| operation != terminator)
+    {
+        block.insert_operation_before(call, operation.clone_with_mapping(&mut mapping));
+    }
+
+    call.replace_uses(
+        &terminator
+            .operands()
+            .map(|operand| mapping.get(operand).unwrap_or(operand))
+            .collect::<Vec<_>>(),
+    )?;
+
+    // Uses of results of the call are replaced already.
+    call.erase();
+
+    Ok(())
+}
+
+// Inlines a callee of multiple blocks. The call and the callee are validated
+// already.
+unsafe fn inline_blocks(call: OperationRef, function: OperationRef) -> Option<()> {
+    let block = call.block()?;
+    let region = block.parent_region()?;
+    let next = call.next_in_block()?;
+
+    // Successors of cloned blocks are remapped by the clone itself.
+    let clone = function.to_owned();
+    let body = clone.region(0)?.take_body();
+    let entry_block = body.first_block()?;
+    let arguments = (0..entry_block.argument_count())
+        .map(|index| entry_block.argument(index).map(Value::from))
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+
+    for inlined_block in body.blocks() {
+        for operation in inlined_block.operations() {
+            operation.walk(WalkOrder::PreOrder, |operation| {
+                for (position, operand) in operation.operands().enumerate() {
+                    if let Some(index) = arguments.iter().position(|&argument| argument == operand)
+                    {
+                        // Positions of existing operands are always valid.
+                        operation
+                            .set_operand(position, call.operand(index).unwrap())
+                            .unwrap();
+                    }
+                }
+
+                WalkResult::Advance
+            });
+        }
+    }
+
+    let continuation = block.split_before(next).ok()?;
+    let results = call
+        .results()
+        .map(|result| continuation.add_argument(result.r#type(), call.location()))
+        .collect::<Vec<_>>();
+
+    call.replace_uses(&results).ok()?;
+
+    for inlined_block in body.blocks() {
+        // Terminators of all blocks are validated already.
+        let terminator = inlined_block.terminator()?;
+
+        if terminator.is("func.return") {
+            inlined_block.append_operation(
+                cf::br(
+                    continuation,
+                    &terminator.operands().collect::<Vec<_>>(),
+                    terminator.location(),
+                )
+                .ok()?,
+            );
+            // Returns have no results.
+            terminator.erase();
+        }
+    }
+
+    for operation in entry_block.operations().collect::<Vec<_>>() {
+        // Operations are only moved and never dropped.
+        block.append_operation(operation.remove_from_parent().unwrap());
+    }
+
+    while let Some(inlined_block) = entry_block.next_in_region() {
+        // Blocks are only moved and never dropped.
+        region.insert_block_before(continuation, inlined_block.detach().unwrap());
+    }
+
+    // Uses of results of the call are replaced already.
+    call.erase();
+
+    Some(())
+}
+
+fn lookup_callee<'a>(call: OperationRef<'a>) -> Option<OperationRef<'a>> {
+    let callee = call
+        .attribute("callee")
+        .filter(|attribute| attribute.is_flat_symbol())?;
+    let name = unsafe { StringRef::from_raw(mlirFlatSymbolRefAttrGetValue(callee.to_raw())) };
+
+    ancestors(call)
+        .filter_map(|operation| SymbolTable::new(operation).ok())
+        .find_map(|table| table.lookup(name.as_str().ok()?))
+}
+
+fn ancestors(operation: OperationRef) -> impl Iterator<Item = OperationRef> {
+    std::iter::successors(operation.parent_operation(), |operation| {
+        operation.parent_operation()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{
+            walk::{WalkOrder, WalkResult},
+            Module,
+        },
+        Context,
+    };
+
+    fn find_call<'a>(module: &'a Module) -> OperationRef<'a> {
+        let mut call = None;
+
+        module
+            .as_operation()
+            .walk(WalkOrder::PreOrder, |operation| {
+                if operation.is("func.call") {
+                    call = Some(operation);
+                }
+
+                WalkResult::Advance
+            });
+
+        call.unwrap()
+    }
+
+    #[test]
+    fn inline() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @add(%arg0: i32, %arg1: i32) -> i32 {
+                %0 = arith.addi %arg0, %arg1 : i32
+                return %0 : i32
+            }
+
+            func.func @main(%arg0: i32) -> i32 {
+                %0 = func.call @add(%arg0, %arg0) : (i32, i32) -> i32
+                %1 = arith.muli %0, %0 : i32
+                return %1 : i32
+            }
+            "#,
+        )
+        .unwrap();
+
+        unsafe { inline_call(find_call(&module)) }.unwrap();
+
+        assert!(module.as_operation().verify());
+
+        let main = SymbolTable::new(module.as_operation())
+            .unwrap()
+            .lookup("main")
+            .unwrap()
+            .to_string();
+
+        assert!(!main.contains("call"));
+        assert!(main.contains("arith.addi %arg0, %arg0 : i32"));
+        assert!(main.contains("arith.muli %0, %0 : i32"));
+    }
+
+    #[test]
+    fn inline_multiple_blocks() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @select(%arg0: i1, %arg1: i32) -> i32 {
+                cf.cond_br %arg0, ^bb1, ^bb2
+            ^bb1:
+                return %arg1 : i32
+            ^bb2:
+                %0 = arith.constant 0 : i32
+                return %0 : i32
+            }
+
+            func.func @main(%arg0: i1, %arg1: i32) -> i32 {
+                %0 = func.call @select(%arg0, %arg1) : (i1, i32) -> i32
+                %1 = arith.muli %0, %0 : i32
+                return %1 : i32
+            }
+            "#,
+        )
+        .unwrap();
+
+        unsafe { inline_call(find_call(&module)) }.unwrap();
+
+        assert!(module.as_operation().verify());
+
+        let main = SymbolTable::new(module.as_operation())
+            .unwrap()
+            .lookup("main")
+            .unwrap()
+            .to_string();
+
+        assert!(!main.contains("call"));
+        assert!(main.contains("cf.cond_br %arg0, ^bb1, ^bb2"));
+        assert!(main.contains("cf.br ^bb3(%arg1 : i32)"));
+        assert!(main.contains("^bb3(%0: i32):"));
+        assert!(main.contains("arith.muli %0, %0 : i32"));
+    }
+
+    #[test]
+    fn inline_recursive_call() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+        let call = find_call(&module);
+
+        assert_eq!(
+            unsafe { inline_call(call) },
+            Err(Error::InlineCall(call.to_string()))
+        );
+    }
+
+    #[test]
+    fn inline_external_function() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func private @foo()
+
+            func.func @bar() {
+                func.call @foo() : () -> ()
+                return
+            }
+            "#,
+        )
+        .unwrap();
+        let call = find_call(&module);
+
+        assert_eq!(
+            unsafe { inline_call(call) },
+            Err(Error::InlineCall(call.to_string()))
+        );
+    }
+}
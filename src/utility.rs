@@ -1,24 +1,60 @@
 //! Utility functions.
 
 use crate::{
-    context::Context, dialect, logical_result::LogicalResult, pass, string_ref::StringRef, Error,
+    context::{Context, ContextRef},
+    diagnostic::{CapturedDiagnostic, Diagnostic},
+    dialect,
+    ir::{
+        operation::{Builder, PrintingFlags},
+        Attribute, BlockRef, Identifier, Location, Module, SymbolTable, Type, Value, ValueLike,
+    },
+    logical_result::LogicalResult,
+    pass,
+    string_ref::StringRef,
+    Error,
 };
 use mlir_sys::{
-    mlirParsePassPipeline, mlirRegisterAllDialects, mlirRegisterAllLLVMTranslations,
-    mlirRegisterAllPasses, MlirStringRef,
+    mlirOperationGetResult, mlirParsePassPipeline, mlirRegisterAllDialects,
+    mlirRegisterAllLLVMTranslations, mlirRegisterAllPasses, mlirValueGetType, MlirStringRef,
 };
 use std::{
     ffi::c_void,
-    fmt::{self, Formatter},
-    sync::Once,
+    fmt::{self, Formatter, Write},
+    mem::take,
+    sync::{Arc, Mutex, Once},
 };
 
+/// Gets a version of MLIR which the crate is built with.
+pub fn mlir_version() -> (usize, usize, usize) {
+    parse_version(env!("MLIR_VERSION"))
+}
+
+/// Checks if a version of MLIR is compatible with a required one.
+///
+/// The version is compatible if it has the same major version and the same or
+/// newer minor version.
+pub fn require_mlir_version(major: usize, minor: usize) -> Result<(), Error> {
+    let version = mlir_version();
+
+    if version.0 == major && version.1 >= minor {
+        Ok(())
+    } else {
+        Err(Error::MlirVersionMismatch(
+            format!("{}.{}", major, minor),
+            format!("{}.{}.{}", version.0, version.1, version.2),
+        ))
+    }
+}
+
 /// Registers all dialects to a dialect registry.
 pub fn register_all_dialects(registry: &dialect::Registry) {
     unsafe { mlirRegisterAllDialects(registry.to_raw()) }
 }
 
 /// Register all translations from other dialects to the `llvm` dialect.
+// TODO Add translation of modules into LLVM IR. `mlirTranslateModuleToLLVMIR`
+// is in the C API of LLVM 17 and later.
+// TODO Emit debug info from locations in LLVM IR.
 pub fn register_all_llvm_translations(context: &Context) {
     unsafe { mlirRegisterAllLLVMTranslations(context.to_raw()) }
 }
@@ -44,9 +80,278 @@ pub fn parse_pass_pipeline(manager: pass::OperationManager, source: &str) -> Res
     }
 }
 
-// TODO Use into_raw_parts.
-pub(crate) unsafe fn into_raw_array<T>(xs: Vec<T>) -> *mut T {
-    xs.leak().as_mut_ptr()
+/// Runs a function with diagnostics captured.
+///
+/// It returns a result of the function and diagnostics reported in a context
+/// while it runs. Captured diagnostics are not passed to other diagnostic
+/// handlers.
+pub fn with_diagnostics<'c, T>(
+    context: &'c Context,
+    run: impl FnOnce() -> T,
+) -> (T, Vec<Diagnostic<'c>>) {
+    let diagnostics = Arc::new(Mutex::new(Diagnostics(vec![])));
+    let id = context.attach_diagnostic_handler({
+        let diagnostics = diagnostics.clone();
+
+        move |diagnostic| {
+            diagnostics.lock().unwrap().0.push(diagnostic);
+            true
+        }
+    });
+
+    let result = run();
+
+    context.detach_diagnostic_handler(id);
+
+    let diagnostics = take(&mut diagnostics.lock().unwrap().0);
+
+    (result, diagnostics)
+}
+
+// Diagnostics can be reported on worker threads of MLIR. Their locations are
+// immutable objects uniqued in contexts.
+struct Diagnostics<'c>(Vec<Diagnostic<'c>>);
+
+unsafe impl<'c> Send for Diagnostics<'c> {}
+
+// Runs a function with diagnostics captured in an owned form to attach them
+// to errors. Unlike `with_diagnostics`, captured diagnostics are still passed
+// to other diagnostic handlers.
+pub(crate) fn capture_diagnostics<T>(
+    context: ContextRef,
+    run: impl FnOnce() -> T,
+) -> (T, Vec<CapturedDiagnostic>) {
+    let diagnostics = Arc::new(Mutex::new(vec![]));
+    let id = context.attach_diagnostic_handler({
+        let diagnostics = diagnostics.clone();
+
+        move |diagnostic| {
+            diagnostics
+                .lock()
+                .unwrap()
+                .push(CapturedDiagnostic::from(&diagnostic));
+            false
+        }
+    });
+
+    let result = run();
+
+    context.detach_diagnostic_handler(id);
+
+    let diagnostics = take(&mut *diagnostics.lock().unwrap());
+
+    (result, diagnostics)
+}
+
+/// Escapes a string as the contents of a string literal in MLIR.
+///
+/// Backslashes are escaped and quotes, non-printable characters, and non-ASCII
+/// bytes are written as hexadecimal escapes, e.g. `\22` for `"`.
+pub fn escape_string(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+
+    for byte in string.bytes() {
+        match byte {
+            b'\\' => escaped.push_str("\\\\"),
+            byte if byte != b'"' && (byte.is_ascii_graphic() || byte == b' ') => {
+                escaped.push(byte as char)
+            }
+            byte => write!(escaped, "\\{:02X}", byte).unwrap(),
+        }
+    }
+
+    escaped
+}
+
+// Gets a length of a string literal at the beginning of a source including its
+// quotes.
+pub(crate) fn string_literal_length(source: &str) -> usize {
+    let mut escaped = false;
+
+    source
+        .char_indices()
+        .skip(1)
+        .find(|&(_, character)| {
+            let end = !escaped && character == '"';
+            escaped = !escaped && character == '\\';
+            end
+        })
+        .map(|(index, _)| index + 1)
+        .unwrap_or(source.len())
+}
+
+// Unescapes the contents of a string literal in MLIR.
+pub(crate) fn unescape_string(string: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(string.len());
+    let mut rest = string.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+
+        match rest {
+            [b'\\', tail @ ..] | [b'"', tail @ ..] => {
+                bytes.push(rest[0]);
+                rest = tail;
+            }
+            [b'n', tail @ ..] => {
+                bytes.push(b'\n');
+                rest = tail;
+            }
+            [b't', tail @ ..] => {
+                bytes.push(b'\t');
+                rest = tail;
+            }
+            [high, low, tail @ ..] => {
+                let high = (*high as char).to_digit(16)?;
+                let low = (*low as char).to_digit(16)?;
+
+                bytes.push((high * 16 + low) as u8);
+                rest = tail;
+            }
+            _ => return None,
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Returns `true` if a name can be written as a bare identifier, e.g. as a
+/// symbol name without quotes.
+pub fn is_bare_identifier(name: &str) -> bool {
+    let mut characters = name.chars();
+
+    characters
+        .next()
+        .map(|character| character.is_ascii_alphabetic() || character == '_')
+        .unwrap_or_default()
+        && characters.all(|character| {
+            character.is_ascii_alphanumeric() || matches!(character, '_' | '$' | '.')
+        })
+}
+
+/// Prints a symbol operation in a module.
+///
+/// Aliases referenced by the operation are printed together. It returns `None`
+/// if the symbol is not found.
+pub fn print_symbol(module: &Module, name: &str, flags: &PrintingFlags) -> Option<String> {
+    let table = SymbolTable::new(module.as_operation()).ok()?;
+
+    // Detached operations are printed as top-level ones with their aliases.
+    Some(table.lookup(name)?.to_owned().to_string_with_flags(flags))
+}
+
+/// Appends operations computing a linear index from indices and strides to
+/// a block.
+///
+/// Indices and strides are values of the same integer or `index` type. The
+/// numbers of indices and strides must be the same.
+pub fn linearize_index<'a>(
+    context: &Context,
+    block: &BlockRef<'a>,
+    indices: &[Value],
+    strides: &[Value],
+    location: Location,
+) -> Result<Value<'a>, Error> {
+    if indices.len() != strides.len() {
+        return Err(Error::IndexCount(strides.len(), indices.len()));
+    }
+
+    Ok(indices
+        .iter()
+        .zip(strides)
+        .map(|(&index, &stride)| {
+            append_binary_operation(block, "arith.muli", index, stride, location)
+        })
+        .reduce(|sum, product| append_binary_operation(block, "arith.addi", sum, product, location))
+        .unwrap_or_else(|| {
+            append_operation_result(
+                block,
+                Builder::new("arith.constant", location)
+                    .add_attributes(&[(
+                        Identifier::new(context, "value"),
+                        Attribute::parse(context, "0 : index").unwrap(),
+                    )])
+                    .add_results(&[Type::index(context)]),
+            )
+        }))
+}
+
+/// Appends operations computing indices from a linear index and strides to a
+/// block.
+///
+/// This is the inverse of [`linearize_index`] for indices within bounds.
+pub fn delinearize_index<'a>(
+    block: &BlockRef<'a>,
+    index: Value,
+    strides: &[Value],
+    location: Location,
+) -> Vec<Value<'a>> {
+    let mut remainder = index;
+    let mut indices = Vec::with_capacity(strides.len());
+
+    for (position, &stride) in strides.iter().enumerate() {
+        indices.push(append_binary_operation(
+            block,
+            "arith.divui",
+            remainder,
+            stride,
+            location,
+        ));
+
+        if position + 1 < strides.len() {
+            remainder = append_binary_operation(block, "arith.remui", remainder, stride, location);
+        }
+    }
+
+    indices
+}
+
+fn append_binary_operation<'a>(
+    block: &BlockRef<'a>,
+    name: &str,
+    lhs: Value,
+    rhs: Value,
+    location: Location,
+) -> Value<'a> {
+    append_operation_result(
+        block,
+        Builder::new(name, location)
+            .add_operands(&[lhs, rhs])
+            .add_results(&[unsafe { Type::from_raw(mlirValueGetType(lhs.to_raw())) }]),
+    )
+}
+
+pub(crate) fn append_operation_result<'a>(block: &BlockRef<'a>, builder: Builder) -> Value<'a> {
+    unsafe {
+        Value::from_raw(mlirOperationGetResult(
+            // Binary operations with explicit result types are always built.
+            block.append_operation(builder.build().unwrap()).to_raw(),
+            0,
+        ))
+    }
+}
+
+// Versions can have suffixes, e.g. `15.0.7git` and `15.0.7-rc1`.
+fn parse_version(version: &str) -> (usize, usize, usize) {
+    let mut components = version.split('.').map(|component| {
+        component
+            .chars()
+            .take_while(|character| character.is_ascii_digit())
+            .collect::<String>()
+            .parse()
+            .unwrap_or_default()
+    });
+
+    (
+        components.next().unwrap_or_default(),
+        components.next().unwrap_or_default(),
+        components.next().unwrap_or_default(),
+    )
 }
 
 pub(crate) unsafe extern "C" fn print_callback(string: MlirStringRef, data: *mut c_void) {
@@ -70,6 +375,24 @@ pub(crate) unsafe extern "C" fn print_callback(string: MlirStringRef, data: *mut
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        diagnostic,
+        ir::{Block, Module},
+    };
+
+    fn operation_names(block: &BlockRef) -> Vec<String> {
+        block
+            .operations()
+            .map(|operation| {
+                operation
+                    .name()
+                    .as_string_ref()
+                    .as_str()
+                    .unwrap()
+                    .to_owned()
+            })
+            .collect()
+    }
 
     #[test]
     fn register_dialects() {
@@ -118,4 +441,303 @@ mod tests {
             register_all_passes();
         }
     }
+
+    #[test]
+    fn with_diagnostics() {
+        let context = Context::new();
+        let location = Location::new(&context, "foo", 1, 2);
+
+        let (result, diagnostics) = super::with_diagnostics(&context, || {
+            location.emit_error("bar");
+            42
+        });
+
+        assert_eq!(result, 42);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity(), diagnostic::Severity::Error);
+        assert_eq!(diagnostics[0].location(), location);
+        assert_eq!(diagnostics[0].message(), "bar");
+    }
+
+    #[test]
+    fn with_diagnostics_on_parse_error() {
+        let context = Context::new();
+
+        let (module, diagnostics) =
+            super::with_diagnostics(&context, || Module::parse(&context, "module {"));
+
+        assert!(module.is_none());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity(), diagnostic::Severity::Error);
+    }
+
+    #[test]
+    fn with_diagnostics_detaches_handler() {
+        let context = Context::new();
+
+        super::with_diagnostics(&context, || {});
+
+        let (_, diagnostics) = super::with_diagnostics(&context, || {
+            Location::unknown(&context).emit_error("foo");
+        });
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn print_symbol() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0: memref<4xf32, affine_map<(d0) -> (d0 + 1)>>) {
+                return
+            }
+
+            func.func @bar() {
+                return
+            }
+            "#,
+        )
+        .unwrap();
+
+        let source = super::print_symbol(&module, "bar", &PrintingFlags::new()).unwrap();
+
+        assert!(source.contains("func.func @bar()"));
+        assert!(!source.contains("@foo"));
+    }
+
+    #[test]
+    fn print_symbol_with_aliases() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0: memref<4xf32, affine_map<(d0) -> (d0 + 1)>>) {
+                return
+            }
+
+            func.func @bar() {
+                return
+            }
+            "#,
+        )
+        .unwrap();
+
+        let source = super::print_symbol(&module, "foo", &PrintingFlags::new()).unwrap();
+
+        assert!(source.contains("#map = affine_map<(d0) -> (d0 + 1)>"));
+        assert!(source.contains("func.func @foo(%arg0: memref<4xf32, #map>)"));
+        assert!(!source.contains("@bar"));
+    }
+
+    #[test]
+    fn print_symbol_with_flags() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0: memref<4xf32, affine_map<(d0) -> (d0 + 1)>>) {
+                return
+            }
+
+            func.func @bar() {
+                return
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert!(super::print_symbol(
+            &module,
+            "bar",
+            &PrintingFlags::new().print_generic_operation_form()
+        )
+        .unwrap()
+        .contains("\"func.func\"()"));
+    }
+
+    #[test]
+    fn print_symbol_none() {
+        let context = Context::new();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%arg0: memref<4xf32, affine_map<(d0) -> (d0 + 1)>>) {
+                return
+            }
+
+            func.func @bar() {
+                return
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::print_symbol(&module, "baz", &PrintingFlags::new()),
+            None
+        );
+    }
+
+    #[test]
+    fn linearize_index() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::index(&context);
+        let block = Block::new(&[(r#type, location); 4]);
+        let arguments = (0..4)
+            .map(|position| block.argument(position).unwrap().into())
+            .collect::<Vec<Value>>();
+
+        let index =
+            super::linearize_index(&context, &block, &arguments[..2], &arguments[2..], location)
+                .unwrap();
+
+        assert_eq!(index.r#type(), r#type);
+        assert_eq!(
+            operation_names(&block),
+            ["arith.muli", "arith.muli", "arith.addi"]
+        );
+        assert!(block.operations().all(|operation| operation.verify()));
+    }
+
+    #[test]
+    fn linearize_index_without_indices() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[]);
+
+        let index = super::linearize_index(&context, &block, &[], &[], location).unwrap();
+
+        assert_eq!(index.r#type(), Type::index(&context));
+        assert_eq!(operation_names(&block), ["arith.constant"]);
+        assert!(block.operations().all(|operation| operation.verify()));
+    }
+
+    #[test]
+    fn linearize_index_with_extra_stride() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location); 3]);
+        let arguments = (0..3)
+            .map(|position| block.argument(position).unwrap().into())
+            .collect::<Vec<Value>>();
+
+        assert_eq!(
+            super::linearize_index(&context, &block, &arguments[..1], &arguments[1..], location),
+            Err(Error::IndexCount(2, 1))
+        );
+        assert_eq!(block.operation_count(), 0);
+    }
+
+    #[test]
+    fn delinearize_index() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let r#type = Type::index(&context);
+        let block = Block::new(&[(r#type, location); 3]);
+        let index = block.argument(0).unwrap().into();
+        let strides = (1..3)
+            .map(|position| block.argument(position).unwrap().into())
+            .collect::<Vec<Value>>();
+
+        let indices = super::delinearize_index(&block, index, &strides, location);
+
+        assert_eq!(indices.len(), 2);
+        assert_eq!(
+            operation_names(&block),
+            ["arith.divui", "arith.remui", "arith.divui"]
+        );
+        assert!(block.operations().all(|operation| operation.verify()));
+    }
+
+    #[test]
+    fn delinearize_index_without_strides() {
+        let context = Context::new();
+        let location = Location::unknown(&context);
+        let block = Block::new(&[(Type::index(&context), location)]);
+
+        assert!(
+            super::delinearize_index(&block, block.argument(0).unwrap().into(), &[], location)
+                .is_empty()
+        );
+        assert_eq!(block.operation_count(), 0);
+    }
+
+    #[test]
+    fn escape_plain_string() {
+        assert_eq!(escape_string("foo bar"), "foo bar");
+    }
+
+    #[test]
+    fn escape_special_characters() {
+        assert_eq!(escape_string("\"\\\n"), "\\22\\\\\\0A");
+    }
+
+    #[test]
+    fn escape_non_ascii_characters() {
+        assert_eq!(escape_string("\u{e9}"), "\\C3\\A9");
+    }
+
+    #[test]
+    fn unescape_escaped_string() {
+        let string = "\"foo\"\\\n\u{e9}";
+
+        assert_eq!(
+            unescape_string(&escape_string(string)).as_deref(),
+            Some(string)
+        );
+    }
+
+    #[test]
+    fn unescape_invalid_string() {
+        assert_eq!(unescape_string("\\"), None);
+        assert_eq!(unescape_string("\\zz"), None);
+    }
+
+    #[test]
+    fn parse_escaped_string() {
+        let context = Context::new();
+        let string = "\"foo\"\\\n\u{e9}";
+
+        assert_eq!(
+            Attribute::parse(&context, &format!("\"{}\"", escape_string(string)))
+                .unwrap()
+                .to_string(),
+            format!("\"{}\"", escape_string(string))
+        );
+    }
+
+    #[test]
+    fn check_bare_identifier() {
+        assert!(is_bare_identifier("foo"));
+        assert!(is_bare_identifier("_foo.bar$baz0"));
+        assert!(!is_bare_identifier(""));
+        assert!(!is_bare_identifier("0foo"));
+        assert!(!is_bare_identifier("foo-bar"));
+        assert!(!is_bare_identifier("foo bar"));
+    }
+
+    #[test]
+    fn mlir_version() {
+        assert_eq!(super::mlir_version().0, 15);
+    }
+
+    #[test]
+    fn parse_version() {
+        assert_eq!(super::parse_version("15.0.7"), (15, 0, 7));
+        assert_eq!(super::parse_version("15.0.7git"), (15, 0, 7));
+        assert_eq!(super::parse_version("16.0.0-rc1"), (16, 0, 0));
+        assert_eq!(super::parse_version("15"), (15, 0, 0));
+    }
+
+    #[test]
+    fn require_mlir_version() {
+        assert_eq!(super::require_mlir_version(15, 0), Ok(()));
+        assert!(matches!(
+            super::require_mlir_version(16, 0),
+            Err(Error::MlirVersionMismatch(required, _)) if required == "16.0"
+        ));
+    }
 }